@@ -0,0 +1,207 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    spanned::Spanned, FnArg, GenericArgument, Ident, ItemFn, Pat, PathArguments, ReturnType, Type,
+};
+
+use crate::lower::Lowering;
+
+/// Whether a parameter is known to the verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+struct Param {
+    name: Ident,
+    visibility: Visibility,
+}
+
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "#[circuit] does not take any arguments",
+        ));
+    }
+    let func: ItemFn = syn::parse2(item)?;
+    let sig = &func.sig;
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "#[circuit] functions cannot be generic",
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "#[circuit] functions cannot be async",
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(syn::Error::new_spanned(
+            variadic,
+            "#[circuit] functions cannot be variadic",
+        ));
+    }
+
+    let params = sig
+        .inputs
+        .iter()
+        .map(param)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let has_output = match &sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, ty) => match visibility(ty)? {
+            Visibility::Public => true,
+            Visibility::Private => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "circuit outputs are public, use `Public<F>`",
+                ))
+            }
+        },
+    };
+
+    let cs = Ident::new("__cs", Span::mixed_site());
+    let (stmts, result) = Lowering::new(&cs).block(&func.block)?;
+    let output = match (has_output, result) {
+        (true, Some(result)) => {
+            let out = Ident::new("__out", Span::mixed_site());
+            quote! {
+                let #out = #result;
+                #cs.output("out", &#out);
+            }
+        }
+        (true, None) => {
+            return Err(syn::Error::new_spanned(
+                &sig.output,
+                "the circuit body must end with the value of its output",
+            ))
+        }
+        (false, Some(_)) => {
+            return Err(syn::Error::new(
+                func.block.span(),
+                "the circuit body returns a value, add `-> Public<F>` to the signature",
+            ))
+        }
+        (false, None) => quote! {},
+    };
+
+    let vis = &func.vis;
+    let attrs = &func.attrs;
+    let module = &sig.ident;
+    let names: Vec<&Ident> = params.iter().map(|p| &p.name).collect();
+    let labels: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+    let introduce = params.iter().map(|p| {
+        let name = &p.name;
+        let label = name.to_string();
+        match p.visibility {
+            Visibility::Public => quote! { let #name = #cs.input(#label); },
+            Visibility::Private => quote! { let #name = #cs.witness(#label); },
+        }
+    });
+    let public: Vec<&Ident> = params
+        .iter()
+        .filter(|p| p.visibility == Visibility::Public)
+        .map(|p| &p.name)
+        .collect();
+    let witness_values = format_ident!("values", span = Span::mixed_site());
+    let evaluator = format_ident!("evaluator", span = Span::mixed_site());
+
+    Ok(quote! {
+        #(#attrs)*
+        #[allow(non_snake_case, clippy::redundant_clone)]
+        #vis mod #module {
+            /// The public inputs of the circuit, in the order the verifier expects them
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            pub struct PublicInputs {
+                #( pub #public: u128, )*
+            }
+
+            impl PublicInputs {
+                pub fn to_vec(&self) -> Vec<::ghayb::FieldElement> {
+                    vec![ #( ::ghayb::FieldElement::new(self.#public, ::ghayb::PRIME) ),* ]
+                }
+            }
+
+            /// The body of the circuit, for any interpreter
+            pub fn build<S: ::ghayb::CircuitSym>(#cs: &mut S) {
+                #(#introduce)*
+                #(#stmts)*
+                #output
+            }
+
+            pub fn circuit() -> ::ghayb::Circuit {
+                let mut #cs = ::ghayb::CircuitBuilder::new();
+                build(&mut #cs);
+                #cs.build()
+            }
+
+            /// Every parameter by name, public and private
+            pub fn witness(#( #names: u128 ),*) -> ::ghayb::Witness {
+                let mut #witness_values = ::std::collections::HashMap::new();
+                #(
+                    #witness_values.insert(
+                        #labels.to_string(),
+                        ::ghayb::FieldElement::new(#names, ::ghayb::PRIME),
+                    );
+                )*
+                ::ghayb::Witness::new(#witness_values)
+            }
+
+            /// Runs the body directly on the given values
+            pub fn evaluate(#( #names: u128 ),*) -> ::ghayb::Evaluator {
+                let mut #evaluator = ::ghayb::Evaluator::new(&witness(#( #names ),*));
+                build(&mut #evaluator);
+                #evaluator
+            }
+        }
+    })
+}
+
+fn param(arg: &FnArg) -> syn::Result<Param> {
+    let FnArg::Typed(arg) = arg else {
+        return Err(syn::Error::new_spanned(
+            arg,
+            "#[circuit] functions cannot take `self`",
+        ));
+    };
+    let Pat::Ident(pat) = &*arg.pat else {
+        return Err(syn::Error::new_spanned(
+            &arg.pat,
+            "circuit parameters must be plain names",
+        ));
+    };
+    if pat.by_ref.is_some() || pat.subpat.is_some() {
+        return Err(syn::Error::new_spanned(
+            pat,
+            "circuit parameters must be plain names",
+        ));
+    }
+    Ok(Param {
+        name: pat.ident.clone(),
+        visibility: visibility(&arg.ty)?,
+    })
+}
+
+/// `Public<F>` or `Private<F>`, by the last path segment
+fn visibility(ty: &Type) -> syn::Result<Visibility> {
+    let error = || syn::Error::new_spanned(ty, "expected `Public<F>` or `Private<F>`");
+    let Type::Path(path) = ty else {
+        return Err(error());
+    };
+    let segment = path.path.segments.last().ok_or_else(error)?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Err(error());
+    };
+    if args.args.len() != 1 || !matches!(args.args[0], GenericArgument::Type(_)) {
+        return Err(error());
+    }
+    match segment.ident.to_string().as_str() {
+        "Public" => Ok(Visibility::Public),
+        "Private" => Ok(Visibility::Private),
+        _ => Err(error()),
+    }
+}
@@ -0,0 +1,36 @@
+//! Procedural macros for `ghayb`.
+//!
+//! `#[circuit]` turns a Rust function over `Public<F>` and `Private<F>` values into
+//! a module with the same name:
+//!
+//! ```ignore
+//!   #[circuit]
+//!   fn affine(x: Public<F>, w: Private<F>) -> Public<F> {
+//!       (w + x) * x
+//!   }
+//!
+//!   let c = affine::circuit();
+//!   let proof = prove!(c, affine::witness(3, 4));
+//!   assert!(Verifier::verify(&c, &affine::PublicInputs { x: 3 }.to_vec(), &proof));
+//! ```
+//!
+//! The module contains:
+//! - `build`, the body written against `ghayb::CircuitSym`, so any interpreter can run it
+//! - `circuit()`, the `Circuit` built from it, with the return value as output `out`
+//! - `witness(..)`, taking every parameter in order and returning a `Witness`
+//! - `evaluate(..)`, running the body directly with `ghayb::Evaluator`
+//! - `PublicInputs`, a struct with one field per public parameter
+
+use proc_macro::TokenStream;
+
+mod expand;
+mod lower;
+#[cfg(test)]
+mod tests;
+
+#[proc_macro_attribute]
+pub fn circuit(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
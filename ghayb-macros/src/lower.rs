@@ -0,0 +1,176 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{punctuated::Punctuated, BinOp, Block, Expr, Ident, Lit, Macro, Pat, Stmt, Token, UnOp};
+
+const UNSUPPORTED: &str = "unsupported expression in #[circuit]: expected `+`, `-`, `*`, \
+     integer literals, variables, blocks, `if`/`else` or `assert_eq!`";
+
+/// Rewrites the body of a `#[circuit]` function into calls on a `CircuitSym`
+pub struct Lowering<'a> {
+    cs: &'a Ident,
+}
+
+impl<'a> Lowering<'a> {
+    pub fn new(cs: &'a Ident) -> Self {
+        Self { cs }
+    }
+
+    /// The statements of the block and, if it has one, its final value
+    pub fn block(&self, block: &Block) -> syn::Result<(Vec<TokenStream>, Option<TokenStream>)> {
+        let mut stmts = Vec::new();
+        let mut result = None;
+        for (i, stmt) in block.stmts.iter().enumerate() {
+            let last = i + 1 == block.stmts.len();
+            match stmt {
+                Stmt::Local(local) => {
+                    let Pat::Ident(pat) = &local.pat else {
+                        return Err(syn::Error::new_spanned(
+                            &local.pat,
+                            "`let` in #[circuit] must bind a plain name",
+                        ));
+                    };
+                    let Some(init) = &local.init else {
+                        return Err(syn::Error::new_spanned(
+                            local,
+                            "`let` in #[circuit] needs a value",
+                        ));
+                    };
+                    if let Some((_, diverge)) = &init.diverge {
+                        return Err(syn::Error::new_spanned(diverge, UNSUPPORTED));
+                    }
+                    let name = &pat.ident;
+                    let value = self.expr(&init.expr)?;
+                    stmts.push(quote! { let #name = #value; });
+                }
+                Stmt::Macro(stmt) => stmts.push(self.assertion(&stmt.mac)?),
+                Stmt::Expr(Expr::Macro(expr), _) => stmts.push(self.assertion(&expr.mac)?),
+                Stmt::Expr(expr, None) if last => result = Some(self.expr(expr)?),
+                Stmt::Expr(expr, _) => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "only `assert_eq!` can be used as a statement in #[circuit]",
+                    ))
+                }
+                Stmt::Item(item) => {
+                    return Err(syn::Error::new_spanned(
+                        item,
+                        "items cannot be declared inside #[circuit]",
+                    ))
+                }
+            }
+        }
+        Ok((stmts, result))
+    }
+
+    fn expr(&self, expr: &Expr) -> syn::Result<TokenStream> {
+        let cs = self.cs;
+        let (l, r) = (
+            Ident::new("__l", Span::mixed_site()),
+            Ident::new("__r", Span::mixed_site()),
+        );
+        match expr {
+            Expr::Paren(e) => self.expr(&e.expr),
+            Expr::Group(e) => self.expr(&e.expr),
+            Expr::Path(path) => match path.path.get_ident() {
+                Some(name) if path.qself.is_none() => Ok(quote! { #name.clone() }),
+                _ => Err(syn::Error::new_spanned(path, UNSUPPORTED)),
+            },
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => {
+                    let value: u128 = int.base10_parse()?;
+                    Ok(quote! { #cs.constant(#value) })
+                }
+                other => Err(syn::Error::new_spanned(
+                    other,
+                    "only integer literals can be used in #[circuit]",
+                )),
+            },
+            Expr::Binary(bin) => {
+                let method = match bin.op {
+                    BinOp::Add(_) => quote! { add },
+                    BinOp::Sub(_) => quote! { sub },
+                    BinOp::Mul(_) => quote! { mul },
+                    op => {
+                        return Err(syn::Error::new_spanned(
+                            op,
+                            "only `+`, `-` and `*` are supported in #[circuit]",
+                        ))
+                    }
+                };
+                let left = self.expr(&bin.left)?;
+                let right = self.expr(&bin.right)?;
+                Ok(quote! {{
+                    let #l = #left;
+                    let #r = #right;
+                    #cs.#method(&#l, &#r)
+                }})
+            }
+            Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+                let value = self.expr(&unary.expr)?;
+                Ok(quote! {{
+                    let #r = #value;
+                    let #l = #cs.constant(0);
+                    #cs.sub(&#l, &#r)
+                }})
+            }
+            Expr::Block(block) if block.label.is_none() => {
+                let (stmts, result) = self.block(&block.block)?;
+                let result = result.ok_or_else(|| {
+                    syn::Error::new_spanned(block, "this block needs a final value")
+                })?;
+                Ok(quote! {{ #(#stmts)* #result }})
+            }
+            Expr::If(expr) => {
+                let Some((_, otherwise)) = &expr.else_branch else {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "`if` in #[circuit] needs an `else` branch",
+                    ));
+                };
+                let cond = self.expr(&expr.cond)?;
+                let (stmts, then) = self.block(&expr.then_branch)?;
+                let then = then.ok_or_else(|| {
+                    syn::Error::new_spanned(&expr.then_branch, "this block needs a final value")
+                })?;
+                let otherwise = self.expr(otherwise)?;
+                let c = Ident::new("__c", Span::mixed_site());
+                Ok(quote! {{
+                    let #c = #cond;
+                    let #l = { #(#stmts)* #then };
+                    let #r = #otherwise;
+                    #cs.select(&#c, &#l, &#r)
+                }})
+            }
+            other => Err(syn::Error::new_spanned(other, UNSUPPORTED)),
+        }
+    }
+
+    /// `assert_eq!(a, b)`
+    fn assertion(&self, mac: &Macro) -> syn::Result<TokenStream> {
+        if !mac.path.is_ident("assert_eq") {
+            return Err(syn::Error::new_spanned(
+                &mac.path,
+                "only `assert_eq!` can be used in #[circuit]",
+            ));
+        }
+        let args = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+        if args.len() != 2 {
+            return Err(syn::Error::new_spanned(
+                mac,
+                "`assert_eq!` in #[circuit] takes exactly two values",
+            ));
+        }
+        let cs = self.cs;
+        let left = self.expr(&args[0])?;
+        let right = self.expr(&args[1])?;
+        let (l, r) = (
+            Ident::new("__l", Span::mixed_site()),
+            Ident::new("__r", Span::mixed_site()),
+        );
+        Ok(quote! {{
+            let #l = #left;
+            let #r = #right;
+            #cs.assert_eq(&#l, &#r);
+        }})
+    }
+}
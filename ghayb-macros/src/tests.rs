@@ -0,0 +1,53 @@
+use quote::quote;
+
+use crate::expand::expand;
+
+fn error(item: proc_macro2::TokenStream) -> String {
+    expand(quote! {}, item)
+        .expect_err("expected the macro to reject the input")
+        .to_string()
+}
+
+#[test]
+fn expands_supported_body() {
+    let item = quote! {
+        fn affine(x: Public<F>, w: Private<F>) -> Public<F> {
+            let y = if w { x * 2 } else { -x };
+            assert_eq!(w * w, w);
+            (y + x) * x
+        }
+    };
+    assert!(expand(quote! {}, item).is_ok());
+}
+
+#[test]
+fn rejects_unsupported_operator() {
+    let message = error(quote! {
+        fn f(x: Public<F>) -> Public<F> { x / 2 }
+    });
+    assert!(message.contains("only `+`, `-` and `*`"));
+}
+
+#[test]
+fn rejects_unknown_parameter_type() {
+    let message = error(quote! {
+        fn f(x: u64) -> Public<F> { x }
+    });
+    assert_eq!(message, "expected `Public<F>` or `Private<F>`");
+}
+
+#[test]
+fn rejects_private_output() {
+    let message = error(quote! {
+        fn f(x: Public<F>) -> Private<F> { x }
+    });
+    assert!(message.contains("circuit outputs are public"));
+}
+
+#[test]
+fn rejects_method_calls() {
+    let message = error(quote! {
+        fn f(x: Public<F>) -> Public<F> { x.pow(3) }
+    });
+    assert!(message.starts_with("unsupported expression"));
+}
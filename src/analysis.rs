@@ -0,0 +1,276 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    field_element::FieldElement,
+    signal::Signal,
+    witness::Witness,
+    witness_generator::WitnessGenerator,
+    PRIME,
+};
+
+/// Why a signal is considered under-constrained
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnderconstrainedKind {
+    /// A witness whose value flows neither into an output nor into an equality check
+    Unused,
+    /// A witness that is only ever compared against other free witnesses,
+    /// so any value works as long as both sides agree
+    OnlyInEquality,
+    /// A declared output that no constraint assigns
+    UnassignedOutput,
+    /// An output whose value does not depend on any public input
+    IndependentOfInputs,
+}
+
+/// A signal whose value is not determined by the constraints of a circuit
+#[derive(Clone, Debug)]
+pub struct UnderconstrainedSignal {
+    pub signal: Signal,
+    pub kind: UnderconstrainedKind,
+    /// Indices into `circuit.constraints` of the constraints mentioning the signal
+    pub constraints: Vec<usize>,
+}
+
+impl Circuit {
+    /// Static analysis reporting witness and output signals that the constraints
+    /// do not pin down. An empty result does not prove the circuit sound.
+    pub fn underconstrained_signals(&self) -> Vec<UnderconstrainedSignal> {
+        let mut findings = Vec::new();
+
+        let produced = produced_names(&self.constraints);
+        let reaches_output = reaching_outputs(self);
+        let reaches_check = reaching_checks(&self.constraints);
+
+        // every witness, in order of first appearance
+        let mut witnesses: Vec<&Signal> = Vec::new();
+        for constraint in &self.constraints {
+            for sig in constraint
+                .operands()
+                .into_iter()
+                .chain([&constraint.output])
+            {
+                if let Signal::Witness(name) = sig {
+                    if !witnesses.iter().any(|w| w.name() == Some(name)) {
+                        witnesses.push(sig);
+                    }
+                }
+            }
+        }
+
+        for sig in witnesses {
+            let Some(name) = sig.name() else {
+                continue;
+            };
+            if reaches_output.contains(name) {
+                continue;
+            }
+            let uses = mentions(&self.constraints, name);
+            // a constraint without a named output checks its result against the
+            // output, that is an assertion
+            if uses
+                .iter()
+                .any(|&i| self.constraints[i].output.name().is_none())
+            {
+                continue;
+            }
+            let eq_uses: Vec<&Constraint> = uses
+                .iter()
+                .map(|&i| &self.constraints[i])
+                .filter(|c| matches!(c.operation, Operation::Eq) && c.output.name() != Some(name))
+                .collect();
+
+            let kind = if eq_uses.is_empty() {
+                // checked through the intermediates it is computed into
+                if reaches_check.contains(name) {
+                    continue;
+                }
+                UnderconstrainedKind::Unused
+            } else if eq_uses.len() == uses.len()
+                && eq_uses.iter().all(|c| {
+                    let other = if c.left.name() == Some(name) {
+                        &c.right
+                    } else {
+                        &c.left
+                    };
+                    match other {
+                        Signal::Witness(other) => {
+                            !produced.contains(other.as_str())
+                                && !reaches_output.contains(other.as_str())
+                        }
+                        _ => false,
+                    }
+                })
+            {
+                UnderconstrainedKind::OnlyInEquality
+            } else {
+                // compared against something the verifier knows, that is an assertion
+                continue;
+            };
+
+            findings.push(UnderconstrainedSignal {
+                signal: sig.clone(),
+                kind,
+                constraints: uses,
+            });
+        }
+
+        let depends_on_input = input_dependence(&self.constraints);
+        for out in &self.outputs {
+            let Some(name) = out.name() else {
+                continue;
+            };
+            let kind = if !produced.contains(name) {
+                UnderconstrainedKind::UnassignedOutput
+            } else if !depends_on_input.contains(name) {
+                UnderconstrainedKind::IndependentOfInputs
+            } else {
+                continue;
+            };
+            findings.push(UnderconstrainedSignal {
+                signal: out.clone(),
+                kind,
+                constraints: mentions(&self.constraints, name),
+            });
+        }
+
+        findings
+    }
+
+    /// Randomized check: keeps the public inputs of `witness` fixed and tries `trials`
+    /// random assignments of the free private signals. Returns a different witness
+    /// that satisfies every constraint and yields the same outputs, if one is found.
+    pub fn find_alternative_witness(&self, witness: &Witness, trials: usize) -> Option<Witness> {
+        let expected = WitnessGenerator::new(self).complete(witness).ok()?.values;
+
+        let produced = produced_names(&self.constraints);
+        let mut free: Vec<String> = Vec::new();
+        for constraint in &self.constraints {
+            for sig in constraint.operands() {
+                if let Signal::Witness(name) = sig {
+                    if !produced.contains(name.as_str()) && !free.iter().any(|f| name == f.as_str())
+                    {
+                        free.push(name.to_string());
+                    }
+                }
+            }
+        }
+        if free.is_empty() {
+            return None;
+        }
+
+        let mut rng = rand::rng();
+        for _ in 0..trials {
+            let mut candidate = witness.values.clone();
+            for name in &free {
+                let value = FieldElement::new(rng.random_range(0..PRIME), PRIME);
+                candidate.insert(name.clone(), value);
+            }
+            if free.iter().all(|name| {
+                witness.values.get(name).map(|v| v.value) == Some(candidate[name].value)
+            }) {
+                continue;
+            }
+
+            let Ok(values) = WitnessGenerator::new(self).complete(&Witness::new(candidate.clone()))
+            else {
+                continue;
+            };
+            let values = values.values;
+            let same_outputs = self.outputs.iter().filter_map(Signal::name).all(|out| {
+                match (values.get(out), expected.get(out)) {
+                    (Some(a), Some(b)) => a.equals(b),
+                    _ => false,
+                }
+            });
+            if same_outputs {
+                return Some(Witness::new(candidate));
+            }
+        }
+        None
+    }
+}
+
+/// Names assigned by some constraint
+fn produced_names(constraints: &[Constraint]) -> HashSet<&str> {
+    constraints
+        .iter()
+        .filter(|c| !matches!(c.output, Signal::Input(_)))
+        .filter_map(|c| c.output.name())
+        .collect()
+}
+
+/// Indices of the constraints that mention `name` in any position
+fn mentions(constraints: &[Constraint], name: &str) -> Vec<usize> {
+    constraints
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| {
+            c.operands()
+                .into_iter()
+                .chain([&c.output])
+                .any(|s| s.name() == Some(name))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Names whose value flows into a declared output
+fn reaching_outputs(circuit: &Circuit) -> HashSet<&str> {
+    let outputs = circuit.outputs.iter().filter_map(Signal::name).collect();
+    flowing_into(&circuit.constraints, outputs)
+}
+
+/// Names whose value flows into an equality check or an assertion
+fn reaching_checks(constraints: &[Constraint]) -> HashSet<&str> {
+    let checked = constraints
+        .iter()
+        .filter(|c| matches!(c.operation, Operation::Eq) || c.output.name().is_none())
+        .flat_map(|c| c.operands().into_iter().filter_map(Signal::name))
+        .collect();
+    flowing_into(constraints, checked)
+}
+
+/// `reached` and every name some constraint computes them from, transitively
+fn flowing_into<'a>(
+    constraints: &'a [Constraint],
+    mut reached: HashSet<&'a str>,
+) -> HashSet<&'a str> {
+    loop {
+        let before = reached.len();
+        for constraint in constraints {
+            let Some(output) = constraint.output.name() else {
+                continue;
+            };
+            if reached.contains(output) {
+                reached.extend(constraint.operands().into_iter().filter_map(Signal::name));
+            }
+        }
+        if reached.len() == before {
+            return reached;
+        }
+    }
+}
+
+/// Names whose value depends on at least one public input
+fn input_dependence(constraints: &[Constraint]) -> HashSet<&str> {
+    let mut dependent: HashSet<&str> = HashSet::new();
+    loop {
+        let before = dependent.len();
+        for constraint in constraints {
+            let depends = constraint.operands().into_iter().any(|s| match s {
+                Signal::Input(_) => true,
+                other => other.name().is_some_and(|n| dependent.contains(n)),
+            });
+            if let (true, Some(output)) = (depends, constraint.output.name()) {
+                dependent.insert(output);
+            }
+        }
+        if dependent.len() == before {
+            return dependent;
+        }
+    }
+}
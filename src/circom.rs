@@ -0,0 +1,574 @@
+//! Reading and writing circom's iden3 binary formats: `.r1cs` constraint systems
+//! and `.wtns` witnesses, plus the JSON that snarkjs exports them as.
+//!
+//! Wire 0 is the constant 1, followed by the public outputs, the public inputs,
+//! the private inputs and the internal wires. Wire `i` becomes the signal `w<i>`:
+//! public wires are circuit inputs, in the order snarkjs lists public signals,
+//! and every other wire is a private witness.
+//!
+//! Each R1CS row `A * B = C` becomes a chain of ghayb constraints: every linear
+//! combination is summed up term by term (`Mul` by the coefficient, then `Add`),
+//! and the product of the first two is checked against the third with `Eq`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    field_element::FieldElement,
+    signal::Signal,
+    witness::Witness,
+    PRIME,
+};
+
+const SECTION_HEADER: u32 = 1;
+const SECTION_CONSTRAINTS: u32 = 2;
+const SECTION_WIRE_LABELS: u32 = 3;
+const SECTION_CUSTOM_GATES: u32 = 4;
+const SECTION_WITNESS: u32 = 2;
+
+/// Field elements are written 32 bytes wide, like circom does for its default curve
+const N8: usize = 32;
+
+/// `sum(coefficient * wire)`
+pub type LinearTerms = Vec<(usize, FieldElement)>;
+
+/// One row of a rank-1 constraint system: `a * b = c`
+#[derive(Clone, Debug)]
+pub struct R1csConstraint {
+    pub a: LinearTerms,
+    pub b: LinearTerms,
+    pub c: LinearTerms,
+}
+
+/// The contents of a `.r1cs` file
+#[derive(Clone, Debug)]
+pub struct R1cs {
+    pub num_wires: usize,
+    pub num_public_outputs: usize,
+    pub num_public_inputs: usize,
+    pub num_private_inputs: usize,
+    pub num_labels: u64,
+    pub constraints: Vec<R1csConstraint>,
+    /// The label of every wire, if the file has them
+    pub wire_labels: Vec<u64>,
+}
+
+/// Why a circom file could not be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircomError {
+    BadMagic {
+        expected: &'static str,
+    },
+    UnsupportedVersion(u32),
+    UnexpectedEnd,
+    MissingSection(u32),
+    DuplicateSection(u32),
+    /// The file's field is larger than `u128`
+    PrimeTooLarge,
+    PrimeMismatch {
+        expected: u128,
+        got: u128,
+    },
+    NonCanonicalElement,
+    WireOutOfRange {
+        wire: usize,
+        wires: usize,
+    },
+    /// PLONK custom gates have no R1CS meaning
+    CustomGates,
+    /// The counts in the header don't add up, or a section is the wrong size
+    Malformed(String),
+    /// A `.wtns` whose first value, the constant wire, is not 1
+    FirstWireNotOne,
+    /// Wire values for a constraint system with a different number of wires
+    WireCount {
+        expected: usize,
+        got: usize,
+    },
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircomError::BadMagic { expected } => write!(f, "not a `{}` file", expected),
+            CircomError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            CircomError::UnexpectedEnd => write!(f, "unexpected end of file"),
+            CircomError::MissingSection(s) => write!(f, "missing section {}", s),
+            CircomError::DuplicateSection(s) => write!(f, "section {} appears twice", s),
+            CircomError::PrimeTooLarge => write!(f, "the field does not fit in 128 bits"),
+            CircomError::PrimeMismatch { expected, got } => write!(
+                f,
+                "the file uses the field of size {}, ghayb uses {}",
+                got, expected
+            ),
+            CircomError::NonCanonicalElement => write!(f, "field element is not reduced"),
+            CircomError::WireOutOfRange { wire, wires } => {
+                write!(f, "wire {} is out of range for {} wires", wire, wires)
+            }
+            CircomError::CustomGates => write!(f, "custom gates are not supported"),
+            CircomError::Malformed(message) => write!(f, "malformed file: {}", message),
+            CircomError::FirstWireNotOne => write!(f, "the constant wire is not 1"),
+            CircomError::WireCount { expected, got } => {
+                write!(f, "expected values for {} wires, got {}", expected, got)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircomError {}
+
+impl R1cs {
+    /// Reads a `.r1cs` file
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircomError> {
+        let sections = read_sections(bytes, "r1cs", &[1])?;
+        if sections.contains_key(&SECTION_CUSTOM_GATES) {
+            return Err(CircomError::CustomGates);
+        }
+
+        let mut header = Reader::new(section(&sections, SECTION_HEADER)?);
+        let n8 = header.field()?;
+        let num_wires = header.u32()?;
+        let num_public_outputs = header.u32()?;
+        let num_public_inputs = header.u32()?;
+        let num_private_inputs = header.u32()?;
+        let num_labels = header.u64()?;
+        let num_constraints = header.u32()?;
+        header.finish()?;
+        if 1 + num_public_outputs + num_public_inputs + num_private_inputs > num_wires {
+            return Err(CircomError::Malformed(
+                "more inputs and outputs than wires".to_string(),
+            ));
+        }
+
+        let mut body = Reader::new(section(&sections, SECTION_CONSTRAINTS)?);
+        let mut constraints = Vec::with_capacity(num_constraints.min(1 << 16));
+        for _ in 0..num_constraints {
+            constraints.push(R1csConstraint {
+                a: body.linear_terms(n8, num_wires)?,
+                b: body.linear_terms(n8, num_wires)?,
+                c: body.linear_terms(n8, num_wires)?,
+            });
+        }
+        body.finish()?;
+
+        let wire_labels = match sections.get(&SECTION_WIRE_LABELS) {
+            Some(labels) => {
+                let mut labels = Reader::new(labels);
+                let wire_labels = (0..num_wires)
+                    .map(|_| labels.u64())
+                    .collect::<Result<_, _>>()?;
+                labels.finish()?;
+                wire_labels
+            }
+            None => Vec::new(),
+        };
+
+        Ok(R1cs {
+            num_wires,
+            num_public_outputs,
+            num_public_inputs,
+            num_private_inputs,
+            num_labels,
+            constraints,
+            wire_labels,
+        })
+    }
+
+    /// Public outputs and inputs, in that order
+    pub fn num_public(&self) -> usize {
+        self.num_public_outputs + self.num_public_inputs
+    }
+
+    /// The signal standing for `wire`
+    pub fn wire_signal(&self, wire: usize) -> Signal {
+        match wire {
+            0 => Signal::Constant(1),
+            w if w <= self.num_public() => Signal::Input(format!("w{}", w).into()),
+            w => Signal::Witness(format!("w{}", w).into()),
+        }
+    }
+
+    pub fn to_circuit(&self) -> Circuit {
+        let inputs = (1..=self.num_public())
+            .map(|w| self.wire_signal(w))
+            .collect();
+        let mut constraints = Vec::new();
+        for (k, row) in self.constraints.iter().enumerate() {
+            let prefix = format!("_r{}", k);
+            let a = self.lower(&row.a, &format!("{}.a", prefix), &mut constraints);
+            let b = self.lower(&row.b, &format!("{}.b", prefix), &mut constraints);
+            let c = self.lower(&row.c, &format!("{}.c", prefix), &mut constraints);
+            let product = Signal::Witness(format!("{}.ab", prefix).into());
+            constraints.push(Constraint {
+                left: a,
+                right: b,
+                output: product.clone(),
+                operation: Operation::Mul,
+            });
+            constraints.push(Constraint {
+                left: product,
+                right: c,
+                output: Signal::Witness(format!("{}.eq", prefix).into()),
+                operation: Operation::Eq,
+            });
+        }
+        Circuit::new(inputs, Vec::new(), constraints)
+    }
+
+    /// A witness assigning every wire but the constant one, e.g. read by `read_wtns`
+    pub fn witness(&self, wires: &[FieldElement]) -> Witness {
+        Witness::new(
+            wires
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(w, value)| (format!("w{}", w), value.clone()))
+                .collect(),
+        )
+    }
+
+    /// What the verifier needs: the public outputs, then the public inputs.
+    /// `wires` must hold a value for every wire, e.g. from a matching `.wtns`.
+    pub fn public_signals(&self, wires: &[FieldElement]) -> Result<Vec<FieldElement>, CircomError> {
+        if wires.len() != self.num_wires {
+            return Err(CircomError::WireCount {
+                expected: self.num_wires,
+                got: wires.len(),
+            });
+        }
+        wires
+            .get(1..=self.num_public())
+            .map(<[FieldElement]>::to_vec)
+            .ok_or_else(|| CircomError::Malformed("more public wires than wires".to_string()))
+    }
+
+    /// Whether the wire values satisfy every row
+    pub fn is_satisfied(&self, wires: &[FieldElement]) -> bool {
+        let eval = |terms: &LinearTerms| {
+            terms
+                .iter()
+                .fold(FieldElement::new(0, PRIME), |acc, (w, c)| {
+                    acc.add(&wires[*w].mul(c))
+                })
+        };
+        wires.len() == self.num_wires
+            && self
+                .constraints
+                .iter()
+                .all(|row| eval(&row.a).mul(&eval(&row.b)).equals(&eval(&row.c)))
+    }
+
+    /// Writes a `.r1cs` file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut header = Writer::default();
+        header.field();
+        header.u32(self.num_wires);
+        header.u32(self.num_public_outputs);
+        header.u32(self.num_public_inputs);
+        header.u32(self.num_private_inputs);
+        header.bytes.extend(self.num_labels.to_le_bytes());
+        header.u32(self.constraints.len());
+
+        let mut body = Writer::default();
+        for row in &self.constraints {
+            for terms in [&row.a, &row.b, &row.c] {
+                body.u32(terms.len());
+                for (wire, coeff) in terms {
+                    body.u32(*wire);
+                    body.number(coeff.value);
+                }
+            }
+        }
+
+        let mut sections = vec![(SECTION_HEADER, header), (SECTION_CONSTRAINTS, body)];
+        if !self.wire_labels.is_empty() {
+            let mut labels = Writer::default();
+            for label in &self.wire_labels {
+                labels.bytes.extend(label.to_le_bytes());
+            }
+            sections.push((SECTION_WIRE_LABELS, labels));
+        }
+        write_sections("r1cs", 1, sections)
+    }
+
+    /// The layout of `snarkjs r1cs export json`, with decimal strings for numbers
+    /// that may not fit a JavaScript number
+    pub fn to_json(&self) -> String {
+        let terms = |terms: &LinearTerms| {
+            let entries: Vec<String> = terms
+                .iter()
+                .map(|(w, c)| format!("\"{}\": \"{}\"", w, c.value))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        };
+        let constraints: Vec<String> = self
+            .constraints
+            .iter()
+            .map(|row| {
+                format!(
+                    "  [{}, {}, {}]",
+                    terms(&row.a),
+                    terms(&row.b),
+                    terms(&row.c)
+                )
+            })
+            .collect();
+        let map: Vec<String> = self.wire_labels.iter().map(u64::to_string).collect();
+        format!(
+            "{{\n \"n8\": {},\n \"prime\": \"{}\",\n \"nVars\": {},\n \"nOutputs\": {},\n \
+             \"nPubInputs\": {},\n \"nPrvInputs\": {},\n \"nLabels\": {},\n \
+             \"nConstraints\": {},\n \"useCustomGates\": false,\n \"constraints\": [\n{}\n ],\n \
+             \"map\": [{}],\n \"customGates\": [],\n \"customGatesUses\": []\n}}\n",
+            N8,
+            PRIME,
+            self.num_wires,
+            self.num_public_outputs,
+            self.num_public_inputs,
+            self.num_private_inputs,
+            self.num_labels,
+            self.constraints.len(),
+            constraints.join(",\n"),
+            map.join(", ")
+        )
+    }
+
+    /// A signal holding the value of `terms`, adding the constraints that sum it up
+    fn lower(&self, terms: &LinearTerms, name: &str, out: &mut Vec<Constraint>) -> Signal {
+        let mut sum: Option<Signal> = None;
+        for (i, (wire, coeff)) in terms.iter().enumerate() {
+            let term = match (self.wire_signal(*wire), coeff.value) {
+                (Signal::Constant(one), c) => Signal::Constant(one * c),
+                (sig, 1) => sig,
+                (sig, c) => {
+                    let scaled = Signal::Witness(format!("{}{}", name, i).into());
+                    out.push(Constraint {
+                        left: sig,
+                        right: Signal::Constant(c),
+                        output: scaled.clone(),
+                        operation: Operation::Mul,
+                    });
+                    scaled
+                }
+            };
+            sum = Some(match sum {
+                None => term,
+                Some(acc) => {
+                    let total = Signal::Witness(format!("{}{}.sum", name, i).into());
+                    out.push(Constraint {
+                        left: acc,
+                        right: term,
+                        output: total.clone(),
+                        operation: Operation::Add,
+                    });
+                    total
+                }
+            });
+        }
+        sum.unwrap_or(Signal::Constant(0))
+    }
+}
+
+/// Reads a `.wtns` file: the value of every wire, starting with the constant 1
+pub fn read_wtns(bytes: &[u8]) -> Result<Vec<FieldElement>, CircomError> {
+    let sections = read_sections(bytes, "wtns", &[1, 2])?;
+    let mut header = Reader::new(section(&sections, SECTION_HEADER)?);
+    let n8 = header.field()?;
+    let count = header.u32()?;
+    header.finish()?;
+
+    let mut body = Reader::new(section(&sections, SECTION_WITNESS)?);
+    let values = (0..count)
+        .map(|_| body.element(n8))
+        .collect::<Result<Vec<_>, _>>()?;
+    body.finish()?;
+    if values.first().is_some_and(|one| one.value != 1) {
+        return Err(CircomError::FirstWireNotOne);
+    }
+    Ok(values)
+}
+
+/// Writes a `.wtns` file holding the value of every wire, starting with the constant 1
+pub fn write_wtns(wires: &[FieldElement]) -> Vec<u8> {
+    let mut header = Writer::default();
+    header.field();
+    header.u32(wires.len());
+    let mut body = Writer::default();
+    for value in wires {
+        body.number(value.value);
+    }
+    write_sections(
+        "wtns",
+        2,
+        vec![(SECTION_HEADER, header), (SECTION_WITNESS, body)],
+    )
+}
+
+/// The layout of `snarkjs wtns export json`: every wire as a decimal string
+pub fn witness_json(wires: &[FieldElement]) -> String {
+    let values: Vec<String> = wires.iter().map(|v| format!(" \"{}\"", v.value)).collect();
+    format!("[\n{}\n]\n", values.join(",\n"))
+}
+
+/// Magic, version, then every section with its type and length
+fn write_sections(magic: &str, version: u32, sections: Vec<(u32, Writer)>) -> Vec<u8> {
+    let mut out = Writer::default();
+    out.bytes.extend(magic.as_bytes());
+    out.bytes.extend(version.to_le_bytes());
+    out.u32(sections.len());
+    for (kind, section) in sections {
+        out.bytes.extend(kind.to_le_bytes());
+        out.bytes.extend((section.bytes.len() as u64).to_le_bytes());
+        out.bytes.extend(section.bytes);
+    }
+    out.bytes
+}
+
+/// Checks the magic and version, and splits the file into sections by type
+fn read_sections<'a>(
+    bytes: &'a [u8],
+    magic: &'static str,
+    versions: &[u32],
+) -> Result<HashMap<u32, &'a [u8]>, CircomError> {
+    let mut r = Reader::new(bytes);
+    if r.take(4).ok() != Some(magic.as_bytes()) {
+        return Err(CircomError::BadMagic { expected: magic });
+    }
+    let version = r.u32()? as u32;
+    if !versions.contains(&version) {
+        return Err(CircomError::UnsupportedVersion(version));
+    }
+    let count = r.u32()?;
+    let mut sections = HashMap::new();
+    for _ in 0..count {
+        let kind = r.u32()? as u32;
+        let len = usize::try_from(r.u64()?).map_err(|_| CircomError::UnexpectedEnd)?;
+        if sections.insert(kind, r.take(len)?).is_some() {
+            return Err(CircomError::DuplicateSection(kind));
+        }
+    }
+    r.finish()?;
+    Ok(sections)
+}
+
+fn section<'a>(sections: &HashMap<u32, &'a [u8]>, kind: u32) -> Result<&'a [u8], CircomError> {
+    sections
+        .get(&kind)
+        .copied()
+        .ok_or(CircomError::MissingSection(kind))
+}
+
+/// Little-endian reads over a byte slice
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CircomError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(CircomError::UnexpectedEnd)?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<usize, CircomError> {
+        let bytes = self.take(4)?.try_into().expect("took 4 bytes");
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn u64(&mut self) -> Result<u64, CircomError> {
+        let bytes = self.take(8)?.try_into().expect("took 8 bytes");
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// An `n8`-byte little-endian number that must fit in a `u128`
+    fn number(&mut self, n8: usize) -> Result<u128, CircomError> {
+        let bytes = self.take(n8)?;
+        if bytes.iter().skip(16).any(|&b| b != 0) {
+            return Err(CircomError::PrimeTooLarge);
+        }
+        let mut le = [0u8; 16];
+        let len = n8.min(16);
+        le[..len].copy_from_slice(&bytes[..len]);
+        Ok(u128::from_le_bytes(le))
+    }
+
+    /// The field description: the element size `n8` followed by the prime.
+    /// Returns `n8`.
+    fn field(&mut self) -> Result<usize, CircomError> {
+        let n8 = self.u32()?;
+        let prime = self.number(n8)?;
+        if prime != PRIME {
+            return Err(CircomError::PrimeMismatch {
+                expected: PRIME,
+                got: prime,
+            });
+        }
+        Ok(n8)
+    }
+
+    fn element(&mut self, n8: usize) -> Result<FieldElement, CircomError> {
+        match self.number(n8) {
+            Ok(value) if value < PRIME => Ok(FieldElement::new(value, PRIME)),
+            Ok(_) | Err(CircomError::PrimeTooLarge) => Err(CircomError::NonCanonicalElement),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn linear_terms(&mut self, n8: usize, wires: usize) -> Result<LinearTerms, CircomError> {
+        let count = self.u32()?;
+        let mut terms = Vec::with_capacity(count.min(1 << 16));
+        for _ in 0..count {
+            let wire = self.u32()?;
+            if wire >= wires {
+                return Err(CircomError::WireOutOfRange { wire, wires });
+            }
+            terms.push((wire, self.element(n8)?));
+        }
+        Ok(terms)
+    }
+
+    fn finish(&self) -> Result<(), CircomError> {
+        if self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(CircomError::Malformed(format!(
+                "{} unexpected bytes at the end of a section",
+                self.bytes.len() - self.pos
+            )))
+        }
+    }
+}
+
+/// Little-endian writes, the counterpart of `Reader`
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, n: usize) {
+        let n = u32::try_from(n).expect("count does not fit the file format");
+        self.bytes.extend(n.to_le_bytes());
+    }
+
+    fn number(&mut self, n: u128) {
+        self.bytes.extend(n.to_le_bytes());
+        self.bytes.extend([0; N8 - 16]);
+    }
+
+    fn field(&mut self) {
+        self.u32(N8);
+        self.number(PRIME);
+    }
+}
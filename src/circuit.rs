@@ -1,20 +1,131 @@
+use std::collections::HashMap;
+
 use crate::constraint::Constraint;
-use crate::signal::Signal;
+use crate::hint::Hint;
+use crate::signal::{Signal, SignalName};
 
 // a Circuit is a collection of constraints plus input and output signals
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "crate::serialization::RawCircuit")
+)]
 pub struct Circuit {
     pub inputs: Vec<Signal>,
     pub outputs: Vec<Signal>,
     pub constraints: Vec<Constraint>,
+    /// Witness arrays, declared so that constraints indexing them are checked
+    /// like those indexing input and output arrays
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub witnesses: Vec<Signal>,
+    /// How the prover computes values the constraints only check.
+    /// Closures can't be serialized, so a deserialized circuit has none.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hints: Vec<Hint>,
+    /// The source text of constraints written with `circuit!`, by index into
+    /// `constraints`, shown when a constraint fails
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub sources: HashMap<usize, String>,
 }
 
 impl Circuit {
+    /// Constraints indexing a declared array out of bounds are reported by
+    /// witness generation, see `WitnessError::OutOfBounds`
     pub fn new(inputs: Vec<Signal>, outputs: Vec<Signal>, constraints: Vec<Constraint>) -> Self {
         Self {
             inputs,
             outputs,
             constraints,
+            witnesses: Vec::new(),
+            hints: Vec::new(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Declares witness arrays, whose indices are then checked
+    pub fn with_witnesses(mut self, witnesses: Vec<Signal>) -> Self {
+        self.witnesses = witnesses;
+        self
+    }
+
+    /// Attaches a hint used during witness generation
+    pub fn with_hint(mut self, hint: Hint) -> Self {
+        self.hints.push(hint);
+        self
+    }
+
+    /// The shape of every declared array, by array name
+    pub(crate) fn shapes(&self) -> HashMap<String, Vec<usize>> {
+        array_shapes(
+            self.inputs
+                .iter()
+                .chain(&self.outputs)
+                .chain(&self.witnesses),
+        )
+    }
+}
+
+/// Why a circuit read from a file is invalid, if it is: a signal indexing a
+/// declared array out of bounds, or a constraint the `Constraint`
+/// constructors would have rejected
+pub(crate) fn invalid_circuit(circuit: &Circuit) -> Option<String> {
+    let shapes = circuit.shapes();
+    circuit.constraints.iter().find_map(|c| {
+        c.operands()
+            .into_iter()
+            .chain([&c.output])
+            .find_map(|sig| out_of_bounds(&shapes, sig))
+            .or_else(|| c.invalid())
+    })
+}
+
+/// The shape of every array among the declared signals, by array name
+pub(crate) fn array_shapes<'a>(
+    declared: impl IntoIterator<Item = &'a Signal>,
+) -> HashMap<String, Vec<usize>> {
+    let mut shapes: HashMap<String, Vec<usize>> = HashMap::new();
+    for sig in declared {
+        let Some((base, indices)) = sig.array_index() else {
+            continue;
+        };
+        if indices.is_empty() {
+            continue;
+        }
+        let dims = shapes
+            .entry(base.to_string())
+            .or_insert_with(|| vec![0; indices.len()]);
+        for (dim, index) in dims.iter_mut().zip(indices) {
+            *dim = (*dim).max(index + 1);
         }
     }
+    shapes
+}
+
+/// The shape of the array `sig` indexes out of bounds, if it does
+pub(crate) fn exceeded_shape<'a>(
+    shapes: &'a HashMap<String, Vec<usize>>,
+    sig: &Signal,
+) -> Option<&'a [usize]> {
+    let (base, indices) = sig.array_index()?;
+    let dims = shapes.get(base)?;
+    let in_bounds = indices.len() == dims.len() && indices.iter().zip(dims).all(|(i, d)| i < d);
+    (!in_bounds).then_some(dims.as_slice())
+}
+
+/// Why `sig` can't index its array, if it can't
+pub(crate) fn out_of_bounds(shapes: &HashMap<String, Vec<usize>>, sig: &Signal) -> Option<String> {
+    let dims = exceeded_shape(shapes, sig)?;
+    let name = sig.signal_name()?;
+    Some(bounds_message(name, dims))
+}
+
+/// The message for `name` indexing an array of shape `dims` out of bounds
+pub(crate) fn bounds_message(name: &SignalName, dims: &[usize]) -> String {
+    format!(
+        "Signal '{}' is out of bounds for array '{}' of shape {:?}",
+        name,
+        name.base(),
+        dims
+    )
 }
@@ -4,7 +4,9 @@ use crate::field_element::FieldElement;
 
 /// hash-based commitment (hides the actual value behind a hash)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Commitment {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
     pub committed_hash: Vec<u8>,
 }
 
@@ -15,9 +17,9 @@ impl Commitment {
         let blind_bytes = blinding.value.to_le_bytes();
 
         let mut hasher = Sha256::new();
-        hasher.update(&val_bytes);
-        hasher.update(&blind_bytes);
-        hasher.update(&val.prime.to_le_bytes());
+        hasher.update(val_bytes);
+        hasher.update(blind_bytes);
+        hasher.update(val.prime.to_le_bytes());
 
         let result = hasher.finalize();
         Self {
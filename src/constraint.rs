@@ -1,19 +1,241 @@
+use crate::gate::Gate;
+use crate::linear::LinearCombination;
+use crate::lookup::Table;
 use crate::signal::Signal;
+use crate::PRIME;
 
 /// The operations allowed in a constraint
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     Add,
     Mul,
     Sub,
     Eq,
+    /// `output = cond * left + (1 - cond) * right`, with `cond` constrained to 0 or 1
+    Select(Signal),
+    /// `output = options[left]`, with `left` constrained to a valid index.
+    /// `right` is unused and set to `Signal::Constant(0)`.
+    Mux(Vec<Signal>),
+    /// `output = gate(inputs)`, one input per wire of the gate.
+    /// `left` and `right` are unused and set to `Signal::Constant(0)`.
+    Custom(Gate, Vec<Signal>),
+    /// `output = table[inputs]`, the last column of the first row starting with
+    /// the inputs. With an input per column, asserts they are a row instead.
+    /// `left` and `right` are unused and set to `Signal::Constant(0)`.
+    Lookup(Table, Vec<Signal>),
 }
 
-/// a single constraint with an operation
+/// a single constraint with an operation. Any operand and the output may be a
+/// `Signal::Linear`, so a `Mul` has the R1CS shape `(lc) * (lc) = (lc)`. An
+/// output without a name, a constant or a linear combination, is checked
+/// against the result instead of assigned.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constraint {
     pub left: Signal,
     pub right: Signal,
     pub output: Signal,
     pub operation: Operation,
 }
+
+impl Constraint {
+    /// `output = if cond { then } else { otherwise }`
+    pub fn select(cond: Signal, then: Signal, otherwise: Signal, output: Signal) -> Self {
+        Self {
+            left: then,
+            right: otherwise,
+            output,
+            operation: Operation::Select(cond),
+        }
+    }
+
+    /// `output = options[index]`
+    pub fn mux(index: Signal, options: Vec<Signal>, output: Signal) -> Self {
+        Self {
+            left: index,
+            right: Signal::Constant(0),
+            output,
+            operation: Operation::Mux(options),
+        }
+    }
+
+    /// `output = gate(inputs)`, or the identity `gate(inputs) = 0` with the
+    /// output `Signal::Constant(0)`
+    pub fn custom(gate: &Gate, inputs: Vec<Signal>, output: Signal) -> Self {
+        assert_eq!(
+            inputs.len(),
+            gate.wires.len(),
+            "gate `{}` takes {} inputs",
+            gate.name,
+            gate.wires.len()
+        );
+        Self {
+            left: Signal::Constant(0),
+            right: Signal::Constant(0),
+            output,
+            operation: Operation::Custom(gate.clone(), inputs),
+        }
+    }
+
+    /// `output = table[inputs]`, or `inputs ∈ table` with an input per column
+    /// and the output `Signal::Constant(0)`
+    pub fn lookup(table: &Table, inputs: Vec<Signal>, output: Signal) -> Self {
+        assert!(
+            !inputs.is_empty()
+                && inputs.len() + 1 >= table.width()
+                && inputs.len() <= table.width(),
+            "table `{}` takes {} or {} inputs",
+            table.name,
+            table.width() - 1,
+            table.width()
+        );
+        Self {
+            left: Signal::Constant(0),
+            right: Signal::Constant(0),
+            output,
+            operation: Operation::Lookup(table.clone(), inputs),
+        }
+    }
+
+    /// Every signal the constraint reads. Linear combinations count as the
+    /// signals in their terms, including an output combination, which is read
+    /// to check the result.
+    pub fn operands(&self) -> Vec<&Signal> {
+        let mut operands = vec![&self.left, &self.right];
+        match &self.operation {
+            Operation::Select(cond) => operands.push(cond),
+            Operation::Mux(options)
+            | Operation::Custom(_, options)
+            | Operation::Lookup(_, options) => operands.extend(options),
+            _ => {}
+        }
+        if let Signal::Linear(_) = &self.output {
+            operands.push(&self.output);
+        }
+        operands
+            .into_iter()
+            .flat_map(|sig| match sig {
+                Signal::Linear(lc) => lc.terms.iter().map(|(s, _)| s).collect(),
+                sig => vec![sig],
+            })
+            .collect()
+    }
+
+    /// Why the constructors could not have built the constraint, if they
+    /// couldn't, for constraints read from a file
+    pub(crate) fn invalid(&self) -> Option<String> {
+        let mut signals = vec![&self.left, &self.right, &self.output];
+        match &self.operation {
+            Operation::Select(cond) => signals.push(cond),
+            Operation::Mux(options)
+            | Operation::Custom(_, options)
+            | Operation::Lookup(_, options) => signals.extend(options),
+            _ => {}
+        }
+        if let Some(message) = signals.into_iter().find_map(non_canonical) {
+            return Some(message);
+        }
+        match &self.operation {
+            Operation::Custom(gate, inputs) => gate.invalid().or_else(|| {
+                (inputs.len() != gate.wires.len()).then(|| {
+                    format!(
+                        "gate `{}` takes {} inputs, got {}",
+                        gate.name,
+                        gate.wires.len(),
+                        inputs.len()
+                    )
+                })
+            }),
+            Operation::Lookup(table, inputs) => table.invalid().or_else(|| {
+                let arity = !inputs.is_empty()
+                    && inputs.len() + 1 >= table.width()
+                    && inputs.len() <= table.width();
+                (!arity).then(|| {
+                    format!(
+                        "table `{}` takes {} or {} inputs, got {}",
+                        table.name,
+                        table.width() - 1,
+                        table.width(),
+                        inputs.len()
+                    )
+                })
+            }),
+            _ => None,
+        }
+    }
+
+    /// The same constraint with every signal replaced by `f(signal)`, mapping
+    /// the terms of linear combinations one by one
+    pub fn map_signals(&self, f: impl Fn(&Signal) -> Signal) -> Self {
+        let f = |sig: &Signal| match sig {
+            Signal::Linear(lc) => Signal::Linear(
+                lc.terms
+                    .iter()
+                    .map(|(s, coeff)| LinearCombination::from_signal(&f(s)).scale(*coeff))
+                    .fold(LinearCombination::constant(lc.constant), |acc, term| {
+                        acc.add(&term)
+                    }),
+            ),
+            sig => f(sig),
+        };
+        let operation = match &self.operation {
+            Operation::Select(cond) => Operation::Select(f(cond)),
+            Operation::Mux(options) => Operation::Mux(options.iter().map(f).collect()),
+            Operation::Custom(gate, inputs) => {
+                Operation::Custom(gate.clone(), inputs.iter().map(f).collect())
+            }
+            Operation::Lookup(table, inputs) => {
+                Operation::Lookup(table.clone(), inputs.iter().map(f).collect())
+            }
+            other => other.clone(),
+        };
+        Self {
+            left: f(&self.left),
+            right: f(&self.right),
+            output: f(&self.output),
+            operation,
+        }
+    }
+}
+
+/// What `name(a, b, ...)` calls in `constraint!`: a custom `Gate` or a lookup `Table`
+pub trait Callable {
+    fn call(&self, inputs: Vec<Signal>, output: Signal) -> Constraint;
+}
+
+impl Callable for Gate {
+    fn call(&self, inputs: Vec<Signal>, output: Signal) -> Constraint {
+        Constraint::custom(self, inputs, output)
+    }
+}
+
+impl Callable for Table {
+    fn call(&self, inputs: Vec<Signal>, output: Signal) -> Constraint {
+        Constraint::lookup(self, inputs, output)
+    }
+}
+
+/// Why `sig` is not reduced as constants and linear combinations are built, if
+/// it isn't
+fn non_canonical(sig: &Signal) -> Option<String> {
+    let unreduced = |v: u128| format!("{} is not a canonical field element", v);
+    match sig {
+        Signal::Constant(v) if *v >= PRIME => Some(unreduced(*v)),
+        Signal::Linear(lc) => {
+            for (i, (term, coeff)) in lc.terms.iter().enumerate() {
+                let Some(name) = term.name() else {
+                    return Some("a linear combination has an unnamed term".to_string());
+                };
+                if *coeff == 0 || *coeff >= PRIME {
+                    return Some(unreduced(*coeff));
+                }
+                if lc.terms[..i].iter().any(|(s, _)| s.name() == Some(name)) {
+                    return Some(format!("`{}` is repeated in a linear combination", name));
+                }
+            }
+            (lc.constant >= PRIME).then(|| unreduced(lc.constant))
+        }
+        _ => None,
+    }
+}
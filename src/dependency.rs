@@ -0,0 +1,128 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::{circuit::Circuit, constraint::Constraint, signal::Signal};
+
+/// Constraints that (transitively) need each other's outputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyCycle {
+    /// `(constraint index, signal it reads from the next constraint in the cycle)`
+    pub steps: Vec<(usize, String)>,
+}
+
+impl fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dependency cycle between constraints: ")?;
+        for (i, (index, signal)) in self.steps.iter().enumerate() {
+            let next = self.steps[(i + 1) % self.steps.len()].0;
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "#{} needs '{}' from #{}", index, signal, next)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
+impl Circuit {
+    /// Indices into `self.constraints` in an order where every constraint comes after
+    /// the constraints producing its operands. Independent constraints keep their
+    /// declaration order.
+    pub fn constraint_order(&self) -> Result<Vec<usize>, DependencyCycle> {
+        let deps = self.constraint_dependencies();
+
+        let mut remaining: Vec<usize> = deps.iter().map(|d| d.len()).collect();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.constraints.len()];
+        for (i, ds) in deps.iter().enumerate() {
+            for (j, _) in ds {
+                dependents[*j].push(i);
+            }
+        }
+
+        let mut ready: BTreeSet<usize> = (0..self.constraints.len())
+            .filter(|&i| remaining[i] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.constraints.len());
+        while let Some(i) = ready.pop_first() {
+            order.push(i);
+            for &d in &dependents[i] {
+                remaining[d] -= 1;
+                if remaining[d] == 0 {
+                    ready.insert(d);
+                }
+            }
+        }
+
+        if order.len() == self.constraints.len() {
+            Ok(order)
+        } else {
+            Err(find_cycle(&deps, &remaining))
+        }
+    }
+
+    /// The constraints sorted by [`Circuit::constraint_order`]
+    pub fn ordered_constraints(&self) -> Result<Vec<&Constraint>, DependencyCycle> {
+        Ok(self
+            .constraint_order()?
+            .into_iter()
+            .map(|i| &self.constraints[i])
+            .collect())
+    }
+
+    /// For each constraint, the `(constraint, signal)` pairs it reads from.
+    /// A signal nobody produces must be supplied by the witness.
+    fn constraint_dependencies(&self) -> Vec<Vec<(usize, String)>> {
+        let mut producers: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            if let Signal::Witness(name) | Signal::Output(name) = &constraint.output {
+                producers.entry(name).or_default().push(i);
+            }
+        }
+
+        self.constraints
+            .iter()
+            .enumerate()
+            .map(|(i, constraint)| {
+                let mut deps: Vec<(usize, String)> = Vec::new();
+                for name in constraint.operands().into_iter().filter_map(Signal::name) {
+                    let Some(ps) = producers.get(name) else {
+                        continue;
+                    };
+                    for &p in ps {
+                        // reading your own output means reading the supplied value
+                        if p != i && !deps.iter().any(|(d, _)| *d == p) {
+                            deps.push((p, name.to_string()));
+                        }
+                    }
+                }
+                deps
+            })
+            .collect()
+    }
+}
+
+/// Walks unresolved dependencies until a constraint repeats
+fn find_cycle(deps: &[Vec<(usize, String)>], remaining: &[usize]) -> DependencyCycle {
+    let start = (0..deps.len())
+        .find(|&i| remaining[i] > 0)
+        .expect("a cycle leaves at least one constraint unresolved");
+
+    let mut path: Vec<(usize, String)> = Vec::new();
+    let mut current = start;
+    loop {
+        if let Some(pos) = path.iter().position(|(i, _)| *i == current) {
+            return DependencyCycle {
+                steps: path.split_off(pos),
+            };
+        }
+        let (next, signal) = deps[current]
+            .iter()
+            .find(|(d, _)| remaining[*d] > 0)
+            .expect("an unresolved constraint depends on another unresolved one")
+            .clone();
+        path.push((current, signal));
+        current = next;
+    }
+}
@@ -0,0 +1,209 @@
+//! Graphviz export: `dot -Tsvg` turns `circuit.to_dot()` into a picture of the
+//! circuit. Signals are ellipses colored by role, gates are boxes labelled with
+//! their operation, and hints are dashed.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::{circuit::Circuit, constraint::Operation, signal::Signal, witness::Witness};
+
+const INPUT_COLOR: &str = "lightblue";
+const WITNESS_COLOR: &str = "khaki";
+const OUTPUT_COLOR: &str = "palegreen";
+const CONSTANT_COLOR: &str = "white";
+
+impl Circuit {
+    /// The circuit as a Graphviz DOT graph
+    pub fn to_dot(&self) -> String {
+        Dot::new(None).render(self)
+    }
+
+    /// Like `to_dot`, with every signal that has a value in `witness` labelled
+    /// with it. Pass a completed witness to see the intermediate values too.
+    pub fn to_dot_with_values(&self, witness: &Witness) -> String {
+        Dot::new(Some(witness)).render(self)
+    }
+}
+
+struct Dot<'a> {
+    witness: Option<&'a Witness>,
+    out: String,
+    drawn: HashSet<String>,
+    constants: usize,
+    linears: usize,
+}
+
+impl<'a> Dot<'a> {
+    fn new(witness: Option<&'a Witness>) -> Self {
+        Self {
+            witness,
+            out: String::new(),
+            drawn: HashSet::new(),
+            constants: 0,
+            linears: 0,
+        }
+    }
+
+    fn render<'c>(mut self, circuit: &'c Circuit) -> String {
+        self.out.push_str("digraph circuit {\n    rankdir=LR;\n");
+        self.out
+            .push_str("    node [style=filled, fontname=monospace];\n");
+        for sig in circuit.inputs.iter().chain(&circuit.outputs) {
+            self.signal(sig);
+        }
+
+        for (i, c) in circuit.constraints.iter().enumerate() {
+            let gate = format!("\"g:{}\"", i);
+            let plain = |edges: &[(&'c Signal, &str)]| -> Vec<(&'c Signal, String)> {
+                edges.iter().map(|(s, l)| (*s, l.to_string())).collect()
+            };
+            let (label, operands) = match &c.operation {
+                Operation::Add => ("+", plain(&[(&c.left, ""), (&c.right, "")])),
+                Operation::Sub => ("-", plain(&[(&c.left, "lhs"), (&c.right, "rhs")])),
+                Operation::Mul => ("*", plain(&[(&c.left, ""), (&c.right, "")])),
+                Operation::Eq => ("==", plain(&[(&c.left, ""), (&c.right, "")])),
+                Operation::Select(cond) => (
+                    "select",
+                    plain(&[(cond, "cond"), (&c.left, "then"), (&c.right, "else")]),
+                ),
+                Operation::Mux(options) => {
+                    let mut operands = plain(&[(&c.left, "index")]);
+                    operands.extend(options.iter().enumerate().map(|(k, o)| (o, k.to_string())));
+                    ("mux", operands)
+                }
+                Operation::Custom(gate, inputs) => (
+                    gate.name.as_str(),
+                    inputs
+                        .iter()
+                        .zip(&gate.wires)
+                        .map(|(s, w)| (s, w.clone()))
+                        .collect(),
+                ),
+                Operation::Lookup(table, inputs) => (
+                    table.name.as_str(),
+                    inputs
+                        .iter()
+                        .zip(&table.columns)
+                        .map(|(s, column)| (s, column.clone()))
+                        .collect(),
+                ),
+            };
+            let _ = writeln!(
+                self.out,
+                "    {} [shape=box, fillcolor=lightgrey, label=\"#{} {}\"];",
+                gate, i, label
+            );
+            for (sig, edge) in operands {
+                let node = self.signal(sig);
+                self.edge(&node, &gate, &edge, false);
+            }
+            let node = self.signal(&c.output);
+            self.edge(&gate, &node, "", false);
+        }
+
+        for (i, hint) in circuit.hints.iter().enumerate() {
+            let gate = format!("\"h:{}\"", i);
+            let _ = writeln!(
+                self.out,
+                "    {} [shape=box, style=dashed, label=\"hint\"];",
+                gate
+            );
+            for name in &hint.inputs {
+                let node = self.named(name, WITNESS_COLOR);
+                self.edge(&node, &gate, "", true);
+            }
+            for name in &hint.outputs {
+                let node = self.named(name, WITNESS_COLOR);
+                self.edge(&gate, &node, "", true);
+            }
+        }
+
+        self.out.push_str("}\n");
+        self.out
+    }
+
+    /// Draws the signal unless it already is, returning its node id. Every use of
+    /// a constant gets a node of its own so that they don't tie the graph together,
+    /// and a linear combination is a node summing its terms.
+    fn signal(&mut self, sig: &Signal) -> String {
+        match sig {
+            Signal::Input(name) => self.named(name, INPUT_COLOR),
+            Signal::Witness(name) => self.named(name, WITNESS_COLOR),
+            Signal::Output(name) => self.named(name, OUTPUT_COLOR),
+            Signal::Constant(value) => {
+                let id = format!("\"c:{}\"", self.constants);
+                self.constants += 1;
+                let _ = writeln!(
+                    self.out,
+                    "    {} [shape=plaintext, fillcolor={}, label=\"{}\"];",
+                    id, CONSTANT_COLOR, value
+                );
+                id
+            }
+            Signal::Linear(lc) => {
+                let id = format!("\"l:{}\"", self.linears);
+                self.linears += 1;
+                let label = match lc.constant {
+                    0 => "sum".to_string(),
+                    k => format!("sum + {}", k),
+                };
+                let _ = writeln!(
+                    self.out,
+                    "    {} [shape=box, fillcolor={}, label=\"{}\"];",
+                    id, CONSTANT_COLOR, label
+                );
+                for (term, coeff) in &lc.terms {
+                    let node = self.signal(term);
+                    let label = if *coeff == 1 {
+                        String::new()
+                    } else {
+                        coeff.to_string()
+                    };
+                    self.edge(&node, &id, &label, false);
+                }
+                id
+            }
+        }
+    }
+
+    /// Signals are keyed by name, so a hint's output is the node its constraints use
+    fn named(&mut self, name: &str, color: &str) -> String {
+        let id = format!("\"s:{}\"", escape(name));
+        if self.drawn.insert(name.to_string()) {
+            let value = self
+                .witness
+                .and_then(|w| w.values.get(name))
+                .map(|v| format!("\\n= {}", v.value))
+                .unwrap_or_default();
+            let _ = writeln!(
+                self.out,
+                "    {} [fillcolor={}, label=\"{}{}\"];",
+                id,
+                color,
+                escape(name),
+                value
+            );
+        }
+        id
+    }
+
+    fn edge(&mut self, from: &str, to: &str, label: &str, dashed: bool) {
+        let mut attrs = Vec::new();
+        if !label.is_empty() {
+            attrs.push(format!("label=\"{}\"", label));
+        }
+        if dashed {
+            attrs.push("style=dashed".to_string());
+        }
+        let attrs = if attrs.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attrs.join(", "))
+        };
+        let _ = writeln!(self.out, "    {} -> {}{};", from, to, attrs);
+    }
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
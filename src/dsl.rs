@@ -3,102 +3,518 @@
 /// Usage:
 /// ```ignore
 ///   constraint!((left@Role + right@Role) -> out@Role)
+///   constraint!((sq.out@Witness * x@Input) -> out@Output)
+///   constraint!((acc[i]@Witness + bits[i + 1]@Input) -> acc[i + 1]@Witness)
+///   constraint!((c@Witness ? a@Input : b@Input) -> out@Output)
+///   constraint!((idx@Witness ? [a@Input, b@Input, c@Input]) -> out@Output)
+///   constraint!(({a@Input + 2 * b@Input} * {c@Witness - 1}) -> {out@Output + d@Witness})
+///   constraint!((sbox(x@Input)) -> y@Witness)
+///   constraint!((xor8(a@Witness, b@Witness)) -> c@Witness)
 /// ```
 ///
 /// - `Role` can be Input, Witness, or Output
+/// - Operands of `+`, `-`, `*` and `==`, the else branch of a select and the
+///   output may be linear combinations in braces, see `linear!`. An output
+///   combination is checked, not assigned.
+/// - `gate(a, b, ...)` applies the custom `Gate` in the variable `gate`, or
+///   looks the inputs up in the `Table` in it
+/// - Dotted names refer to signals of a template instance
+/// - Indices select an element of an array signal and may be any `usize` expression
+/// - `c ? a : b` selects `a` when `c` is 1 and `b` when `c` is 0
+/// - `i ? [a, b, c]` selects the option at index `i`
 #[macro_export]
 macro_rules! constraint {
-    (( $l:ident @ $roleL:ident + $r:ident @ $roleR:ident ) -> $o:ident @ $roleO:ident) => {
-        $crate::Constraint {
-            left: $crate::Signal::$roleL(stringify!($l).to_string()),
-            right: $crate::Signal::$roleR(stringify!($r).to_string()),
-            output: $crate::Signal::$roleO(stringify!($o).to_string()),
-            operation: $crate::Operation::Add,
-        }
+    (( $( $inner:tt )+ ) -> $( $out:tt )+) => {
+        $crate::constraint!(@left [] [ $( $out )+ ] $( $inner )+)
     };
-    (( $l:ident @ $roleL:ident - $r:ident @ $roleR:ident ) -> $o:ident @ $roleO:ident) => {
-        $crate::Constraint {
-            left: $crate::Signal::$roleL(stringify!($l).to_string()),
-            right: $crate::Signal::$roleR(stringify!($r).to_string()),
-            output: $crate::Signal::$roleO(stringify!($o).to_string()),
-            operation: $crate::Operation::Sub,
-        }
+
+    // Operands are munched token by token up to their `@Role`, or are a
+    // linear combination in braces
+    (@left [] $out:tt $gate:ident ( $( $args:tt )+ )) => {
+        $crate::constraint!(@args $gate [] [] $out $( $args )+)
+    };
+    (@left [] $out:tt { $( $lc:tt )+ } $op:tt $( $rest:tt )+) => {
+        $crate::constraint!(@right [ binary { $( $lc )+ } $op ] [] $out $( $rest )+)
+    };
+    (@left [ $( $l:tt )+ ] $out:tt @ $roleL:ident ? [ $( $options:tt )+ ]) => {
+        $crate::constraint!(@options [ [ $( $l )+ ] $roleL ] [] [] $out $( $options )+)
+    };
+    (@left [ $( $l:tt )+ ] $out:tt @ $roleL:ident ? $( $rest:tt )+) => {
+        $crate::constraint!(@then [ [ $( $l )+ ] $roleL ] [] $out $( $rest )+)
+    };
+    (@left [ $( $l:tt )+ ] $out:tt @ $roleL:ident $op:tt $( $rest:tt )+) => {
+        $crate::constraint!(@right [ binary [ [ $( $l )+ ] $roleL ] $op ] [] $out $( $rest )+)
+    };
+    (@left [ $( $l:tt )* ] $out:tt $next:tt $( $rest:tt )+) => {
+        $crate::constraint!(@left [ $( $l )* $next ] $out $( $rest )+)
+    };
+    (@then $cond:tt [ $( $t:tt )+ ] $out:tt @ $roleT:ident : $( $rest:tt )+) => {
+        $crate::constraint!(@right [ select $cond [ [ $( $t )+ ] $roleT ] ] [] $out $( $rest )+)
+    };
+    (@then $cond:tt [ $( $t:tt )* ] $out:tt $next:tt $( $rest:tt )+) => {
+        $crate::constraint!(@then $cond [ $( $t )* $next ] $out $( $rest )+)
+    };
+    (@options $index:tt [ $( $done:tt )* ] [ $( $o:tt )+ ] $out:tt @ $role:ident , $( $rest:tt )+) => {
+        $crate::constraint!(@options $index [ $( $done )* [ [ $( $o )+ ] $role ] ] [] $out $( $rest )+)
+    };
+    (@options $index:tt [ $( $done:tt )* ] [ $( $o:tt )+ ] [ $( $out:tt )+ ] @ $role:ident $(,)?) => {
+        $crate::constraint!(@output [ mux $index [ $( $done )* [ [ $( $o )+ ] $role ] ] ] [] $( $out )+)
+    };
+    (@options $index:tt $done:tt [ $( $o:tt )* ] $out:tt $next:tt $( $rest:tt )+) => {
+        $crate::constraint!(@options $index $done [ $( $o )* $next ] $out $( $rest )+)
+    };
+    (@args $gate:ident [ $( $done:tt )* ] [ $( $a:tt )+ ] $out:tt @ $role:ident , $( $rest:tt )+) => {
+        $crate::constraint!(@args $gate [ $( $done )* [ [ $( $a )+ ] $role ] ] [] $out $( $rest )+)
+    };
+    (@args $gate:ident [ $( $done:tt )* ] [ $( $a:tt )+ ] [ $( $out:tt )+ ] @ $role:ident $(,)?) => {
+        $crate::constraint!(@output [ custom $gate [ $( $done )* [ [ $( $a )+ ] $role ] ] ] [] $( $out )+)
+    };
+    (@args $gate:ident $done:tt [ $( $a:tt )* ] $out:tt $next:tt $( $rest:tt )+) => {
+        $crate::constraint!(@args $gate $done [ $( $a )* $next ] $out $( $rest )+)
     };
-    (( $l:ident @ $roleL:ident * $r:ident @ $roleR:ident ) -> $o:ident @ $roleO:ident) => {
+    (@right [ $( $build:tt )* ] [] [ $( $out:tt )+ ] { $( $lc:tt )+ }) => {
+        $crate::constraint!(@output [ $( $build )* { $( $lc )+ } ] [] $( $out )+)
+    };
+    (@right [ $( $build:tt )* ] [ $( $r:tt )+ ] [ $( $out:tt )+ ] @ $roleR:ident) => {
+        $crate::constraint!(@output [ $( $build )* [ [ $( $r )+ ] $roleR ] ] [] $( $out )+)
+    };
+    (@right $build:tt [ $( $r:tt )* ] $out:tt $next:tt $( $rest:tt )*) => {
+        $crate::constraint!(@right $build [ $( $r )* $next ] $out $( $rest )*)
+    };
+    (@output [ $( $build:tt )* ] [] { $( $lc:tt )+ }) => {
+        $crate::constraint!(@finish $( $build )* { $( $lc )+ })
+    };
+    (@output [ $( $build:tt )* ] [ $( $o:tt )+ ] @ $roleO:ident) => {
+        $crate::constraint!(@finish $( $build )* [ [ $( $o )+ ] $roleO ])
+    };
+    (@output $build:tt [ $( $o:tt )* ] $next:tt $( $rest:tt )*) => {
+        $crate::constraint!(@output $build [ $( $o )* $next ] $( $rest )*)
+    };
+
+    (@finish binary $left:tt $op:tt $right:tt $output:tt) => {
         $crate::Constraint {
-            left: $crate::Signal::$roleL(stringify!($l).to_string()),
-            right: $crate::Signal::$roleR(stringify!($r).to_string()),
-            output: $crate::Signal::$roleO(stringify!($o).to_string()),
-            operation: $crate::Operation::Mul,
+            left: $crate::constraint!(@signal $left),
+            right: $crate::constraint!(@signal $right),
+            output: $crate::constraint!(@signal $output),
+            operation: $crate::constraint!(@operation $op),
         }
     };
-    (( $l:ident @ $roleL:ident == $r:ident @ $roleR:ident ) -> $o:ident @ $roleO:ident) => {
-        $crate::Constraint {
-            left: $crate::Signal::$roleL(stringify!($l).to_string()),
-            right: $crate::Signal::$roleR(stringify!($r).to_string()),
-            output: $crate::Signal::$roleO(stringify!($o).to_string()),
-            operation: $crate::Operation::Eq,
+    (@finish select $cond:tt $then:tt $otherwise:tt $output:tt) => {
+        $crate::Constraint::select(
+            $crate::constraint!(@signal $cond),
+            $crate::constraint!(@signal $then),
+            $crate::constraint!(@signal $otherwise),
+            $crate::constraint!(@signal $output),
+        )
+    };
+    (@finish mux $index:tt [ $( $option:tt )+ ] $output:tt) => {
+        $crate::Constraint::mux(
+            $crate::constraint!(@signal $index),
+            vec![ $( $crate::constraint!(@signal $option) ),+ ],
+            $crate::constraint!(@signal $output),
+        )
+    };
+    (@finish custom $gate:ident [ $( $arg:tt )+ ] $output:tt) => {
+        $crate::Callable::call(
+            &$gate,
+            vec![ $( $crate::constraint!(@signal $arg) ),+ ],
+            $crate::constraint!(@signal $output),
+        )
+    };
+    (@signal [ [ $( $name:tt )+ ] $role:ident ]) => {
+        $crate::Signal::$role($crate::signal_name!($( $name )+))
+    };
+    (@signal { $( $lc:tt )+ }) => {
+        $crate::Signal::Linear($crate::linear!($( $lc )+))
+    };
+    (@operation +) => { $crate::Operation::Add };
+    (@operation -) => { $crate::Operation::Sub };
+    (@operation *) => { $crate::Operation::Mul };
+    (@operation ==) => { $crate::Operation::Eq };
+}
+
+/// Build the name of a signal as a `SignalName`
+///
+/// Usage:
+/// ```ignore
+///   signal_name!(x)              // "x"
+///   signal_name!(grid[i][j])     // "grid[1][2]" for i = 1, j = 2
+///   signal_name!(hasher[0].out)  // "hasher[0].out"
+/// ```
+///
+/// - Indices are `usize`; a path after `.` keeps the indices before it in the base
+#[macro_export]
+macro_rules! signal_name {
+    ( $name:ident $( [ $idx:expr ] )* ) => {
+        $crate::SignalName::element(stringify!($name), &[ $( { let _index: usize = $idx; _index } ),* ])
+    };
+    ( $name:ident $( [ $idx:expr ] )* . $($rest:tt)+ ) => {
+        {
+            let _rest = $crate::signal_name!($($rest)+);
+            let _base = format!("{}.{}", $crate::signal_name!($name $( [ $idx ] )*), _rest.base());
+            $crate::SignalName::element(&_base, _rest.indices())
         }
     };
 }
 
-/// Define a circuit with inputs, outputs, and constraints
+/// Build a `LinearCombination` from terms joined by `+` and `-`
+///
+/// Usage:
+/// ```ignore
+///   linear!(a@Input + b@Witness)
+///   linear!(2 * acc[i]@Witness - bits[i]@Input + 1)
+///   linear!((1 << i) * bits[i]@Witness)
+/// ```
+///
+/// - A term is a signal, a coefficient times a signal, or a constant
+/// - Coefficients and constants are literals or parenthesized expressions
+#[macro_export]
+macro_rules! linear {
+    (@term $acc:tt $sign:tt $k:literal * $( $rest:tt )+) => {
+        $crate::linear!(@signal $acc $sign [ $k ] [] $( $rest )+)
+    };
+    (@term $acc:tt $sign:tt ( $k:expr ) * $( $rest:tt )+) => {
+        $crate::linear!(@signal $acc $sign [ $k ] [] $( $rest )+)
+    };
+    (@term $acc:tt $sign:tt $k:literal $( $rest:tt )*) => {
+        $crate::linear!(@next $acc $sign [ $crate::LinearCombination::constant($k) ] $( $rest )*)
+    };
+    (@term $acc:tt $sign:tt ( $k:expr ) $( $rest:tt )*) => {
+        $crate::linear!(@next $acc $sign [ $crate::LinearCombination::constant($k) ] $( $rest )*)
+    };
+    (@term $acc:tt $sign:tt $( $rest:tt )+) => {
+        $crate::linear!(@signal $acc $sign [ 1 ] [] $( $rest )+)
+    };
+
+    // A signal is munched token by token up to its `@Role`
+    (@signal $acc:tt $sign:tt [ $k:expr ] [ $( $name:tt )+ ] @ $role:ident $( $rest:tt )*) => {
+        $crate::linear!(
+            @next $acc $sign
+            [
+                $crate::LinearCombination::from_signal(
+                    &$crate::Signal::$role($crate::signal_name!($( $name )+))
+                )
+                .scale($k)
+            ]
+            $( $rest )*
+        )
+    };
+    (@signal $acc:tt $sign:tt $k:tt [ $( $name:tt )* ] $next:tt $( $rest:tt )+) => {
+        $crate::linear!(@signal $acc $sign $k [ $( $name )* $next ] $( $rest )+)
+    };
+
+    (@next [ $acc:expr ] + [ $term:expr ] $( $rest:tt )*) => {
+        $crate::linear!(@done [ $acc.add(&$term) ] $( $rest )*)
+    };
+    (@next [ $acc:expr ] - [ $term:expr ] $( $rest:tt )*) => {
+        $crate::linear!(@done [ $acc.sub(&$term) ] $( $rest )*)
+    };
+    (@done [ $acc:expr ]) => { $acc };
+    (@done $acc:tt $sign:tt $( $rest:tt )+) => {
+        $crate::linear!(@term $acc $sign $( $rest )+)
+    };
+
+    ( $( $t:tt )+ ) => {
+        $crate::linear!(@term [ $crate::LinearCombination::default() ] + $( $t )+)
+    };
+}
+
+/// Define a circuit with inputs, outputs, constraints and optionally hints
 ///
 /// Usage:
 /// ```ignore
 ///   circuit! {
-///       [x] -> [out],
+///       [x, bits[8], [grid; 4]] -> [out],
 ///       constraints = [
 ///           constraint!((w@Witness + x@Input) -> out@Output)
+///       ],
+///       hints = [
+///           hint!(w = |x| x.sqrt())
 ///       ]
 ///   }
 /// ```
+///
+/// Repetitive constraints go in a block with `for` loops over ranges,
+/// whose bounds are literals, identifiers or parenthesized expressions:
+/// ```ignore
+///   circuit! {
+///       [[xs; 4]] -> [out],
+///       witnesses = [acc[3]],
+///       constraints = {
+///           constraint!((xs[0]@Input + xs[1]@Input) -> acc[1]@Witness);
+///           for i in 2..(N - 1) {
+///               constraint!((acc[i - 1]@Witness + xs[i]@Input) -> acc[i]@Witness);
+///           }
+///           constraint!((acc[2]@Witness + xs[3]@Input) -> out@Output);
+///       }
+///   }
+/// ```
+///
+/// Witness arrays may be declared like inputs and outputs. Literal indices into
+/// declared arrays are checked at compile time, other indices when a witness is
+/// generated:
+/// ```compile_fail
+///   use ghayb::{circuit, constraint};
+///   circuit! {
+///       [xs[3]] -> [out],
+///       constraints = [
+///           constraint!((xs[0]@Input + xs[3]@Input) -> out@Output)
+///       ]
+///   };
+/// ```
 #[macro_export]
 macro_rules! circuit {
     (
-        [ $( $inp:ident ),* $(,)? ] -> [ $( $out:ident ),* $(,)? ],
-        constraints = [ $( $c:expr ),* $(,)? ]
+        [ $( $inp:tt )* ] -> [ $( $out:tt )* ],
+        $( witnesses = [ $( $wit:tt )* ], )?
+        constraints = [ $( $c:tt )* ]
+        $(, hints = [ $( $h:expr ),* $(,)? ] )?
     ) => {
         {
-            let _in_signals = vec![
-                $(
-                    $crate::Signal::Input(stringify!($inp).to_string()),
-                )*
-            ];
+            let _in_signals = $crate::circuit!(@signals Input; $( $inp )*);
 
-            let _out_signals = vec![
-                $(
-                    $crate::Signal::Output(stringify!($out).to_string()),
-                )*
-            ];
+            let _out_signals = $crate::circuit!(@signals Output; $( $out )*);
 
-            let _constraints = vec![
-                $(
-                    $c,
-                )*
-            ];
+            let _wit_signals = $crate::circuit!(@signals Witness; $( $( $wit )* )?);
+
+            $crate::circuit!(@shapes [] $( $inp )* , $( $out )* , $( $( $wit )* )?);
+            $crate::circuit!(@checks $( $c )*);
+            let (_constraints, _sources): (Vec<$crate::Constraint>, Vec<&str>) =
+                $crate::circuit!(@list $( $c )*);
+
+            let mut _circuit = $crate::Circuit::new(_in_signals, _out_signals, _constraints)
+                .with_witnesses(_wit_signals);
+            _circuit.sources = _sources.iter().map(|s| s.to_string()).enumerate().collect();
+            $( $(
+                let _circuit = _circuit.with_hint($h);
+            )* )?
+            _circuit
+        }
+    };
+    (
+        [ $( $inp:tt )* ] -> [ $( $out:tt )* ],
+        $( witnesses = [ $( $wit:tt )* ], )?
+        constraints = { $( $body:tt )* }
+        $(, hints = [ $( $h:expr ),* $(,)? ] )?
+    ) => {
+        {
+            let _in_signals = $crate::circuit!(@signals Input; $( $inp )*);
+
+            let _out_signals = $crate::circuit!(@signals Output; $( $out )*);
+
+            let _wit_signals = $crate::circuit!(@signals Witness; $( $( $wit )* )?);
+
+            $crate::circuit!(@shapes [] $( $inp )* , $( $out )* , $( $( $wit )* )?);
+            let mut _constraints: Vec<$crate::Constraint> = Vec::new();
+            let mut _sources = std::collections::HashMap::new();
+            $crate::circuit!(@body _constraints _sources; $( $body )*);
 
-            $crate::Circuit::new(_in_signals, _out_signals, _constraints)
+            let mut _circuit = $crate::Circuit::new(_in_signals, _out_signals, _constraints)
+                .with_witnesses(_wit_signals);
+            _circuit.sources = _sources;
+            $( $(
+                let _circuit = _circuit.with_hint($h);
+            )* )?
+            _circuit
+        }
+    };
+
+    // Constraint lists, kept as tokens up to here so that they can be checked
+    (@list $( $c:expr ),* $(,)? ) => {
+        (vec![ $( $c ),* ], vec![ $( stringify!($c) ),* ])
+    };
+
+    // Constraint blocks: `constraint!(...);` statements and `for` loops over ranges
+    (@body $vec:ident $src:ident; ) => {};
+    (@body $vec:ident $src:ident; for $i:ident in $start:tt .. $end:tt { $( $inner:tt )* } $( $rest:tt )* ) => {
+        for $i in $start..$end {
+            $crate::circuit!(@body $vec $src; $( $inner )*);
+        }
+        $crate::circuit!(@body $vec $src; $( $rest )*);
+    };
+    (@body $vec:ident $src:ident; for $i:ident in $start:tt ..= $end:tt { $( $inner:tt )* } $( $rest:tt )* ) => {
+        for $i in $start..=$end {
+            $crate::circuit!(@body $vec $src; $( $inner )*);
+        }
+        $crate::circuit!(@body $vec $src; $( $rest )*);
+    };
+    (@body $vec:ident $src:ident; $m:tt $bang:tt $args:tt; $( $rest:tt )* ) => {
+        $crate::circuit!(@check $m $bang $args);
+        $crate::circuit!(@push $vec $src; $m $bang $args);
+        $crate::circuit!(@body $vec $src; $( $rest )*);
+    };
+    (@body $vec:ident $src:ident; $c:expr; $( $rest:tt )* ) => {
+        $crate::circuit!(@push $vec $src; $c);
+        $crate::circuit!(@body $vec $src; $( $rest )*);
+    };
+    (@push $vec:ident $src:ident; $c:expr) => {
+        $src.insert($vec.len(), stringify!($c).to_string());
+        $vec.extend(std::iter::once($c));
+    };
+
+    // Compile-time bounds checks: `__ghayb_bounds!` maps each declared array
+    // to its dimensions, and the arguments of every `constraint!(...)` are
+    // scanned for signals with literal indices
+    (@shapes [ $( $acc:tt )* ] ) => {
+        $crate::circuit!(@bounds ($) $( $acc )*);
+    };
+    (@shapes [ $( $acc:tt )* ] , $( $rest:tt )* ) => {
+        $crate::circuit!(@shapes [ $( $acc )* ] $( $rest )*);
+    };
+    (@shapes [ $( $acc:tt )* ] [ $name:ident ; $len:expr ] $( $rest:tt )* ) => {
+        $crate::circuit!(@shapes [ $( $acc )* $name [ $len ] ] $( $rest )*);
+    };
+    (@shapes [ $( $acc:tt )* ] $name:ident $( [ $dim:expr ] )+ $( , $( $rest:tt )* )? ) => {
+        $crate::circuit!(@shapes [ $( $acc )* $name [ $( $dim ),+ ] ] $( $( $rest )* )?);
+    };
+    (@shapes [ $( $acc:tt )* ] $name:ident $( , $( $rest:tt )* )? ) => {
+        $crate::circuit!(@shapes [ $( $acc )* ] $( $( $rest )* )?);
+    };
+    (@bounds ($d:tt) $( $name:ident [ $( $dim:expr ),+ ] )* ) => {
+        #[allow(unused_macros)]
+        macro_rules! __ghayb_bounds {
+            $(
+                ($name [ $d( $d index:literal ),+ ]) => {
+                    const _: () = assert!(
+                        $crate::indices_in_bounds(&[ $d( $d index ),+ ], &[ $( $dim ),+ ]),
+                        concat!(
+                            "Signal '", stringify!($name), $d( "[", stringify!($d index), "]", )+
+                            "' is out of bounds for array '", stringify!($name), "'"
+                        )
+                    );
+                };
+            )*
+            ($d other:ident [ $d( $d index:literal ),+ ]) => {};
+        }
+    };
+    (@checks $( $m:tt $bang:tt $args:tt ),* $(,)? ) => {
+        $( $crate::circuit!(@check $m $bang $args); )*
+    };
+    (@checks $( $other:tt )* ) => {};
+    (@check $m:ident ! ( $( $args:tt )* ) ) => {
+        $crate::circuit!(@scan $( $args )*);
+    };
+    (@check $( $other:tt )* ) => {};
+    (@scan ) => {};
+    (@scan $name:ident $( [ $index:literal ] )+ @ $( $rest:tt )* ) => {
+        __ghayb_bounds!($name [ $( $index ),+ ]);
+        $crate::circuit!(@scan $( $rest )*);
+    };
+    (@scan ( $( $inner:tt )* ) $( $rest:tt )* ) => {
+        $crate::circuit!(@scan $( $inner )*);
+        $crate::circuit!(@scan $( $rest )*);
+    };
+    (@scan [ $( $inner:tt )* ] $( $rest:tt )* ) => {
+        $crate::circuit!(@scan $( $inner )*);
+        $crate::circuit!(@scan $( $rest )*);
+    };
+    (@scan { $( $inner:tt )* } $( $rest:tt )* ) => {
+        $crate::circuit!(@scan $( $inner )*);
+        $crate::circuit!(@scan $( $rest )*);
+    };
+    (@scan $other:tt $( $rest:tt )* ) => {
+        $crate::circuit!(@scan $( $rest )*);
+    };
+
+    // Declared signals: `x`, `bits[32]`, `grid[9][9]` or `[bits; 32]`
+    (@signals $role:ident; ) => {
+        Vec::<$crate::Signal>::new()
+    };
+    (@signals $role:ident; , $( $rest:tt )* ) => {
+        $crate::circuit!(@signals $role; $( $rest )*)
+    };
+    (@signals $role:ident; [ $name:ident ; $len:expr ] $( $rest:tt )* ) => {
+        $crate::circuit!(@signals $role; $name[$len] $( $rest )*)
+    };
+    (@signals $role:ident; $name:ident $( [ $dim:expr ] )+ $( , $( $rest:tt )* )? ) => {
+        {
+            let mut _signals: Vec<$crate::Signal> = $crate::SignalName::elements(
+                stringify!($name),
+                &[ $( { const DIM: usize = $dim; DIM } ),+ ],
+            )
+            .into_iter()
+            .map($crate::Signal::$role)
+            .collect();
+            $( _signals.extend($crate::circuit!(@signals $role; $( $rest )*)); )?
+            _signals
+        }
+    };
+    (@signals $role:ident; $name:ident $( , $( $rest:tt )* )? ) => {
+        {
+            let mut _signals = vec![$crate::Signal::$role(stringify!($name).into())];
+            $( _signals.extend($crate::circuit!(@signals $role; $( $rest )*)); )?
+            _signals
         }
     };
 }
 
+/// Whether the literal indices of a `circuit!` signal are below the dimensions
+/// of its array, the rest is checked when a witness is generated
+#[doc(hidden)]
+pub const fn indices_in_bounds(indices: &[usize], dims: &[usize]) -> bool {
+    let mut k = 0;
+    while k < indices.len() && k < dims.len() {
+        if indices[k] >= dims[k] {
+            return false;
+        }
+        k += 1;
+    }
+    true
+}
+
+/// Define a hint: a closure over named signals computing values the prover
+/// must supply but the constraints only check
+///
+/// Usage:
+/// ```ignore
+///   hint!(r = |x| x.sqrt())
+///   hint!([b0, b1, b2] = |x| x.to_bits(3))
+/// ```
+///
+/// - The closure parameters name the input signals, each bound to a `FieldElement`
+/// - The body returns a `FieldElement`, an `Option<FieldElement>` or a `Vec<FieldElement>`
+#[macro_export]
+macro_rules! hint {
+    ( [ $( $out:ident ),+ $(,)? ] = | $( $arg:ident ),* | $body:expr ) => {
+        $crate::Hint::new(
+            &[ $( stringify!($out) ),+ ],
+            &[ $( stringify!($arg) ),* ],
+            move |_args: &[$crate::FieldElement]| {
+                let mut _args = _args.iter().cloned();
+                $(
+                    let $arg: $crate::FieldElement = _args.next().unwrap();
+                )*
+                $crate::IntoHintValues::into_hint_values($body)
+            },
+        )
+    };
+    ( $out:ident = | $( $arg:ident ),* | $body:expr ) => {
+        $crate::hint!([ $out ] = | $( $arg ),* | $body)
+    };
+}
+
 /// Define a witness (private inputs for a circuit)
 ///
 /// Usage:
 /// ```ignore
 ///    witness! { w = 5, x = 10 }
+///    witness! { bits = [1, 0, 1], grid = [[1, 2], [3, 4]], acc[0] = 7 }
 /// ```
+///
+/// - Array values are assigned element-wise, row-major
 #[macro_export]
 macro_rules! witness {
-    ( $( $name:ident = $val:expr ),* $(,)? ) => {
+    ( $( $name:ident $( [ $idx:expr ] )* = $val:expr ),* $(,)? ) => {
         {
             let mut _map = std::collections::HashMap::new();
             $(
-                _map.insert(
-                    stringify!($name).to_string(),
-                    $crate::FieldElement::new($val, $crate::PRIME),
-                );
+                let _base = $crate::signal_name!($name $( [ $idx ] )*);
+                for (_indices, _value) in $crate::WitnessValue::flatten($val) {
+                    _map.insert(
+                        _base.index(&_indices).to_string(),
+                        $crate::FieldElement::new(_value, $crate::PRIME),
+                    );
+                }
             )*
             $crate::Witness::new(_map)
         }
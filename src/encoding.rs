@@ -0,0 +1,622 @@
+//! Compact binary encoding of proofs and circuits.
+//!
+//! ```text
+//! "GHYB" | version: u8 | kind: u8 | prime: u128 | section*
+//! section = tag: u8 | length: u32 | payload
+//! ```
+//!
+//! Integers are little-endian, strings and byte strings are prefixed with their
+//! `u32` length, and field elements take the fewest bytes that fit any element
+//! (2 for `PRIME`). Every value of the field is written as a field element:
+//! constant signals, the coefficients and constant of a linear combination, the
+//! coefficients of a gate and the values of a table. A linear combination is
+//! its terms, each a named signal with a non-zero coefficient, then its
+//! constant. A custom gate is its name, its wire names and its terms, each a
+//! coefficient and one exponent per wire, in the order `Polynomial` keeps them.
+//! A lookup table is its name, its column names and its kind: a tag and a
+//! number of bits, or its rows. Map entries are sorted by name, so a value has
+//! exactly one encoding, and decoding rejects anything else: unreduced
+//! elements, unsorted or duplicate names, unknown tags and trailing bytes.
+//!
+//! A circuit is its inputs, outputs, constraints and declared witness arrays,
+//! in that order. It doubles as the verification key. Its hints are closures
+//! and are not encoded.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    circuit::{invalid_circuit, Circuit},
+    commitment::Commitment,
+    constraint::{Constraint, Operation},
+    field_element::FieldElement,
+    gate::{Gate, Monomial, Polynomial},
+    linear::LinearCombination,
+    lookup::{Table, TableKind},
+    proof::Proof,
+    signal::Signal,
+    PRIME,
+};
+
+const MAGIC: &[u8; 4] = b"GHYB";
+const VERSION: u8 = 3;
+
+const KIND_PROOF: u8 = 1;
+const KIND_CIRCUIT: u8 = 2;
+
+const SECTION_COMMITMENTS: u8 = 1;
+const SECTION_REVEALED: u8 = 2;
+const SECTION_INPUTS: u8 = 1;
+const SECTION_OUTPUTS: u8 = 2;
+const SECTION_CONSTRAINTS: u8 = 3;
+const SECTION_WITNESSES: u8 = 4;
+
+/// Why bytes could not be decoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    WrongKind {
+        expected: u8,
+        got: u8,
+    },
+    /// The bytes were encoded for a different field
+    FieldMismatch {
+        expected: u128,
+        got: u128,
+    },
+    UnexpectedEnd,
+    MissingSection {
+        expected: u8,
+        got: u8,
+    },
+    /// A section's payload was shorter or longer than its length prefix
+    SectionLength {
+        section: u8,
+    },
+    NonCanonicalElement(u128),
+    /// Names must be strictly increasing
+    UnsortedName(String),
+    InvalidUtf8,
+    InvalidTag(u8),
+    TrailingBytes(usize),
+    /// Decoded fine, but `Circuit::new` would reject it
+    InvalidCircuit(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not a ghayb encoding"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            DecodeError::WrongKind { expected, got } => {
+                write!(f, "expected an encoding of kind {}, got {}", expected, got)
+            }
+            DecodeError::FieldMismatch { expected, got } => write!(
+                f,
+                "encoded for the field of size {}, expected {}",
+                got, expected
+            ),
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            DecodeError::MissingSection { expected, got } => {
+                write!(f, "expected section {}, got {}", expected, got)
+            }
+            DecodeError::SectionLength { section } => {
+                write!(f, "section {} does not match its length", section)
+            }
+            DecodeError::NonCanonicalElement(v) => {
+                write!(f, "{} is not a canonical field element", v)
+            }
+            DecodeError::UnsortedName(name) => {
+                write!(f, "name '{}' is out of order or repeated", name)
+            }
+            DecodeError::InvalidUtf8 => write!(f, "name is not valid UTF-8"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid tag {}", tag),
+            DecodeError::TrailingBytes(n) => write!(f, "{} trailing bytes", n),
+            DecodeError::InvalidCircuit(message) => write!(f, "invalid circuit: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Proof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Encoder::header(KIND_PROOF);
+        out.section(SECTION_COMMITMENTS, |e| {
+            e.sorted(&self.commitments, |e, c| e.bytes(&c.committed_hash))
+        });
+        out.section(SECTION_REVEALED, |e| {
+            e.sorted(&self.revealed_witness, |e, (value, blinding)| {
+                e.element(value);
+                e.element(blinding);
+            })
+        });
+        out.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut d = Decoder::new(bytes);
+        d.header(KIND_PROOF)?;
+        let commitments = d.section(SECTION_COMMITMENTS, |d| {
+            d.sorted(|d| {
+                Ok(Commitment {
+                    committed_hash: d.bytes()?.to_vec(),
+                })
+            })
+        })?;
+        let revealed_witness = d.section(SECTION_REVEALED, |d| {
+            d.sorted(|d| Ok((d.element()?, d.element()?)))
+        })?;
+        d.finish()?;
+        Ok(Proof {
+            commitments,
+            revealed_witness,
+        })
+    }
+}
+
+impl Circuit {
+    /// The circuit without its hints
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Encoder::header(KIND_CIRCUIT);
+        out.section(SECTION_INPUTS, |e| e.signals(&self.inputs));
+        out.section(SECTION_OUTPUTS, |e| e.signals(&self.outputs));
+        out.section(SECTION_CONSTRAINTS, |e| {
+            e.u32(self.constraints.len());
+            for c in &self.constraints {
+                e.constraint(c);
+            }
+        });
+        out.section(SECTION_WITNESSES, |e| e.signals(&self.witnesses));
+        out.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut d = Decoder::new(bytes);
+        d.header(KIND_CIRCUIT)?;
+        let inputs = d.section(SECTION_INPUTS, Decoder::signals)?;
+        let outputs = d.section(SECTION_OUTPUTS, Decoder::signals)?;
+        let constraints: Vec<Constraint> = d.section(SECTION_CONSTRAINTS, |d| {
+            (0..d.u32()?).map(|_| d.constraint()).collect()
+        })?;
+        let witnesses = d.section(SECTION_WITNESSES, Decoder::signals)?;
+        d.finish()?;
+
+        let circuit = Circuit::new(inputs, outputs, constraints).with_witnesses(witnesses);
+        if let Some(message) = invalid_circuit(&circuit) {
+            return Err(DecodeError::InvalidCircuit(message));
+        }
+        Ok(circuit)
+    }
+}
+
+/// Bytes of a field element: enough for `PRIME - 1`
+fn element_width() -> usize {
+    let bits = 128 - (PRIME - 1).leading_zeros() as usize;
+    bits.div_ceil(8).max(1)
+}
+
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn header(kind: u8) -> Self {
+        let mut e = Encoder { bytes: Vec::new() };
+        e.bytes.extend_from_slice(MAGIC);
+        e.u8(VERSION);
+        e.u8(kind);
+        e.u128(PRIME);
+        e
+    }
+
+    fn section(&mut self, tag: u8, write: impl FnOnce(&mut Encoder)) {
+        let mut payload = Encoder { bytes: Vec::new() };
+        write(&mut payload);
+        self.u8(tag);
+        self.bytes(&payload.bytes);
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.bytes.push(v);
+    }
+
+    fn u32(&mut self, v: usize) {
+        let v = u32::try_from(v).expect("length does not fit in the encoding");
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u128(&mut self, v: u128) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.u32(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    fn element(&mut self, e: &FieldElement) {
+        assert_eq!(e.prime, PRIME, "Mismatched primes!");
+        self.bytes
+            .extend_from_slice(&e.value.to_le_bytes()[..element_width()]);
+    }
+
+    /// A value of the field, reduced and written like an element
+    fn value(&mut self, v: u128) {
+        self.element(&FieldElement::new(v, PRIME));
+    }
+
+    /// Entries in name order, prefixed with their count
+    fn sorted<V>(&mut self, map: &HashMap<String, V>, mut write: impl FnMut(&mut Encoder, &V)) {
+        let mut names: Vec<&String> = map.keys().collect();
+        names.sort();
+        self.u32(names.len());
+        for name in names {
+            self.bytes(name.as_bytes());
+            write(self, &map[name]);
+        }
+    }
+
+    fn signals(&mut self, signals: &[Signal]) {
+        self.u32(signals.len());
+        for sig in signals {
+            self.signal(sig);
+        }
+    }
+
+    fn signal(&mut self, sig: &Signal) {
+        match sig {
+            Signal::Input(name) => {
+                self.u8(0);
+                self.bytes(name.as_bytes());
+            }
+            Signal::Witness(name) => {
+                self.u8(1);
+                self.bytes(name.as_bytes());
+            }
+            Signal::Output(name) => {
+                self.u8(2);
+                self.bytes(name.as_bytes());
+            }
+            Signal::Constant(value) => {
+                self.u8(3);
+                self.value(*value);
+            }
+            Signal::Linear(lc) => {
+                self.u8(4);
+                self.u32(lc.terms.len());
+                for (sig, coeff) in &lc.terms {
+                    self.signal(sig);
+                    self.value(*coeff);
+                }
+                self.value(lc.constant);
+            }
+        }
+    }
+
+    fn constraint(&mut self, c: &Constraint) {
+        let tag = match &c.operation {
+            Operation::Add => 0,
+            Operation::Mul => 1,
+            Operation::Sub => 2,
+            Operation::Eq => 3,
+            Operation::Select(_) => 4,
+            Operation::Mux(_) => 5,
+            Operation::Custom(..) => 6,
+            Operation::Lookup(..) => 7,
+        };
+        self.u8(tag);
+        self.signal(&c.left);
+        self.signal(&c.right);
+        self.signal(&c.output);
+        match &c.operation {
+            Operation::Select(cond) => self.signal(cond),
+            Operation::Mux(options) => self.signals(options),
+            Operation::Custom(gate, inputs) => {
+                self.gate(gate);
+                self.signals(inputs);
+            }
+            Operation::Lookup(table, inputs) => {
+                self.table(table);
+                self.signals(inputs);
+            }
+            _ => {}
+        }
+    }
+
+    fn table(&mut self, table: &Table) {
+        self.bytes(table.name.as_bytes());
+        self.u32(table.columns.len());
+        for column in &table.columns {
+            self.bytes(column.as_bytes());
+        }
+        match &table.kind {
+            TableKind::Range(bits) | TableKind::Xor(bits) | TableKind::And(bits) => {
+                self.u8(match table.kind {
+                    TableKind::Range(_) => 0,
+                    TableKind::Xor(_) => 1,
+                    _ => 2,
+                });
+                self.u32(*bits as usize);
+            }
+            TableKind::Rows(rows) => {
+                self.u8(3);
+                self.u32(rows.len());
+                for value in rows.iter().flatten() {
+                    self.value(*value);
+                }
+            }
+        }
+    }
+
+    fn gate(&mut self, gate: &Gate) {
+        self.bytes(gate.name.as_bytes());
+        self.u32(gate.wires.len());
+        for wire in &gate.wires {
+            self.bytes(wire.as_bytes());
+        }
+        self.u32(gate.polynomial.terms.len());
+        for term in &gate.polynomial.terms {
+            self.value(term.coeff);
+            for power in &term.powers {
+                self.u32(*power as usize);
+            }
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn header(&mut self, kind: u8) -> Result<(), DecodeError> {
+        if self.take(MAGIC.len()).map_err(|_| DecodeError::BadMagic)? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = self.u8()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let got = self.u8()?;
+        if got != kind {
+            return Err(DecodeError::WrongKind {
+                expected: kind,
+                got,
+            });
+        }
+        let prime = self.u128()?;
+        if prime != PRIME {
+            return Err(DecodeError::FieldMismatch {
+                expected: PRIME,
+                got: prime,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads a section whose payload `read` must consume exactly
+    fn section<T>(
+        &mut self,
+        tag: u8,
+        read: impl FnOnce(&mut Decoder<'a>) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        let got = self.u8()?;
+        if got != tag {
+            return Err(DecodeError::MissingSection { expected: tag, got });
+        }
+        let mut payload = Decoder::new(self.bytes()?);
+        let value = read(&mut payload)?;
+        if payload.pos != payload.bytes.len() {
+            return Err(DecodeError::SectionLength { section: tag });
+        }
+        Ok(value)
+    }
+
+    fn finish(self) -> Result<(), DecodeError> {
+        match self.remaining() {
+            0 => Ok(()),
+            n => Err(DecodeError::TrailingBytes(n)),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<usize, DecodeError> {
+        let bytes = self.take(4)?.try_into().expect("took 4 bytes");
+        Ok(u32::from_le_bytes(bytes) as usize)
+    }
+
+    fn u128(&mut self) -> Result<u128, DecodeError> {
+        let bytes = self.take(16)?.try_into().expect("took 16 bytes");
+        Ok(u128::from_le_bytes(bytes))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.u32()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    fn element(&mut self) -> Result<FieldElement, DecodeError> {
+        let mut bytes = [0u8; 16];
+        bytes[..element_width()].copy_from_slice(self.take(element_width())?);
+        let value = u128::from_le_bytes(bytes);
+        if value >= PRIME {
+            return Err(DecodeError::NonCanonicalElement(value));
+        }
+        Ok(FieldElement::new(value, PRIME))
+    }
+
+    fn sorted<V>(
+        &mut self,
+        mut read: impl FnMut(&mut Decoder<'a>) -> Result<V, DecodeError>,
+    ) -> Result<HashMap<String, V>, DecodeError> {
+        let count = self.u32()?;
+        let mut map = HashMap::new();
+        let mut previous: Option<String> = None;
+        for _ in 0..count {
+            let name = self.string()?;
+            if previous.as_ref().is_some_and(|p| *p >= name) {
+                return Err(DecodeError::UnsortedName(name));
+            }
+            let value = read(self)?;
+            map.insert(name.clone(), value);
+            previous = Some(name);
+        }
+        Ok(map)
+    }
+
+    fn signals(&mut self) -> Result<Vec<Signal>, DecodeError> {
+        (0..self.u32()?).map(|_| self.signal()).collect()
+    }
+
+    fn signal(&mut self) -> Result<Signal, DecodeError> {
+        match self.u8()? {
+            0 => Ok(Signal::Input(self.string()?.into())),
+            1 => Ok(Signal::Witness(self.string()?.into())),
+            2 => Ok(Signal::Output(self.string()?.into())),
+            3 => Ok(Signal::Constant(self.element()?.value)),
+            4 => {
+                let mut terms: Vec<(Signal, u128)> = Vec::new();
+                for _ in 0..self.u32()? {
+                    let sig = match self.signal()? {
+                        Signal::Constant(_) | Signal::Linear(_) => {
+                            return Err(DecodeError::InvalidTag(4))
+                        }
+                        sig => sig,
+                    };
+                    let coeff = self.element()?.value;
+                    let repeated = terms.iter().any(|(s, _)| s.name() == sig.name());
+                    if coeff == 0 || repeated {
+                        return Err(DecodeError::NonCanonicalElement(coeff));
+                    }
+                    terms.push((sig, coeff));
+                }
+                let constant = self.element()?.value;
+                Ok(Signal::Linear(LinearCombination { terms, constant }))
+            }
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+
+    fn gate(&mut self) -> Result<Gate, DecodeError> {
+        let name = self.string()?;
+        let wires = (0..self.u32()?)
+            .map(|_| self.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut terms = Vec::new();
+        for _ in 0..self.u32()? {
+            let coeff = self.element()?.value;
+            let powers = (0..wires.len())
+                .map(|_| Ok(self.u32()? as u32))
+                .collect::<Result<Vec<_>, _>>()?;
+            terms.push(Monomial { coeff, powers });
+        }
+        // checked with the rest of the circuit
+        let polynomial = Polynomial {
+            wires: wires.len(),
+            terms,
+        };
+        Ok(Gate {
+            name,
+            wires,
+            polynomial,
+        })
+    }
+
+    fn table(&mut self) -> Result<Table, DecodeError> {
+        let name = self.string()?;
+        let columns = (0..self.u32()?)
+            .map(|_| self.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let kind = match self.u8()? {
+            tag @ 0..=2 => {
+                let bits = self.u32()? as u32;
+                match tag {
+                    0 => TableKind::Range(bits),
+                    1 => TableKind::Xor(bits),
+                    _ => TableKind::And(bits),
+                }
+            }
+            3 => {
+                if columns.is_empty() {
+                    return Err(DecodeError::InvalidCircuit(format!(
+                        "table `{}` has no columns",
+                        name
+                    )));
+                }
+                // every row takes bytes, so a count the input can't hold is
+                // refused before anything is allocated for it
+                let count = self.u32()?;
+                if count > self.remaining() / (element_width() * columns.len()) {
+                    return Err(DecodeError::UnexpectedEnd);
+                }
+                let mut rows = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let row = (0..columns.len())
+                        .map(|_| Ok(self.element()?.value))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    rows.push(row);
+                }
+                TableKind::Rows(rows)
+            }
+            tag => return Err(DecodeError::InvalidTag(tag)),
+        };
+        // checked with the rest of the circuit
+        Ok(Table {
+            name,
+            columns,
+            kind,
+        })
+    }
+
+    fn constraint(&mut self) -> Result<Constraint, DecodeError> {
+        let tag = self.u8()?;
+        let (left, right, output) = (self.signal()?, self.signal()?, self.signal()?);
+        let operation = match tag {
+            0 => Operation::Add,
+            1 => Operation::Mul,
+            2 => Operation::Sub,
+            3 => Operation::Eq,
+            4 => Operation::Select(self.signal()?),
+            5 => Operation::Mux(self.signals()?),
+            6 => Operation::Custom(self.gate()?, self.signals()?),
+            7 => Operation::Lookup(self.table()?, self.signals()?),
+            tag => return Err(DecodeError::InvalidTag(tag)),
+        };
+        Ok(Constraint {
+            left,
+            right,
+            output,
+            operation,
+        })
+    }
+}
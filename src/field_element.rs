@@ -44,4 +44,82 @@ impl FieldElement {
         assert_eq!(self.prime, other.prime, "Mismatched primes!");
         self.value == other.value
     }
+
+    /// Square-and-multiply exponentiation
+    pub fn pow(&self, mut exp: u128) -> Self {
+        let mut base = self.clone();
+        let mut result = FieldElement::new(1, self.prime);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            base = base.mul(&base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Multiplicative inverse by Fermat's little theorem, `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        if self.value == 0 {
+            return None;
+        }
+        Some(self.pow(self.prime - 2))
+    }
+
+    /// A square root by Tonelli-Shanks, `None` if the value is not a square.
+    /// The other root is its negation.
+    pub fn sqrt(&self) -> Option<Self> {
+        let p = self.prime;
+        let zero = FieldElement::new(0, p);
+        let one = FieldElement::new(1, p);
+        if self.value == 0 {
+            return Some(zero);
+        }
+        if p == 2 {
+            return Some(self.clone());
+        }
+        // Euler's criterion
+        if !self.pow((p - 1) / 2).equals(&one) {
+            return None;
+        }
+
+        // p - 1 = q * 2^s with q odd
+        let mut q = p - 1;
+        let mut s = 0;
+        while q.is_multiple_of(2) {
+            q /= 2;
+            s += 1;
+        }
+        let mut z = FieldElement::new(2, p);
+        while z.pow((p - 1) / 2).equals(&one) {
+            z = z.add(&one);
+        }
+
+        let mut m = s;
+        let mut c = z.pow(q);
+        let mut t = self.pow(q);
+        let mut r = self.pow(q.div_ceil(2));
+        while !t.equals(&one) {
+            let mut i = 0;
+            let mut t2i = t.clone();
+            while !t2i.equals(&one) {
+                t2i = t2i.mul(&t2i);
+                i += 1;
+            }
+            let b = c.pow(1 << (m - i - 1));
+            m = i;
+            c = b.mul(&b);
+            t = t.mul(&c);
+            r = r.mul(&b);
+        }
+        Some(r)
+    }
+
+    /// The lowest `n` bits of the value, least significant first
+    pub fn to_bits(&self, n: usize) -> Vec<Self> {
+        (0..n)
+            .map(|i| FieldElement::new((self.value >> i) & 1, self.prime))
+            .collect()
+    }
 }
@@ -0,0 +1,283 @@
+//! Custom gates: polynomial identities over a fixed number of wires, as PLONKish
+//! backends define them, e.g. `x^5` for a Poseidon S-box or `a*b + c*d - e`.
+//!
+//! A constraint using a gate assigns the value of the polynomial at its inputs to
+//! its output. With the output `Signal::Constant(0)` it is the identity
+//! `p(inputs) = 0` instead.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{field_element::FieldElement, text, text::ParseError, PRIME};
+
+/// A coefficient times every wire raised to a power
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Monomial {
+    pub coeff: u128,
+    /// The exponent of each wire, in wire order
+    pub powers: Vec<u32>,
+}
+
+impl Monomial {
+    /// The sum of the powers, `None` if it overflows
+    pub(crate) fn checked_degree(&self) -> Option<u32> {
+        self.powers
+            .iter()
+            .try_fold(0u32, |sum, p| sum.checked_add(*p))
+    }
+
+    pub fn degree(&self) -> u32 {
+        self.checked_degree().expect("monomial degree overflows")
+    }
+}
+
+/// A polynomial over `wires` numbered wires. Coefficients are reduced modulo
+/// `PRIME` and terms are merged and sorted, highest degree first, so equal
+/// polynomials compare equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polynomial {
+    pub wires: usize,
+    pub terms: Vec<Monomial>,
+}
+
+impl Polynomial {
+    pub fn constant(wires: usize, value: u128) -> Self {
+        Self::normalized(
+            wires,
+            vec![Monomial {
+                coeff: value,
+                powers: vec![0; wires],
+            }],
+        )
+    }
+
+    /// The value of wire `index`
+    pub fn wire(wires: usize, index: usize) -> Self {
+        assert!(
+            index < wires,
+            "wire {} of a {}-wire polynomial",
+            index,
+            wires
+        );
+        let mut powers = vec![0; wires];
+        powers[index] = 1;
+        Self::normalized(wires, vec![Monomial { coeff: 1, powers }])
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let terms = self.terms.iter().chain(&other.terms).cloned().collect();
+        Self::normalized(self.wires, terms)
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.scale(PRIME - 1))
+    }
+
+    pub fn scale(&self, factor: u128) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|t| Monomial {
+                coeff: t.coeff * (factor % PRIME) % PRIME,
+                powers: t.powers.clone(),
+            })
+            .collect();
+        Self::normalized(self.wires, terms)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut terms = Vec::with_capacity(self.terms.len() * other.terms.len());
+        for a in &self.terms {
+            for b in &other.terms {
+                terms.push(Monomial {
+                    coeff: a.coeff * b.coeff % PRIME,
+                    powers: a
+                        .powers
+                        .iter()
+                        .zip(&b.powers)
+                        .map(|(x, y)| x.checked_add(*y).expect("wire power overflows"))
+                        .collect(),
+                });
+            }
+        }
+        Self::normalized(self.wires, terms)
+    }
+
+    /// By square and multiply
+    pub fn pow(&self, exponent: u32) -> Self {
+        self.bounded_pow(exponent, usize::MAX)
+            .expect("no bound on the terms")
+    }
+
+    /// `mul`, or `None` if the product has more than `max_terms` terms. Both
+    /// factors are expected to be within the bound already.
+    pub(crate) fn bounded_mul(&self, other: &Self, max_terms: usize) -> Option<Self> {
+        let product = self.mul(other);
+        (product.terms.len() <= max_terms).then_some(product)
+    }
+
+    /// `pow`, or `None` as soon as a power on the way has more than
+    /// `max_terms` terms
+    pub(crate) fn bounded_pow(&self, exponent: u32, max_terms: usize) -> Option<Self> {
+        let mut result = Self::constant(self.wires, 1);
+        for bit in (0..32 - exponent.leading_zeros()).rev() {
+            result = result.bounded_mul(&result, max_terms)?;
+            if exponent >> bit & 1 == 1 {
+                result = result.bounded_mul(self, max_terms)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// The highest degree of a term, 0 for constants
+    pub fn degree(&self) -> u32 {
+        self.terms.iter().map(Monomial::degree).max().unwrap_or(0)
+    }
+
+    /// `values` holds one element per wire
+    pub fn evaluate(&self, values: &[FieldElement]) -> FieldElement {
+        assert_eq!(values.len(), self.wires, "one value per wire");
+        let mut sum = FieldElement::new(0, PRIME);
+        for term in &self.terms {
+            let mut product = FieldElement::new(term.coeff, PRIME);
+            for (value, power) in values.iter().zip(&term.powers) {
+                product = product.mul(&value.pow(*power as u128));
+            }
+            sum = sum.add(&product);
+        }
+        sum
+    }
+
+    /// Whether the terms are merged, sorted and reduced, as `normalized` leaves them
+    pub fn is_normalized(&self) -> bool {
+        *self == Self::normalized(self.wires, self.terms.clone())
+    }
+
+    fn normalized(wires: usize, terms: Vec<Monomial>) -> Self {
+        let mut coeffs: BTreeMap<Vec<u32>, u128> = BTreeMap::new();
+        for term in terms {
+            let coeff = coeffs.entry(term.powers).or_default();
+            *coeff = (*coeff + term.coeff % PRIME) % PRIME;
+        }
+        let mut merged: Vec<Monomial> = coeffs
+            .into_iter()
+            .filter(|(powers, coeff)| *coeff != 0 && powers.len() == wires)
+            .map(|(powers, coeff)| Monomial { coeff, powers })
+            .collect();
+        merged.sort_by(|a, b| {
+            b.degree()
+                .cmp(&a.degree())
+                .then_with(|| b.powers.cmp(&a.powers))
+        });
+        Self {
+            wires,
+            terms: merged,
+        }
+    }
+}
+
+/// A named polynomial over named wires, used by `Constraint::custom`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gate {
+    pub name: String,
+    pub wires: Vec<String>,
+    pub polynomial: Polynomial,
+}
+
+impl Gate {
+    /// The highest degree a gate may have. Every backend multiplies terms out,
+    /// so larger degrees would only be a way to make it loop for long.
+    pub const MAX_DEGREE: u32 = 64;
+
+    /// The most terms a gate may have once multiplied out, for the same reason:
+    /// `(a + b + c + d)^64` alone has tens of thousands
+    pub const MAX_TERMS: usize = 256;
+
+    /// Reads the polynomial from `+`, `-`, `*`, `^` with a constant exponent,
+    /// numbers, parentheses and the wire names, e.g.
+    /// `Gate::new("mix", &["a", "b", "c", "d", "e"], "a*b + c*d - e")`, of degree at
+    /// most `MAX_DEGREE` and with at most `MAX_TERMS` terms
+    pub fn new(name: &str, wires: &[&str], expression: &str) -> Result<Gate, ParseError> {
+        let wires: Vec<String> = wires.iter().map(|w| w.to_string()).collect();
+        let polynomial = text::parse_polynomial(&wires, expression)?;
+        Ok(Gate {
+            name: name.to_string(),
+            wires,
+            polynomial,
+        })
+    }
+
+    /// A gate from a polynomial built directly, with one wire name per wire
+    pub fn from_polynomial(name: &str, wires: &[&str], polynomial: Polynomial) -> Gate {
+        assert_eq!(
+            wires.len(),
+            polynomial.wires,
+            "gate `{}` needs one name per wire",
+            name
+        );
+        assert!(
+            polynomial.degree() <= Self::MAX_DEGREE,
+            "gate `{}` has degree above {}",
+            name,
+            Self::MAX_DEGREE
+        );
+        assert!(
+            polynomial.terms.len() <= Self::MAX_TERMS,
+            "gate `{}` has more than {} terms",
+            name,
+            Self::MAX_TERMS
+        );
+        Gate {
+            name: name.to_string(),
+            wires: wires.iter().map(|w| w.to_string()).collect(),
+            polynomial,
+        }
+    }
+
+    pub fn evaluate(&self, values: &[FieldElement]) -> FieldElement {
+        self.polynomial.evaluate(values)
+    }
+
+    /// Why `new` or `from_polynomial` could not have built the gate, if they
+    /// couldn't
+    pub(crate) fn invalid(&self) -> Option<String> {
+        if self.wires.len() != self.polynomial.wires {
+            return Some(format!("gate `{}` needs one name per wire", self.name));
+        }
+        let too_high = self
+            .polynomial
+            .terms
+            .iter()
+            .any(|t| t.checked_degree().is_none_or(|d| d > Self::MAX_DEGREE));
+        if too_high {
+            return Some(format!(
+                "gate `{}` has degree above {}",
+                self.name,
+                Self::MAX_DEGREE
+            ));
+        }
+        if self.polynomial.terms.len() > Self::MAX_TERMS {
+            return Some(format!(
+                "gate `{}` has more than {} terms",
+                self.name,
+                Self::MAX_TERMS
+            ));
+        }
+        if !self.polynomial.is_normalized() {
+            return Some(format!(
+                "the terms of gate `{}` are not reduced and sorted",
+                self.name
+            ));
+        }
+        None
+    }
+}
+
+/// `name(a, b) = a*b + 1`, as in the `gates` section of the `.ghb` format
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", text::gate_text(self))
+    }
+}
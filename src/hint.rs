@@ -0,0 +1,62 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::field_element::FieldElement;
+
+/// Computes output values from input values outside of the constraint system
+pub type HintFn = Arc<dyn Fn(&[FieldElement]) -> Vec<FieldElement> + Send + Sync>;
+
+/// A non-deterministic value supplier, e.g. an inverse or a bit decomposition.
+/// The circuit only checks the values, the hint is how the prover finds them.
+#[derive(Clone)]
+pub struct Hint {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub function: HintFn,
+}
+
+impl Hint {
+    pub fn new<F>(outputs: &[&str], inputs: &[&str], function: F) -> Self
+    where
+        F: Fn(&[FieldElement]) -> Vec<FieldElement> + Send + Sync + 'static,
+    {
+        Self {
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+            function: Arc::new(function),
+        }
+    }
+}
+
+impl fmt::Debug for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hint")
+            .field("inputs", &self.inputs)
+            .field("outputs", &self.outputs)
+            .finish_non_exhaustive()
+    }
+}
+
+/// What a `hint!` body may evaluate to
+pub trait IntoHintValues {
+    fn into_hint_values(self) -> Vec<FieldElement>;
+}
+
+impl IntoHintValues for FieldElement {
+    fn into_hint_values(self) -> Vec<FieldElement> {
+        vec![self]
+    }
+}
+
+/// `None` produces no value, which witness generation reports as an error
+impl IntoHintValues for Option<FieldElement> {
+    fn into_hint_values(self) -> Vec<FieldElement> {
+        self.into_iter().collect()
+    }
+}
+
+impl IntoHintValues for Vec<FieldElement> {
+    fn into_hint_values(self) -> Vec<FieldElement> {
+        self
+    }
+}
@@ -1,26 +1,77 @@
 #![allow(unused)]
 
+// lets `#[circuit]` refer to `::ghayb` from inside this crate too
+extern crate self as ghayb;
+
+mod analysis;
+mod circom;
 mod circuit;
 mod commitment;
 mod constraint;
+mod dependency;
+mod dot;
 mod dsl;
+mod encoding;
 mod field_element;
+mod gate;
+mod hint;
+mod linear;
+mod lookup;
+mod optimize;
+mod poseidon;
 mod proof;
 mod prover;
+mod r1cs;
+#[cfg(feature = "serde")]
+mod serialization;
 mod signal;
+mod stats;
+mod sym;
+mod template;
+mod text;
+mod trace;
 mod verifier;
 mod witness;
+mod witness_generator;
 
+pub use analysis::{UnderconstrainedKind, UnderconstrainedSignal};
+pub use circom::{
+    read_wtns, witness_json, write_wtns, CircomError, LinearTerms, R1cs, R1csConstraint,
+};
 pub use circuit::Circuit;
 pub use commitment::Commitment;
-pub use constraint::{Constraint, Operation};
+pub use constraint::{Callable, Constraint, Operation};
+pub use dependency::DependencyCycle;
 pub use dsl::*;
+pub use encoding::DecodeError;
 pub use field_element::FieldElement;
+pub use gate::{Gate, Monomial, Polynomial};
+pub use hint::{Hint, HintFn, IntoHintValues};
+pub use linear::LinearCombination;
+pub use lookup::{LookupArgument, Table, TableKind};
+pub use optimize::OptimizationReport;
+pub use poseidon::Poseidon;
 pub use proof::Proof;
 pub use prover::Prover;
-pub use signal::Signal;
+pub use r1cs::R1csExport;
+pub use signal::{Signal, SignalName};
+pub use stats::{CircuitStats, OperationCounts};
+pub use sym::{
+    le_bits, Bool, CircuitBuilder, CircuitSym, ConstraintCounter, Counted, Evaluator, Field,
+    Partial, PartialEvaluator, PrettyPrinter, Typed, UInt, UInt32, UInt64, WrappingUInt,
+};
+pub use template::{Template, TemplateFn};
+pub use text::ParseError;
+pub use trace::{Failure, Trace, TraceStep};
 pub use verifier::Verifier;
-pub use witness::Witness;
+pub use witness::{Witness, WitnessValue};
+pub use witness_generator::{WitnessError, WitnessGenerator};
+
+/// Procedural macros. `#[circuit]` lives here because `ghayb::circuit` is the
+/// `circuit!` macro.
+pub mod macros {
+    pub use ghayb_macros::circuit;
+}
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::{field_element::FieldElement, signal::Signal, PRIME};
+
+/// `c1 * s1 + c2 * s2 + ... + k` over named signals, with every coefficient
+/// reduced modulo `PRIME`. As `Signal::Linear` it is an operand that costs no
+/// constraint of its own.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearCombination {
+    /// Distinct named signals with non-zero coefficients, in the order they were added
+    pub terms: Vec<(Signal, u128)>,
+    pub constant: u128,
+}
+
+impl LinearCombination {
+    pub fn constant(value: u128) -> Self {
+        Self {
+            terms: Vec::new(),
+            constant: value % PRIME,
+        }
+    }
+
+    /// The value of any signal: constants and combinations stay what they are,
+    /// a named signal gets the coefficient 1
+    pub fn from_signal(sig: &Signal) -> Self {
+        match sig {
+            Signal::Constant(value) => Self::constant(*value),
+            Signal::Linear(lc) => lc.clone(),
+            named => Self::default().plus_term(named, 1),
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut sum = self.clone();
+        for (sig, coeff) in &other.terms {
+            sum = sum.plus_term(sig, *coeff);
+        }
+        sum.constant = (sum.constant + other.constant) % PRIME;
+        sum
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.scale(PRIME - 1))
+    }
+
+    pub fn scale(&self, factor: u128) -> Self {
+        let factor = factor % PRIME;
+        let mut scaled = Self::constant(self.constant * factor);
+        for (sig, coeff) in &self.terms {
+            scaled = scaled.plus_term(sig, coeff * factor);
+        }
+        scaled
+    }
+
+    /// The constant, if there are no terms
+    pub fn as_constant(&self) -> Option<u128> {
+        self.terms.is_empty().then_some(self.constant)
+    }
+
+    /// `None` while a term has no value
+    pub fn evaluate(&self, values: &HashMap<String, FieldElement>) -> Option<FieldElement> {
+        let mut sum = FieldElement::new(self.constant, PRIME);
+        for (sig, coeff) in &self.terms {
+            let value = values.get(sig.name()?)?;
+            sum = sum.add(&value.mul(&FieldElement::new(*coeff, PRIME)));
+        }
+        Some(sum)
+    }
+
+    /// Adds `coeff * sig` for a named signal, merging it with an existing term
+    fn plus_term(mut self, sig: &Signal, coeff: u128) -> Self {
+        let coeff = coeff % PRIME;
+        match self.terms.iter().position(|(s, _)| s.name() == sig.name()) {
+            Some(i) => {
+                let merged = (self.terms[i].1 + coeff) % PRIME;
+                if merged == 0 {
+                    self.terms.remove(i);
+                } else {
+                    self.terms[i].1 = merged;
+                }
+            }
+            None if coeff != 0 => self.terms.push((sig.clone(), coeff)),
+            None => {}
+        }
+        self
+    }
+}
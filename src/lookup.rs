@@ -0,0 +1,382 @@
+//! Lookup tables: fixed relations a constraint asserts a tuple of signals
+//! belongs to, instead of decomposing it into bits, e.g. an 8-bit XOR or a byte
+//! range.
+//!
+//! The reference prover and verifier check the tuples against the table
+//! directly. `LookupArgument` is the logUp argument a polynomial backend proves
+//! them with: for random challenges `alpha` and `beta`, with every tuple `t`
+//! compressed to `t_0 + alpha * t_1 + alpha^2 * t_2 + ...`,
+//!
+//! ```text
+//! sum over looked-up tuples f:  1 / (beta - f)
+//!     = sum over table rows t:  m_t / (beta - t)
+//! ```
+//!
+//! where `m_t` counts how often row `t` is looked up. The challenges come from
+//! hashing the tuples and multiplicities (Fiat-Shamir). With a field as small
+//! as `PRIME` a cheating prover finds matching challenges quickly, so the
+//! argument shows the shape of the check rather than adding soundness.
+
+use std::collections::{BTreeMap, HashMap};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    field_element::FieldElement,
+    witness::Witness,
+    witness_generator::{lookup, WitnessError, WitnessGenerator},
+    PRIME,
+};
+
+/// How the rows of a table are given
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableKind {
+    /// `(v)` for every `v < 2^bits`
+    Range(u32),
+    /// `(a, b, a ^ b)` for every `a, b < 2^bits`
+    Xor(u32),
+    /// `(a, b, a & b)` for every `a, b < 2^bits`
+    And(u32),
+    /// Listed row by row
+    Rows(Vec<Vec<u128>>),
+}
+
+/// A named table with named columns, used by `Constraint::lookup`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub kind: TableKind,
+}
+
+impl Table {
+    /// A table of the given rows, one value per column in each
+    pub fn new(name: &str, columns: &[&str], rows: Vec<Vec<u128>>) -> Table {
+        Self::with_kind(name, columns, TableKind::Rows(rows))
+    }
+
+    /// Every value below `2^bits`, e.g. `Table::range("byte", 8)`
+    pub fn range(name: &str, bits: u32) -> Table {
+        Self::with_kind(name, &["v"], TableKind::Range(bits))
+    }
+
+    /// `c = a ^ b` for `a` and `b` below `2^bits`
+    pub fn xor(name: &str, bits: u32) -> Table {
+        Self::with_kind(name, &["a", "b", "c"], TableKind::Xor(bits))
+    }
+
+    /// `c = a & b` for `a` and `b` below `2^bits`
+    pub fn and(name: &str, bits: u32) -> Table {
+        Self::with_kind(name, &["a", "b", "c"], TableKind::And(bits))
+    }
+
+    /// Panics if the columns don't fit the kind or its values don't fit the
+    /// field. Listed values are reduced modulo `PRIME`.
+    pub fn with_kind(name: &str, columns: &[&str], kind: TableKind) -> Table {
+        let kind = match kind {
+            TableKind::Rows(rows) => TableKind::Rows(
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(|v| v % PRIME).collect())
+                    .collect(),
+            ),
+            kind => kind,
+        };
+        let table = Table {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            kind,
+        };
+        if let Some(message) = table.invalid() {
+            panic!("{}", message);
+        }
+        table
+    }
+
+    /// Why `with_kind` would refuse the table, if it would: the columns don't
+    /// fit the kind or the values don't fit the field
+    pub(crate) fn invalid(&self) -> Option<String> {
+        let name = &self.name;
+        match &self.kind {
+            TableKind::Range(bits) | TableKind::Xor(bits) | TableKind::And(bits) => {
+                let width = if let TableKind::Range(_) = self.kind {
+                    1
+                } else {
+                    3
+                };
+                if self.width() != width {
+                    return Some(format!("table `{}` has {} columns", name, width));
+                }
+                if *bits >= 64 || 1u128 << bits > PRIME {
+                    return Some(format!(
+                        "table `{}` has values of {} bits, more than the field holds",
+                        name, bits
+                    ));
+                }
+            }
+            TableKind::Rows(rows) => {
+                if self.columns.is_empty() {
+                    return Some(format!("table `{}` has no columns", name));
+                }
+                for row in rows {
+                    if row.len() != self.width() {
+                        return Some(format!("table `{}` has {} columns", name, self.width()));
+                    }
+                    if let Some(value) = row.iter().find(|v| **v >= PRIME) {
+                        return Some(format!(
+                            "table `{}` has the value {}, which is not reduced",
+                            name, value
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// The number of rows
+    pub fn len(&self) -> usize {
+        match &self.kind {
+            TableKind::Range(bits) => 1 << bits,
+            TableKind::Xor(bits) | TableKind::And(bits) => 1 << (2 * bits),
+            TableKind::Rows(rows) => rows.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Row `index`, below `len()`
+    pub fn row(&self, index: usize) -> Vec<u128> {
+        let index = index as u128;
+        match &self.kind {
+            TableKind::Range(_) => vec![index],
+            TableKind::Xor(bits) | TableKind::And(bits) => {
+                let (a, b) = (index >> bits, index & ((1 << bits) - 1));
+                let c = if let TableKind::Xor(_) = self.kind {
+                    a ^ b
+                } else {
+                    a & b
+                };
+                vec![a, b, c]
+            }
+            TableKind::Rows(rows) => rows[index as usize].clone(),
+        }
+    }
+
+    /// The index of the first row equal to `values`
+    pub fn position(&self, values: &[u128]) -> Option<usize> {
+        if values.len() != self.width() {
+            return None;
+        }
+        match &self.kind {
+            TableKind::Range(bits) => (values[0] < 1 << bits).then_some(values[0] as usize),
+            TableKind::Xor(bits) | TableKind::And(bits) => {
+                let index = ((values[0] << bits) | values[1]) as usize;
+                let fits = values[0] < 1 << bits && values[1] < 1 << bits;
+                (fits && self.row(index) == values).then_some(index)
+            }
+            TableKind::Rows(rows) => rows.iter().position(|row| row == values),
+        }
+    }
+
+    /// The last column of the first row starting with `keys`
+    pub fn lookup(&self, keys: &[u128]) -> Option<u128> {
+        if keys.len() + 1 != self.width() {
+            return None;
+        }
+        match &self.kind {
+            TableKind::Xor(bits) | TableKind::And(bits) => {
+                if keys.iter().any(|k| *k >= 1 << bits) {
+                    return None;
+                }
+                Some(self.row(((keys[0] << bits) | keys[1]) as usize)[2])
+            }
+            TableKind::Range(_) | TableKind::Rows(_) => (0..self.len())
+                .map(|i| self.row(i))
+                .find(|row| row.starts_with(keys))
+                .map(|row| row[keys.len()]),
+        }
+    }
+}
+
+impl Circuit {
+    /// The tables the constraints look up, in order of first use
+    pub fn tables(&self) -> Vec<&Table> {
+        let mut tables: Vec<&Table> = Vec::new();
+        for c in &self.constraints {
+            if let Operation::Lookup(table, _) = &c.operation {
+                if !tables.contains(&table) {
+                    tables.push(table);
+                }
+            }
+        }
+        tables
+    }
+}
+
+/// The values a lookup constraint asserts are a row: its inputs, followed by
+/// its output unless the inputs fill every column
+pub(crate) fn looked_up(
+    constraint: &Constraint,
+    values: &HashMap<String, FieldElement>,
+) -> Option<Vec<u128>> {
+    let Operation::Lookup(table, inputs) = &constraint.operation else {
+        return None;
+    };
+    let mut tuple = inputs
+        .iter()
+        .map(|i| lookup(i, values).map(|v| v.value))
+        .collect::<Option<Vec<_>>>()?;
+    if tuple.len() < table.width() {
+        tuple.push(lookup(&constraint.output, values)?.value);
+    }
+    Some(tuple)
+}
+
+/// A logUp argument that every tuple a circuit looks up in one table is a row
+/// of it, see the module docs
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LookupArgument {
+    pub table: String,
+    /// `(row, count)` for every row looked up at least once, by row
+    pub multiplicities: Vec<(usize, u128)>,
+}
+
+impl LookupArgument {
+    /// One argument per table of the circuit, from what the prover knows
+    pub fn prove(circuit: &Circuit, witness: &Witness) -> Result<Vec<Self>, WitnessError> {
+        let values = WitnessGenerator::new(circuit).complete(witness)?.values;
+        Ok(circuit
+            .tables()
+            .into_iter()
+            .map(|table| {
+                let mut counts: BTreeMap<usize, u128> = BTreeMap::new();
+                for tuple in tuples(circuit, &table.name, &values) {
+                    let row = table
+                        .position(&tuple)
+                        .expect("witness generation checks the lookups");
+                    *counts.entry(row).or_default() += 1;
+                }
+                LookupArgument {
+                    table: table.name.clone(),
+                    multiplicities: counts.into_iter().collect(),
+                }
+            })
+            .collect())
+    }
+
+    /// Checks the argument for the tuples looked up with the complete
+    /// assignment `values`, which a real backend would only hold committed
+    pub fn verify(&self, circuit: &Circuit, values: &HashMap<String, FieldElement>) -> bool {
+        let Some(table) = circuit.tables().into_iter().find(|t| t.name == self.table) else {
+            return false;
+        };
+        let sorted = self.multiplicities.windows(2).all(|w| w[0].0 < w[1].0);
+        let valid = self
+            .multiplicities
+            .iter()
+            .all(|(row, count)| *row < table.len() && *count != 0 && *count < PRIME);
+        if !sorted || !valid {
+            return false;
+        }
+
+        let looked_up = tuples(circuit, &table.name, values);
+        let rows: Vec<(Vec<u128>, u128)> = self
+            .multiplicities
+            .iter()
+            .map(|(row, count)| (table.row(*row), *count))
+            .collect();
+        let Some((alpha, beta)) = challenges(&table.name, &looked_up, &self.multiplicities, &rows)
+        else {
+            return false;
+        };
+        let inverse = |tuple: &[u128]| {
+            beta.sub(&compress(tuple, &alpha))
+                .inverse()
+                .expect("challenges avoid every tuple")
+        };
+
+        let zero = FieldElement::new(0, PRIME);
+        let lhs = looked_up
+            .iter()
+            .fold(zero.clone(), |sum, tuple| sum.add(&inverse(tuple)));
+        let rhs = rows.iter().fold(zero, |sum, (row, count)| {
+            sum.add(&inverse(row).mul(&FieldElement::new(*count, PRIME)))
+        });
+        lhs.equals(&rhs)
+    }
+}
+
+/// The tuples looked up in the table named `table`, in constraint order
+fn tuples(
+    circuit: &Circuit,
+    table: &str,
+    values: &HashMap<String, FieldElement>,
+) -> Vec<Vec<u128>> {
+    circuit
+        .constraints
+        .iter()
+        .filter(|c| matches!(&c.operation, Operation::Lookup(t, _) if t.name == table))
+        .filter_map(|c| looked_up(c, values))
+        .collect()
+}
+
+/// `t_0 + alpha * t_1 + alpha^2 * t_2 + ...`
+fn compress(tuple: &[u128], alpha: &FieldElement) -> FieldElement {
+    tuple
+        .iter()
+        .rev()
+        .fold(FieldElement::new(0, PRIME), |acc, v| {
+            acc.mul(alpha).add(&FieldElement::new(*v, PRIME))
+        })
+}
+
+/// `alpha` and `beta` from hashing everything the argument is about, rehashed
+/// with a counter until `beta` differs from every compressed tuple
+fn challenges(
+    table: &str,
+    looked_up: &[Vec<u128>],
+    multiplicities: &[(usize, u128)],
+    rows: &[(Vec<u128>, u128)],
+) -> Option<(FieldElement, FieldElement)> {
+    let mut hasher = Sha256::new();
+    hasher.update(table.as_bytes());
+    for tuple in looked_up {
+        for v in tuple {
+            hasher.update(v.to_le_bytes());
+        }
+    }
+    for (row, count) in multiplicities {
+        hasher.update((*row as u64).to_le_bytes());
+        hasher.update(count.to_le_bytes());
+    }
+
+    for attempt in 0u32..256 {
+        let mut round = hasher.clone();
+        round.update(attempt.to_le_bytes());
+        let digest = round.finalize();
+        let element = |bytes: &[u8]| {
+            let value = u128::from_le_bytes(bytes.try_into().expect("16 bytes"));
+            FieldElement::new(value % PRIME, PRIME)
+        };
+        let (alpha, beta) = (element(&digest[..16]), element(&digest[16..]));
+        let clear = looked_up
+            .iter()
+            .chain(rows.iter().map(|(row, _)| row))
+            .all(|tuple| !compress(tuple, &alpha).equals(&beta));
+        if clear {
+            return Some((alpha, beta));
+        }
+    }
+    None
+}
@@ -0,0 +1,429 @@
+//! Circuit optimization. Each round runs four passes until none changes anything:
+//!
+//! - folding: constraints whose operands are all constants become constants,
+//!   and identities like `x * 1`, `x + 0` or `c ? a : b` with a constant `c`
+//!   become copies of an operand
+//! - merging: a constraint identical to an earlier one reuses its output
+//! - collapsing: `Add`, `Sub` and `Mul` by a constant form linear combinations,
+//!   so a chain of them becomes one constraint over a `Signal::Linear`
+//! - elimination: constraints that no output, assertion or hint depends on go
+//!
+//! Only private intermediate signals are replaced or removed. Inputs, outputs,
+//! signals hints read or write, and signals several constraints assign stay.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    linear::LinearCombination,
+    signal::Signal,
+    text::{gate_text, operand, table_text},
+    witness_generator::evaluate_constraint,
+    PRIME,
+};
+
+/// What `Circuit::optimize` did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationReport {
+    pub before: usize,
+    pub after: usize,
+    /// Constraints replaced by a constant or a copy of an operand
+    pub folded: usize,
+    /// Constraints identical to an earlier one
+    pub merged: usize,
+    /// Linear constraints absorbed into the linear combination of their reader
+    pub collapsed: usize,
+    /// Constraints nothing depended on
+    pub eliminated: usize,
+}
+
+impl OptimizationReport {
+    pub fn saved(&self) -> usize {
+        self.before - self.after
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} constraints ({} saved): {} folded, {} merged, {} collapsed, {} eliminated",
+            self.before,
+            self.after,
+            self.saved(),
+            self.folded,
+            self.merged,
+            self.collapsed,
+            self.eliminated
+        )
+    }
+}
+
+impl Circuit {
+    /// An equivalent circuit with fewer constraints. It accepts the same
+    /// witnesses and computes the same outputs; values of removed intermediate
+    /// signals are simply not needed any more. A circuit whose constraints form
+    /// a cycle is returned as is.
+    pub fn optimize(&self) -> (Circuit, OptimizationReport) {
+        let mut optimizer = Optimizer::new(self);
+        if self.constraint_order().is_ok() {
+            loop {
+                let before = optimizer.report.clone();
+                optimizer.fold();
+                optimizer.merge();
+                optimizer.collapse();
+                optimizer.eliminate();
+                if optimizer.report == before {
+                    break;
+                }
+            }
+        }
+        optimizer.finish()
+    }
+}
+
+struct Optimizer<'a> {
+    circuit: &'a Circuit,
+    /// The remaining constraints, with their index in `circuit.constraints`
+    constraints: Vec<(Constraint, usize)>,
+    /// Signals that must keep their name and their constraints
+    pinned: HashSet<String>,
+    report: OptimizationReport,
+}
+
+impl<'a> Optimizer<'a> {
+    fn new(circuit: &'a Circuit) -> Self {
+        let mut pinned: HashSet<String> = circuit
+            .inputs
+            .iter()
+            .chain(&circuit.outputs)
+            .filter_map(Signal::name)
+            .map(str::to_string)
+            .collect();
+        for hint in &circuit.hints {
+            pinned.extend(hint.inputs.iter().chain(&hint.outputs).cloned());
+        }
+        let mut assigned = HashSet::new();
+        for c in &circuit.constraints {
+            if let Some(name) = c.output.name() {
+                if !assigned.insert(name) || !matches!(c.output, Signal::Witness(_)) {
+                    pinned.insert(name.to_string());
+                }
+            }
+        }
+
+        Self {
+            circuit,
+            constraints: circuit.constraints.iter().cloned().zip(0..).collect(),
+            pinned,
+            report: OptimizationReport {
+                before: circuit.constraints.len(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn finish(self) -> (Circuit, OptimizationReport) {
+        let mut report = self.report;
+        report.after = self.constraints.len();
+        let (constraints, origins): (Vec<Constraint>, Vec<usize>) =
+            self.constraints.into_iter().unzip();
+
+        let mut circuit = Circuit::new(
+            self.circuit.inputs.clone(),
+            self.circuit.outputs.clone(),
+            constraints,
+        );
+        circuit.witnesses = self.circuit.witnesses.clone();
+        circuit.hints = self.circuit.hints.clone();
+        circuit.sources = origins
+            .iter()
+            .enumerate()
+            .filter_map(|(i, origin)| Some((i, self.circuit.sources.get(origin)?.clone())))
+            .collect();
+        (circuit, report)
+    }
+
+    /// Indices into `self.constraints` with producers before their readers
+    fn order(&self) -> Vec<usize> {
+        let current = Circuit::new(
+            Vec::new(),
+            Vec::new(),
+            self.constraints.iter().map(|(c, _)| c.clone()).collect(),
+        );
+        current
+            .constraint_order()
+            .expect("optimizing keeps the constraints acyclic")
+    }
+
+    /// The name of a signal this pass may replace or drop
+    fn removable(&self, sig: &Signal) -> Option<String> {
+        match sig {
+            Signal::Witness(name) if !self.pinned.contains(name.as_str()) => Some(name.to_string()),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, removed: &HashSet<usize>) {
+        let mut i = 0;
+        self.constraints.retain(|_| {
+            i += 1;
+            !removed.contains(&(i - 1))
+        });
+    }
+
+    /// Walks the constraints in order, replacing what earlier steps substituted,
+    /// and drops the constraints `step` turns into a substitution for their output
+    fn substitute_pass(
+        &mut self,
+        mut step: impl FnMut(&Self, &Constraint) -> Option<Signal>,
+    ) -> usize {
+        let mut substitutions: HashMap<String, Signal> = HashMap::new();
+        let mut removed = HashSet::new();
+        for i in self.order() {
+            let c = self.constraints[i]
+                .0
+                .map_signals(|s| resolve(&substitutions, s));
+            if let (Some(name), Some(replacement)) = (self.removable(&c.output), step(self, &c)) {
+                substitutions.insert(name, replacement);
+                removed.insert(i);
+            }
+            self.constraints[i].0 = c;
+        }
+        self.remove(&removed);
+        removed.len()
+    }
+
+    fn fold(&mut self) {
+        self.report.folded += self.substitute_pass(|_, c| folded(c));
+    }
+
+    fn merge(&mut self) {
+        let mut seen: HashMap<String, Signal> = HashMap::new();
+        self.report.merged += self.substitute_pass(|_, c| {
+            let key = key(c);
+            match seen.get(&key) {
+                Some(first) => Some(first.clone()),
+                None => {
+                    if c.output.name().is_some() {
+                        seen.insert(key, c.output.clone());
+                    }
+                    None
+                }
+            }
+        });
+    }
+
+    fn collapse(&mut self) {
+        let read_before = self.read_signals();
+        let mut linear: HashMap<String, LinearCombination> = HashMap::new();
+        let mut rewritten = HashSet::new();
+        for i in self.order() {
+            let c = &self.constraints[i].0;
+            let Some((value, expanded)) = linear_value(c, &linear) else {
+                continue;
+            };
+            if let Some(name) = self.removable(&c.output) {
+                linear.insert(name, value.clone());
+            }
+            if expanded {
+                rewritten.insert(i);
+                self.constraints[i].0 = Constraint {
+                    left: Signal::Linear(value),
+                    right: Signal::Constant(0),
+                    output: c.output.clone(),
+                    operation: Operation::Add,
+                };
+            }
+        }
+        if rewritten.is_empty() {
+            return;
+        }
+
+        // what only the rewritten constraints read is now unused; what nothing
+        // read before is left for elimination
+        let read = self.read_signals();
+        let removed: HashSet<usize> = (0..self.constraints.len())
+            .filter(|&i| {
+                self.constraints[i].0.output.name().is_some_and(|n| {
+                    linear.contains_key(n) && read_before.contains(n) && !read.contains(n)
+                })
+            })
+            .collect();
+        self.report.collapsed += removed.len();
+        self.remove(&removed);
+    }
+
+    fn eliminate(&mut self) {
+        let mut live: HashSet<usize> = self
+            .constraints
+            .iter()
+            .enumerate()
+            .filter(|(_, (c, _))| self.removable(&c.output).is_none() || can_fail(c))
+            .map(|(i, _)| i)
+            .collect();
+        loop {
+            let mut needed: HashSet<&str> = live
+                .iter()
+                .flat_map(|&i| self.constraints[i].0.operands())
+                .filter_map(Signal::name)
+                .collect();
+            for hint in &self.circuit.hints {
+                if hint.outputs.iter().any(|o| needed.contains(o.as_str())) {
+                    needed.extend(hint.inputs.iter().map(String::as_str));
+                }
+            }
+            let before = live.len();
+            for (i, (c, _)) in self.constraints.iter().enumerate() {
+                if c.output.name().is_some_and(|n| needed.contains(n)) {
+                    live.insert(i);
+                }
+            }
+            if live.len() == before {
+                break;
+            }
+        }
+
+        let dead: HashSet<usize> = (0..self.constraints.len())
+            .filter(|i| !live.contains(i))
+            .collect();
+        self.report.eliminated += dead.len();
+        self.remove(&dead);
+    }
+
+    fn read_signals(&self) -> HashSet<String> {
+        self.constraints
+            .iter()
+            .flat_map(|(c, _)| c.operands())
+            .filter_map(Signal::name)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Follows substitutions until a signal has none
+fn resolve(substitutions: &HashMap<String, Signal>, sig: &Signal) -> Signal {
+    match sig.name().and_then(|name| substitutions.get(name)) {
+        Some(replacement) => resolve(substitutions, replacement),
+        None => sig.clone(),
+    }
+}
+
+/// The value of a constant operand, reduced
+fn constant(sig: &Signal) -> Option<u128> {
+    match sig {
+        Signal::Constant(value) => Some(value % PRIME),
+        Signal::Linear(lc) => lc.as_constant(),
+        _ => None,
+    }
+}
+
+/// What the output of `c` can be replaced with, if it doesn't need a constraint
+fn folded(c: &Constraint) -> Option<Signal> {
+    if let Some(Ok(value)) = evaluate_constraint(0, c, &HashMap::new()) {
+        return Some(Signal::Constant(value.value));
+    }
+    let (left, right) = (constant(&c.left), constant(&c.right));
+    match &c.operation {
+        Operation::Add => match (left, right) {
+            (Some(0), _) => Some(c.right.clone()),
+            (_, Some(0)) => Some(c.left.clone()),
+            _ => None,
+        },
+        Operation::Sub => (right == Some(0)).then(|| c.left.clone()),
+        Operation::Mul => match (left, right) {
+            (Some(0), _) | (_, Some(0)) => Some(Signal::Constant(0)),
+            (Some(1), _) => Some(c.right.clone()),
+            (_, Some(1)) => Some(c.left.clone()),
+            _ => None,
+        },
+        Operation::Eq => {
+            let same = c.left.name().is_some() && c.left.name() == c.right.name();
+            same.then(|| c.left.clone())
+        }
+        Operation::Select(cond) => match constant(cond) {
+            Some(1) => Some(c.left.clone()),
+            Some(0) => Some(c.right.clone()),
+            _ => None,
+        },
+        Operation::Mux(options) => {
+            let index = usize::try_from(left?).ok()?;
+            options.get(index).cloned()
+        }
+        Operation::Custom(..) | Operation::Lookup(..) => None,
+    }
+}
+
+/// Whether a constraint checks something besides computing its output
+fn can_fail(c: &Constraint) -> bool {
+    matches!(
+        c.operation,
+        Operation::Eq | Operation::Select(_) | Operation::Mux(_) | Operation::Lookup(..)
+    )
+}
+
+/// Identifies what a constraint computes regardless of its output
+fn key(c: &Constraint) -> String {
+    let mut operands = [operand(&c.left), operand(&c.right)];
+    if matches!(c.operation, Operation::Add | Operation::Mul) {
+        operands.sort();
+    }
+    let extra = match &c.operation {
+        Operation::Select(cond) => operand(cond),
+        Operation::Mux(options) | Operation::Custom(_, options) | Operation::Lookup(_, options) => {
+            options.iter().map(operand).collect::<Vec<_>>().join(",")
+        }
+        _ => String::new(),
+    };
+    let tag = match &c.operation {
+        Operation::Add => "+".to_string(),
+        Operation::Sub => "-".to_string(),
+        Operation::Mul => "*".to_string(),
+        Operation::Eq => "==".to_string(),
+        Operation::Select(_) => "?".to_string(),
+        Operation::Mux(_) => "[]".to_string(),
+        Operation::Custom(gate, _) => gate_text(gate),
+        Operation::Lookup(table, _) => table_text(table),
+    };
+    format!("{} {} {}", tag, operands.join(" "), extra)
+}
+
+/// The output of a linear constraint as a combination of signals that aren't
+/// themselves collapsible, and whether that needed any expansion
+fn linear_value(
+    c: &Constraint,
+    linear: &HashMap<String, LinearCombination>,
+) -> Option<(LinearCombination, bool)> {
+    let mut expanded = false;
+    let mut expand = |sig: &Signal| {
+        let lc = LinearCombination::from_signal(sig);
+        let mut value = LinearCombination::constant(lc.constant);
+        for (term, coeff) in &lc.terms {
+            let part = match term.name().and_then(|n| linear.get(n)) {
+                Some(inner) => {
+                    expanded = true;
+                    inner.clone()
+                }
+                None => LinearCombination::from_signal(term),
+            };
+            value = value.add(&part.scale(*coeff));
+        }
+        value
+    };
+    let value = match &c.operation {
+        Operation::Add => expand(&c.left).add(&expand(&c.right)),
+        Operation::Sub => expand(&c.left).sub(&expand(&c.right)),
+        Operation::Mul => {
+            let (left, right) = (expand(&c.left), expand(&c.right));
+            match (left.as_constant(), right.as_constant()) {
+                (Some(k), _) => right.scale(k),
+                (_, Some(k)) => left.scale(k),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some((value, expanded))
+}
@@ -0,0 +1,286 @@
+//! The Poseidon permutation and sponge hash, natively on field elements and as
+//! a gadget for any `CircuitSym`.
+//!
+//! Each round adds round constants, applies the S-box `x^alpha` to every state
+//! element in full rounds and to the first one in partial rounds, then
+//! multiplies the state by the MDS matrix. Half of the full rounds come before
+//! the partial rounds and half after. In a circuit the constants and the matrix
+//! are linear, so only the S-boxes cost constraints.
+//!
+//! Parameters are generated as in the Poseidon reference implementation: a
+//! Grain LFSR seeded with the field, S-box, width and round numbers yields the
+//! round constants, by rejection sampling, and then the points of the Cauchy
+//! MDS matrix `1 / (x_i + y_j)`. The reference's extra checks against weak
+//! matrices are not done, and with a field as small as `PRIME` no choice of
+//! rounds reaches a meaningful security level.
+
+use crate::{field_element::FieldElement, sym::CircuitSym, PRIME};
+
+/// Poseidon parameters, see `Poseidon::new`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Poseidon {
+    /// Elements in the state: one for the capacity, the rest absorb inputs
+    pub width: usize,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    /// The S-box exponent, the smallest `alpha >= 3` with `x^alpha` a bijection
+    pub alpha: u128,
+    /// `width` constants per round
+    pub round_constants: Vec<Vec<u128>>,
+    /// `width` rows of `width` elements
+    pub mds: Vec<Vec<u128>>,
+}
+
+impl Default for Poseidon {
+    /// Width 3, hashing two elements per permutation as for Merkle trees, with
+    /// 8 full and 57 partial rounds as commonly used for that width
+    fn default() -> Self {
+        Self::new(3, 8, 57)
+    }
+}
+
+impl Poseidon {
+    /// Generates the parameters for `width` state elements. `full_rounds` must
+    /// be even.
+    pub fn new(width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        assert!(width >= 2, "Poseidon needs a capacity and a rate element");
+        assert!(
+            full_rounds.is_multiple_of(2),
+            "full rounds are split evenly around the partial rounds"
+        );
+        let alpha = (3..)
+            .find(|a| gcd(*a, PRIME - 1) == 1)
+            .expect("some exponent is coprime to p - 1");
+
+        let bits = 128 - PRIME.leading_zeros() as usize;
+        let mut grain = Grain::new(bits, width, full_rounds, partial_rounds);
+        let round_constants = (0..full_rounds + partial_rounds)
+            .map(|_| {
+                (0..width)
+                    .map(|_| loop {
+                        let value = grain.integer(bits);
+                        if value < PRIME {
+                            break value;
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mds = loop {
+            let points: Vec<u128> = (0..2 * width)
+                .map(|_| grain.integer(bits) % PRIME)
+                .collect();
+            let distinct = points
+                .iter()
+                .enumerate()
+                .all(|(i, p)| !points[..i].contains(p));
+            let (xs, ys) = points.split_at(width);
+            let sums: Vec<Vec<FieldElement>> = xs
+                .iter()
+                .map(|x| ys.iter().map(|y| FieldElement::new(x + y, PRIME)).collect())
+                .collect();
+            if distinct && sums.iter().flatten().all(|s| s.value != 0) {
+                break sums
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|s| s.inverse().expect("non-zero").value)
+                            .collect()
+                    })
+                    .collect();
+            }
+        };
+
+        Poseidon {
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        }
+    }
+
+    /// Elements absorbed per permutation
+    pub fn rate(&self) -> usize {
+        self.width - 1
+    }
+
+    fn is_full(&self, round: usize) -> bool {
+        let half = self.full_rounds / 2;
+        round < half || round >= half + self.partial_rounds
+    }
+
+    /// The permutation of `width` elements
+    pub fn permute(&self, state: &[FieldElement]) -> Vec<FieldElement> {
+        assert_eq!(state.len(), self.width, "Poseidon state width");
+        let mut state = state.to_vec();
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (x, c) in state.iter_mut().zip(constants) {
+                *x = x.add(&FieldElement::new(*c, PRIME));
+            }
+            let sboxes = if self.is_full(round) { self.width } else { 1 };
+            for x in &mut state[..sboxes] {
+                *x = x.pow(self.alpha);
+            }
+            state = self
+                .mds
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(&state)
+                        .fold(FieldElement::new(0, PRIME), |sum, (m, x)| {
+                            sum.add(&FieldElement::new(*m, PRIME).mul(x))
+                        })
+                })
+                .collect();
+        }
+        state
+    }
+
+    /// Sponge hash: the capacity element starts as the number of inputs, `rate`
+    /// inputs at a time are added to the rest of the state before each
+    /// permutation, and the first rate element is the digest
+    pub fn hash(&self, inputs: &[FieldElement]) -> FieldElement {
+        let zero = FieldElement::new(0, PRIME);
+        let mut state = vec![zero.clone(); self.width];
+        state[0] = FieldElement::new(inputs.len() as u128, PRIME);
+        for chunk in self.chunks(inputs.len()) {
+            for (x, input) in state[1..].iter_mut().zip(&inputs[chunk]) {
+                *x = x.add(input);
+            }
+            state = self.permute(&state);
+        }
+        state[1].clone()
+    }
+
+    /// The permutation as a circuit: constraints for the S-boxes only
+    pub fn permute_sym<S: CircuitSym>(&self, s: &mut S, state: &[S::Repr]) -> Vec<S::Repr> {
+        assert_eq!(state.len(), self.width, "Poseidon state width");
+        let mut state = state.to_vec();
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (x, c) in state.iter_mut().zip(constants) {
+                let c = s.constant(*c);
+                *x = s.add(x, &c);
+            }
+            let sboxes = if self.is_full(round) { self.width } else { 1 };
+            for x in &mut state[..sboxes] {
+                *x = pow_sym(s, x, self.alpha);
+            }
+            state = self
+                .mds
+                .iter()
+                .map(|row| {
+                    let mut sum = s.constant(0);
+                    for (m, x) in row.iter().zip(&state) {
+                        let m = s.constant(*m);
+                        let term = s.mul(&m, x);
+                        sum = s.add(&sum, &term);
+                    }
+                    sum
+                })
+                .collect();
+        }
+        state
+    }
+
+    /// `hash` as a circuit
+    pub fn hash_sym<S: CircuitSym>(&self, s: &mut S, inputs: &[S::Repr]) -> S::Repr {
+        let mut state: Vec<S::Repr> = (0..self.width).map(|_| s.constant(0)).collect();
+        state[0] = s.constant(inputs.len() as u128);
+        for chunk in self.chunks(inputs.len()) {
+            for (x, input) in state[1..].iter_mut().zip(&inputs[chunk]) {
+                *x = s.add(x, input);
+            }
+            state = self.permute_sym(s, &state);
+        }
+        state[1].clone()
+    }
+
+    /// The ranges of inputs absorbed by each permutation, at least one
+    fn chunks(&self, len: usize) -> Vec<std::ops::Range<usize>> {
+        let count = len.div_ceil(self.rate()).max(1);
+        (0..count)
+            .map(|i| i * self.rate()..((i + 1) * self.rate()).min(len))
+            .collect()
+    }
+}
+
+/// `x^e` by square and multiply
+fn pow_sym<S: CircuitSym>(s: &mut S, x: &S::Repr, e: u128) -> S::Repr {
+    let mut result: Option<S::Repr> = None;
+    for bit in (0..128 - e.leading_zeros()).rev() {
+        if let Some(r) = &result {
+            result = Some(s.mul(r, r));
+        }
+        if e >> bit & 1 == 1 {
+            result = Some(match &result {
+                Some(r) => s.mul(r, x),
+                None => x.clone(),
+            });
+        }
+    }
+    result.unwrap_or_else(|| s.constant(1))
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The 80-bit Grain LFSR of the Poseidon reference parameter generation
+struct Grain {
+    state: Vec<bool>,
+}
+
+impl Grain {
+    fn new(field_bits: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut state = Vec::with_capacity(80);
+        let mut push = |value: usize, len: usize| {
+            state.extend((0..len).rev().map(|i| value >> i & 1 == 1));
+        };
+        // a prime field, the S-box x^alpha
+        push(1, 2);
+        push(0, 4);
+        push(field_bits, 12);
+        push(width, 12);
+        push(full_rounds, 10);
+        push(partial_rounds, 10);
+        state.extend([true; 30]);
+
+        let mut grain = Grain { state };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.remove(0);
+        self.state.push(bit);
+        bit
+    }
+
+    /// Self-shrinking output: of each pair of bits, the second if the first is set
+    fn bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// `bits` output bits, most significant first
+    fn integer(&mut self, bits: usize) -> u128 {
+        (0..bits).fold(0, |acc, _| acc << 1 | self.bit() as u128)
+    }
+}
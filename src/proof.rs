@@ -10,9 +10,18 @@ use crate::field_element::FieldElement;
 /// In a real zk system, we shouldn't reveal the actual witness or blinding
 /// Here we do so the verifier can re-check each commitment and constraint.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proof {
     /// For each private signal we store a named commitment
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialization::sorted")
+    )]
     pub commitments: HashMap<String, Commitment>,
     /// The (value, blinding) pairs for each private signal so the Verifier can re-check.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialization::sorted")
+    )]
     pub revealed_witness: HashMap<String, (FieldElement, FieldElement)>,
 }
@@ -3,8 +3,8 @@ use std::collections::HashMap;
 use rand::Rng;
 
 use crate::{
-    circuit::Circuit, commitment::Commitment, constraint::Operation, field_element::FieldElement,
-    proof::Proof, signal::Signal, witness::Witness,
+    circuit::Circuit, commitment::Commitment, field_element::FieldElement, proof::Proof,
+    signal::Signal, witness::Witness, witness_generator::WitnessGenerator,
 };
 
 /// The prover:
@@ -17,30 +17,16 @@ pub struct Prover;
 impl Prover {
     #[allow(deprecated)]
     pub fn prove(circuit: &Circuit, witness: &Witness) -> Proof {
-        let mut value_map: HashMap<String, FieldElement> = witness.values.clone();
-
-        // Compute all intermediate witness values before committing
-        for constraint in &circuit.constraints {
-            let left_val = Self::eval_signal(&constraint.left, &value_map).unwrap();
-            let right_val = Self::eval_signal(&constraint.right, &value_map).unwrap();
-
-            let result = match constraint.operation {
-                Operation::Add => left_val.add(&right_val),
-                Operation::Mul => left_val.mul(&right_val),
-                Operation::Sub => left_val.sub(&right_val),
-                Operation::Eq => {
-                    assert!(left_val.equals(&right_val), "Constraint equation failed!");
-                    left_val.clone()
-                }
-            };
-
-            match &constraint.output {
-                Signal::Output(out_name) | Signal::Witness(out_name) => {
-                    value_map.insert(out_name.clone(), result);
-                }
-                _ => {}
-            }
-        }
+        // Compute all intermediate witness values before committing. If a
+        // constraint fails, generate again, tracing how it got its operands.
+        let generator = WitnessGenerator::new(circuit);
+        let value_map = match generator.complete(witness) {
+            Ok(full) => full.values,
+            Err(err) => match generator.trace(witness).failure {
+                Some(failure) => panic!("{}", failure),
+                None => panic!("{}", err),
+            },
+        };
 
         // Now create commitments
         let mut commitments = HashMap::new();
@@ -67,24 +53,17 @@ impl Prover {
         }
     }
 
-    fn eval_signal(
-        signal: &Signal,
-        value_map: &HashMap<String, FieldElement>,
-    ) -> Option<FieldElement> {
-        match signal {
-            Signal::Input(name) => value_map.get(name).cloned(),
-            Signal::Witness(name) => value_map.get(name).cloned(),
-            Signal::Output(name) => value_map.get(name).cloned(),
-        }
-    }
-
     fn collect_witness_names(circuit: &Circuit) -> Vec<String> {
-        let mut names = Vec::new();
+        let mut names: Vec<String> = Vec::new();
         for constraint in &circuit.constraints {
-            for sig in [&constraint.left, &constraint.right, &constraint.output] {
+            for sig in constraint
+                .operands()
+                .into_iter()
+                .chain([&constraint.output])
+            {
                 if let Signal::Witness(nm) = sig {
-                    if !names.contains(nm) {
-                        names.push(nm.clone());
+                    if !names.iter().any(|n| nm == n.as_str()) {
+                        names.push(nm.to_string());
                     }
                 }
             }
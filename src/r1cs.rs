@@ -0,0 +1,449 @@
+//! Lowering a circuit to a rank-1 constraint system on circom's wire layout, to
+//! cross-check ghayb circuits with circom and snarkjs tooling.
+//!
+//! Wire 0 is the constant 1, followed by the circuit outputs, the circuit inputs,
+//! the private inputs (witness signals nothing computes) and the remaining
+//! signals in the order they first appear. Linear operations become rows
+//! `0 * 0 = lc` as circom writes them, i.e. `lc = 0`. `Select` and `Mux` need
+//! auxiliary wires, appended last:
+//!
+//! - `Select(c)`: `c * (c - 1) = 0` and `c * (then - else) = out - else`
+//! - `Mux`: one boolean selector `s_i` per option with `sum(s_i) = 1` and
+//!   `sum(i * s_i) = index`, products `s_i * option_i = p_i` and `sum(p_i) = out`
+//! - `Custom`: each term of the gate's polynomial is multiplied up one factor at
+//!   a time, `m_1 = x * y`, `m_2 = m_1 * z`, ..., and `sum(c_i * m_i) = out`
+//! - `Lookup`: R1CS has no tables, so one boolean selector `s_j` per table row
+//!   with `sum(s_j) = 1`, and every looked-up value is `sum(s_j * row_j)`. Keep
+//!   such tables small: `to_r1cs` refuses tables of more than
+//!   `R1csExport::MAX_TABLE_ROWS` rows.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    circom::{witness_json, write_wtns, LinearTerms, R1cs, R1csConstraint},
+    circuit::Circuit,
+    constraint::Operation,
+    field_element::FieldElement,
+    gate::Gate,
+    lookup::Table,
+    signal::Signal,
+    witness::Witness,
+    witness_generator::{lookup, WitnessError, WitnessGenerator},
+    PRIME,
+};
+
+/// A circuit laid out on circom wires, see `Circuit::to_r1cs`
+#[derive(Clone, Debug)]
+pub struct R1csExport<'a> {
+    circuit: &'a Circuit,
+    pub r1cs: R1cs,
+    wires: Vec<Wire>,
+    /// The table and looked-up signals of every lookup, for `Wire::TableRow`
+    lookups: Vec<(Table, Vec<Signal>)>,
+}
+
+/// What a wire holds
+#[derive(Clone, Debug)]
+enum Wire {
+    One,
+    Signal(String),
+    /// 1 if the mux index is `option`, 0 otherwise
+    Selector {
+        index: Signal,
+        option: usize,
+    },
+    /// A selector wire times a mux option
+    Product {
+        selector: usize,
+        option: Signal,
+    },
+    /// The product of the first factors of a custom gate's term
+    Monomial {
+        factors: Vec<Signal>,
+    },
+    /// 1 if lookup number `lookup` finds its values in row `row`, 0 otherwise
+    TableRow {
+        lookup: usize,
+        row: usize,
+    },
+}
+
+/// A linear combination being built, by wire
+#[derive(Default)]
+struct Lc(BTreeMap<usize, FieldElement>);
+
+impl Lc {
+    fn add(mut self, wire: usize, coeff: u128) -> Self {
+        let coeff = FieldElement::new(coeff % PRIME, PRIME);
+        let sum = match self.0.get(&wire) {
+            Some(c) => c.add(&coeff),
+            None => coeff,
+        };
+        self.0.insert(wire, sum);
+        self
+    }
+
+    /// Drops the zero coefficients
+    fn terms(self) -> LinearTerms {
+        self.0.into_iter().filter(|(_, c)| c.value != 0).collect()
+    }
+}
+
+/// The wires of the circuit's signals, before any auxiliary wire
+pub(crate) struct WireLayout {
+    ids: HashMap<String, usize>,
+    wires: Vec<Wire>,
+    pub(crate) num_public_outputs: usize,
+    pub(crate) num_public_inputs: usize,
+    pub(crate) num_private_inputs: usize,
+}
+
+impl Circuit {
+    /// Lays the circuit out as a rank-1 constraint system, e.g. to write it as a
+    /// `.r1cs` file with `export.r1cs.to_bytes()`. Panics if a lookup reads a
+    /// table with more than `R1csExport::MAX_TABLE_ROWS` rows.
+    pub fn to_r1cs(&self) -> R1csExport<'_> {
+        let layout = self.wire_layout();
+        let mut lowering = Lowering::new(layout.ids, layout.wires, false);
+        for c in &self.constraints {
+            lowering.constraint(&c.left, &c.right, &c.output, &c.operation);
+        }
+
+        let num_wires = lowering.wires.len();
+        R1csExport {
+            circuit: self,
+            r1cs: R1cs {
+                num_wires,
+                num_public_outputs: layout.num_public_outputs,
+                num_public_inputs: layout.num_public_inputs,
+                num_private_inputs: layout.num_private_inputs,
+                num_labels: num_wires as u64,
+                constraints: lowering.rows,
+                wire_labels: (0..num_wires as u64).collect(),
+            },
+            wires: lowering.wires,
+            lookups: lowering.lookups,
+        }
+    }
+
+    /// The number of rows of `to_r1cs`, counting the rows of tables instead of
+    /// writing them out
+    pub(crate) fn r1cs_rows(&self) -> usize {
+        let layout = self.wire_layout();
+        let mut lowering = Lowering::new(layout.ids, layout.wires, true);
+        for c in &self.constraints {
+            lowering.constraint(&c.left, &c.right, &c.output, &c.operation);
+        }
+        lowering.row_count
+    }
+
+    pub(crate) fn wire_layout(&self) -> WireLayout {
+        let mut wires = vec![Wire::One];
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut number = |sig: &Signal, wires: &mut Vec<Wire>| {
+            if let Some(name) = sig.name() {
+                if !ids.contains_key(name) {
+                    ids.insert(name.to_string(), wires.len());
+                    wires.push(Wire::Signal(name.to_string()));
+                }
+            }
+        };
+
+        let computed: HashSet<&str> = self
+            .constraints
+            .iter()
+            .filter_map(|c| c.output.name())
+            .chain(
+                self.hints
+                    .iter()
+                    .flat_map(|h| h.outputs.iter().map(String::as_str)),
+            )
+            .collect();
+        let appearing: Vec<&Signal> = self
+            .constraints
+            .iter()
+            .flat_map(|c| c.operands().into_iter().chain([&c.output]))
+            .collect();
+
+        for sig in &self.outputs {
+            number(sig, &mut wires);
+        }
+        let num_public_outputs = wires.len() - 1;
+        for sig in &self.inputs {
+            number(sig, &mut wires);
+        }
+        let num_public_inputs = wires.len() - 1 - num_public_outputs;
+        for sig in &appearing {
+            if matches!(sig, Signal::Witness(name) if !computed.contains(name.as_str())) {
+                number(sig, &mut wires);
+            }
+        }
+        let num_private_inputs = wires.len() - 1 - num_public_outputs - num_public_inputs;
+        for sig in &appearing {
+            number(sig, &mut wires);
+        }
+
+        WireLayout {
+            ids,
+            wires,
+            num_public_outputs,
+            num_public_inputs,
+            num_private_inputs,
+        }
+    }
+}
+
+struct Lowering {
+    ids: HashMap<String, usize>,
+    wires: Vec<Wire>,
+    rows: Vec<R1csConstraint>,
+    /// Rows that are not trivial, kept or not
+    row_count: usize,
+    /// Only count rows, without the rows and auxiliary wires of lookups
+    count_only: bool,
+    lookups: Vec<(Table, Vec<Signal>)>,
+}
+
+impl Lowering {
+    fn new(ids: HashMap<String, usize>, wires: Vec<Wire>, count_only: bool) -> Self {
+        Lowering {
+            ids,
+            wires,
+            rows: Vec::new(),
+            row_count: 0,
+            count_only,
+            lookups: Vec::new(),
+        }
+    }
+
+    fn constraint(&mut self, left: &Signal, right: &Signal, out: &Signal, op: &Operation) {
+        let minus = PRIME - 1;
+        match op {
+            Operation::Add => {
+                let sum = self.lc(self.lc(self.one(left), right, 1), out, minus);
+                self.linear(sum);
+            }
+            Operation::Sub => {
+                let diff = self.lc(self.lc(self.one(left), right, minus), out, minus);
+                self.linear(diff);
+            }
+            Operation::Mul => self.row(self.one(left), self.one(right), self.one(out)),
+            Operation::Eq => {
+                self.linear(self.lc(self.one(left), right, minus));
+                self.linear(self.lc(self.one(left), out, minus));
+            }
+            Operation::Select(cond) => {
+                self.row(self.one(cond), self.one(cond).add(0, minus), Lc::default());
+                self.row(
+                    self.one(cond),
+                    self.lc(self.one(left), right, minus),
+                    self.lc(self.one(out), right, minus),
+                );
+            }
+            Operation::Mux(options) => self.mux(left, options, out),
+            Operation::Custom(gate, inputs) => self.custom(gate, inputs, out),
+            Operation::Lookup(table, inputs) => self.lookup(table, inputs, out),
+        }
+    }
+
+    fn lookup(&mut self, table: &Table, inputs: &[Signal], out: &Signal) {
+        let minus = PRIME - 1;
+        let mut tuple = inputs.to_vec();
+        if tuple.len() < table.width() {
+            tuple.push(out.clone());
+        } else {
+            self.linear(self.one(out));
+        }
+        if self.count_only {
+            // a boolean row per table row, their sum, and a sum per column
+            // unless it is `0 = 0`
+            let columns = tuple.iter().enumerate().filter(|(i, sig)| {
+                !self.lc(Lc::default(), sig, 1).terms().is_empty()
+                    || (0..table.len()).any(|row| table.row(row)[*i] != 0)
+            });
+            self.row_count += table.len() + 1 + columns.count();
+            return;
+        }
+        assert!(
+            table.len() <= R1csExport::MAX_TABLE_ROWS,
+            "table `{}` has {} rows, more than the {} R1CS can select from",
+            table.name,
+            table.len(),
+            R1csExport::MAX_TABLE_ROWS
+        );
+        let lookup = self.lookups.len();
+        self.lookups.push((table.clone(), tuple.clone()));
+
+        let mut ones = Lc::default().add(0, minus);
+        let mut columns: Vec<Lc> = tuple
+            .iter()
+            .map(|sig| self.lc(Lc::default(), sig, minus))
+            .collect();
+        for row in 0..table.len() {
+            let s = self.aux(Wire::TableRow { lookup, row });
+            let selector = || Lc::default().add(s, 1);
+            self.row(selector(), selector().add(0, minus), Lc::default());
+            ones = ones.add(s, 1);
+            for (column, value) in columns.iter_mut().zip(table.row(row)) {
+                *column = std::mem::take(column).add(s, value);
+            }
+        }
+        self.linear(ones);
+        for column in columns {
+            self.linear(column);
+        }
+    }
+
+    fn custom(&mut self, gate: &Gate, inputs: &[Signal], out: &Signal) {
+        let mut sum = self.lc(Lc::default(), out, PRIME - 1);
+        for term in &gate.polynomial.terms {
+            let factors: Vec<&Signal> = inputs
+                .iter()
+                .zip(&term.powers)
+                .flat_map(|(input, power)| std::iter::repeat_n(input, *power as usize))
+                .collect();
+            let mut product = match factors.first() {
+                Some(first) => self.one(first),
+                None => Lc::default().add(0, 1),
+            };
+            for k in 1..factors.len() {
+                let m = self.aux(Wire::Monomial {
+                    factors: factors[..=k].iter().map(|f| (*f).clone()).collect(),
+                });
+                self.row(product, self.one(factors[k]), Lc::default().add(m, 1));
+                product = Lc::default().add(m, 1);
+            }
+            for (wire, coeff) in product.0 {
+                sum = sum.add(wire, coeff.value * term.coeff % PRIME);
+            }
+        }
+        self.linear(sum);
+    }
+
+    fn mux(&mut self, index: &Signal, options: &[Signal], out: &Signal) {
+        let minus = PRIME - 1;
+        let mut ones = Lc::default().add(0, minus);
+        let mut weighted = self.lc(Lc::default(), index, minus);
+        let mut picked = self.lc(Lc::default(), out, minus);
+        for (i, option) in options.iter().enumerate() {
+            let s = self.aux(Wire::Selector {
+                index: index.clone(),
+                option: i,
+            });
+            let selector = || Lc::default().add(s, 1);
+            self.row(selector(), selector().add(0, minus), Lc::default());
+            ones = ones.add(s, 1);
+            weighted = weighted.add(s, i as u128);
+            picked = match option {
+                // a constant option needs no product wire
+                Signal::Constant(c) => picked.add(s, *c),
+                option => {
+                    let p = self.aux(Wire::Product {
+                        selector: s,
+                        option: option.clone(),
+                    });
+                    self.row(selector(), self.one(option), Lc::default().add(p, 1));
+                    picked.add(p, 1)
+                }
+            };
+        }
+        self.linear(ones);
+        self.linear(weighted);
+        self.linear(picked);
+    }
+
+    /// `sig` on its own
+    fn one(&self, sig: &Signal) -> Lc {
+        self.lc(Lc::default(), sig, 1)
+    }
+
+    /// `lc + coeff * sig`
+    fn lc(&self, lc: Lc, sig: &Signal, coeff: u128) -> Lc {
+        match sig {
+            Signal::Constant(c) => lc.add(0, c % PRIME * coeff),
+            Signal::Linear(terms) => terms
+                .terms
+                .iter()
+                .fold(lc.add(0, terms.constant * coeff), |lc, (s, c)| {
+                    self.lc(lc, s, c * coeff)
+                }),
+            named => lc.add(self.ids[named.name().expect("named signal")], coeff),
+        }
+    }
+
+    fn aux(&mut self, wire: Wire) -> usize {
+        self.wires.push(wire);
+        self.wires.len() - 1
+    }
+
+    /// `lc = 0`
+    fn linear(&mut self, lc: Lc) {
+        self.row(Lc::default(), Lc::default(), lc);
+    }
+
+    fn row(&mut self, a: Lc, b: Lc, c: Lc) {
+        let row = R1csConstraint {
+            a: a.terms(),
+            b: b.terms(),
+            c: c.terms(),
+        };
+        // e.g. `x = x`
+        let trivial = row.c.is_empty() && (row.a.is_empty() || row.b.is_empty());
+        if !trivial {
+            self.row_count += 1;
+            if !self.count_only {
+                self.rows.push(row);
+            }
+        }
+    }
+}
+
+impl R1csExport<'_> {
+    /// The longest table a lookup can select a row from, one wire per row
+    pub const MAX_TABLE_ROWS: usize = 1 << 16;
+
+    /// The value of every wire, starting with the constant 1. `witness` is what
+    /// the prover gets; the intermediate values are computed from it.
+    pub fn wire_values(&self, witness: &Witness) -> Result<Vec<FieldElement>, WitnessError> {
+        let values = WitnessGenerator::new(self.circuit)
+            .complete(witness)?
+            .values;
+        let value =
+            |sig: &Signal| lookup(sig, &values).expect("witness generation assigns every operand");
+
+        let mut wires: Vec<FieldElement> = Vec::with_capacity(self.wires.len());
+        for wire in &self.wires {
+            let v = match wire {
+                Wire::One => FieldElement::new(1, PRIME),
+                Wire::Signal(name) => values
+                    .get(name)
+                    .unwrap_or_else(|| panic!("Missing witness value for signal '{}'", name))
+                    .clone(),
+                Wire::Selector { index, option } => {
+                    FieldElement::new((value(index).value == *option as u128) as u128, PRIME)
+                }
+                Wire::Product { selector, option } => wires[*selector].mul(&value(option)),
+                Wire::Monomial { factors } => factors
+                    .iter()
+                    .fold(FieldElement::new(1, PRIME), |acc, f| acc.mul(&value(f))),
+                Wire::TableRow { lookup, row } => {
+                    let (table, tuple) = &self.lookups[*lookup];
+                    let values: Vec<u128> = tuple.iter().map(|sig| value(sig).value).collect();
+                    FieldElement::new((table.position(&values) == Some(*row)) as u128, PRIME)
+                }
+            };
+            wires.push(v);
+        }
+        Ok(wires)
+    }
+
+    /// The `.wtns` file for `witness`
+    pub fn wtns_bytes(&self, witness: &Witness) -> Result<Vec<u8>, WitnessError> {
+        Ok(write_wtns(&self.wire_values(witness)?))
+    }
+
+    /// The snarkjs `witness.json` for `witness`
+    pub fn witness_json(&self, witness: &Witness) -> Result<String, WitnessError> {
+        Ok(witness_json(&self.wire_values(witness)?))
+    }
+}
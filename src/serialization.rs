@@ -0,0 +1,108 @@
+//! Serde support, behind the `serde` feature.
+//!
+//! Field elements are written as canonical decimal strings (`"42"`, never `"042"`
+//! or a value above the prime), hashes as lowercase hex and maps sorted by key,
+//! so equal values always serialize to the same bytes. A circuit is checked as
+//! strictly as `Circuit::from_bytes` checks it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    circuit::{invalid_circuit, Circuit},
+    constraint::Constraint,
+    field_element::FieldElement,
+    signal::Signal,
+    PRIME,
+};
+
+impl Serialize for FieldElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.prime != PRIME {
+            return Err(serde::ser::Error::custom(format!(
+                "cannot serialize an element of the field of size {}",
+                self.prime
+            )));
+        }
+        serializer.serialize_str(&self.value.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_canonical(&text).map_err(de::Error::custom)
+    }
+}
+
+/// A decimal field element without sign, leading zeros or reduction
+fn parse_canonical(text: &str) -> Result<FieldElement, String> {
+    let digits = !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    if !digits || (text.len() > 1 && text.starts_with('0')) {
+        return Err(format!("'{}' is not a canonical field element", text));
+    }
+    match text.parse::<u128>() {
+        Ok(value) if value < PRIME => Ok(FieldElement::new(value, PRIME)),
+        _ => Err(format!(
+            "'{}' is not a canonical field element, it must be below {}",
+            text, PRIME
+        )),
+    }
+}
+
+/// A `Circuit` as it is deserialized, before it is checked
+#[derive(Deserialize)]
+pub(crate) struct RawCircuit {
+    inputs: Vec<Signal>,
+    outputs: Vec<Signal>,
+    constraints: Vec<Constraint>,
+    #[serde(default)]
+    witnesses: Vec<Signal>,
+}
+
+impl TryFrom<RawCircuit> for Circuit {
+    type Error = String;
+
+    fn try_from(raw: RawCircuit) -> Result<Self, String> {
+        let circuit =
+            Circuit::new(raw.inputs, raw.outputs, raw.constraints).with_witnesses(raw.witnesses);
+        match invalid_circuit(&circuit) {
+            Some(message) => Err(message),
+            None => Ok(circuit),
+        }
+    }
+}
+
+/// Writes a `HashMap` in key order
+pub(crate) fn sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+pub(crate) mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let text: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let lowercase_hex = text.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        if text.len() % 2 != 0 || !lowercase_hex {
+            return Err(de::Error::custom(format!(
+                "'{}' is not lowercase hex",
+                text
+            )));
+        }
+        Ok((0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).expect("checked to be hex"))
+            .collect())
+    }
+}
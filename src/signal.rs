@@ -1,11 +1,210 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
 use crate::field_element::FieldElement;
+use crate::linear::LinearCombination;
 
+/// The name of a signal: a base name and, for an element of an array, its
+/// indices, e.g. `grid[1][2]` is `grid` with `[1, 2]`. Template instances keep
+/// their own indices in the base, as in `hasher[0].out`.
+///
+/// Names are compared, hashed and shown as text, so a name read from a file
+/// equals the one built from its parts.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", from = "String")
+)]
+pub struct SignalName {
+    base: String,
+    indices: Vec<usize>,
+    /// The base followed by the indices in brackets
+    text: String,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Signal {
     /// Public input by name, e.g. "x" or "y"
-    Input(String),
+    Input(SignalName),
     /// Private witness by name, e.g. "secret1"
-    Witness(String),
+    Witness(SignalName),
     /// An output signal by name, e.g. "out"
-    Output(String),
+    Output(SignalName),
+    /// A fixed value known to everyone, e.g. the 1 in `1 - c`
+    Constant(u128),
+    /// A linear combination of other signals, e.g. `a + 2 * b - 1`
+    Linear(LinearCombination),
+}
+
+impl Signal {
+    /// The name of the signal regardless of its role, `None` for constants
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Signal::Input(name) | Signal::Witness(name) | Signal::Output(name) => Some(name),
+            Signal::Constant(_) | Signal::Linear(_) => None,
+        }
+    }
+
+    /// The structured name, `None` for constants
+    pub fn signal_name(&self) -> Option<&SignalName> {
+        match self {
+            Signal::Input(name) | Signal::Witness(name) | Signal::Output(name) => Some(name),
+            Signal::Constant(_) | Signal::Linear(_) => None,
+        }
+    }
+
+    /// Name of an array element, e.g. `grid[1][2]`
+    pub fn element_name(base: &str, indices: &[usize]) -> String {
+        SignalName::element(base, indices).text
+    }
+
+    /// Names of every element of an array with the given dimensions, row-major
+    pub fn element_names(base: &str, dims: &[usize]) -> Vec<String> {
+        SignalName::elements(base, dims)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// The array name and the indices of an element, e.g. `("grid", [1, 2])`
+    /// for `grid[1][2]` and `("h[0].out", [])` for `h[0].out`
+    pub fn array_index(&self) -> Option<(&str, &[usize])> {
+        let name = self.signal_name()?;
+        Some((name.base(), name.indices()))
+    }
+}
+
+impl SignalName {
+    /// The element of the array `base` at `indices`, or just `base` without
+    /// indices
+    pub fn element(base: &str, indices: &[usize]) -> Self {
+        let mut text = base.to_string();
+        for index in indices {
+            text.push_str(&format!("[{}]", index));
+        }
+        Self {
+            base: base.to_string(),
+            indices: indices.to_vec(),
+            text,
+        }
+    }
+
+    /// Every element of an array with the given dimensions, row-major
+    pub fn elements(base: &str, dims: &[usize]) -> Vec<Self> {
+        let total: usize = dims.iter().product();
+        (0..total)
+            .map(|mut flat| {
+                let mut indices = vec![0; dims.len()];
+                for (k, dim) in dims.iter().enumerate().rev() {
+                    indices[k] = flat % dim;
+                    flat /= dim;
+                }
+                Self::element(base, &indices)
+            })
+            .collect()
+    }
+
+    /// The element at `more` of this element, e.g. `acc[0][2]` for `acc[0]`
+    /// and `[2]`
+    pub fn index(&self, more: &[usize]) -> Self {
+        let indices: Vec<usize> = self.indices.iter().chain(more).copied().collect();
+        Self::element(&self.base, &indices)
+    }
+
+    /// The name without indices
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// The indices of an array element, empty for other signals
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The whole name, e.g. `grid[1][2]`
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Reads the trailing indices of a name, e.g. `grid[1][2]`
+impl From<&str> for SignalName {
+    fn from(text: &str) -> Self {
+        let mut base = text;
+        let mut indices = Vec::new();
+        while let Some(rest) = base.strip_suffix(']') {
+            let Some(open) = rest.rfind('[') else {
+                break;
+            };
+            // only canonical numbers, so that the text stays the same
+            let index = &rest[open + 1..];
+            let canonical = index == "0" || !index.starts_with('0');
+            let Some(index) = index.parse().ok().filter(|_| canonical) else {
+                break;
+            };
+            indices.push(index);
+            base = &rest[..open];
+        }
+        indices.reverse();
+        Self {
+            base: base.to_string(),
+            indices,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl From<String> for SignalName {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl From<SignalName> for String {
+    fn from(name: SignalName) -> Self {
+        name.text
+    }
+}
+
+impl Deref for SignalName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl PartialEq for SignalName {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for SignalName {}
+
+impl Hash for SignalName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+    }
+}
+
+impl PartialEq<str> for SignalName {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for SignalName {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl fmt::Display for SignalName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
 }
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    signal::Signal,
+};
+
+/// How many constraints of each operation a circuit has
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperationCounts {
+    pub add: usize,
+    pub sub: usize,
+    pub mul: usize,
+    pub eq: usize,
+    pub select: usize,
+    pub mux: usize,
+    pub custom: usize,
+    pub lookup: usize,
+}
+
+/// The size and cost of a circuit, see `Circuit::stats`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircuitStats {
+    pub constraints: usize,
+    pub operations: OperationCounts,
+    pub public_inputs: usize,
+    pub outputs: usize,
+    /// Witness signals the prover supplies: neither a constraint nor a hint computes them
+    pub private_inputs: usize,
+    /// Every other signal
+    pub intermediate: usize,
+    /// The most non-linear operations on a path from the inputs to a signal.
+    /// Multiplying by a constant is linear. `None` if the constraints form a cycle.
+    pub multiplicative_depth: Option<usize>,
+    /// Rows of the system `Circuit::to_r1cs` builds
+    pub r1cs_rows: usize,
+    /// Estimated gates of a fan-in 2 PLONK arithmetization, where constants fold
+    /// into selectors and copies are free
+    pub plonk_gates: usize,
+    /// The signal read by the most constraints, and by how many
+    pub max_fan_out: Option<(String, usize)>,
+}
+
+impl Circuit {
+    /// Counts what the circuit costs, e.g. to pick a proving backend
+    pub fn stats(&self) -> CircuitStats {
+        let mut operations = OperationCounts::default();
+        let mut plonk_gates = 0;
+        for c in &self.constraints {
+            match &c.operation {
+                Operation::Add => operations.add += 1,
+                Operation::Sub => operations.sub += 1,
+                Operation::Mul => operations.mul += 1,
+                Operation::Eq => operations.eq += 1,
+                Operation::Select(_) => operations.select += 1,
+                Operation::Mux(_) => operations.mux += 1,
+                Operation::Custom(..) => operations.custom += 1,
+                Operation::Lookup(..) => operations.lookup += 1,
+            }
+            plonk_gates += plonk_cost(c);
+        }
+
+        let layout = self.wire_layout();
+        let named: HashSet<&str> = self
+            .inputs
+            .iter()
+            .chain(&self.outputs)
+            .chain(
+                self.constraints
+                    .iter()
+                    .flat_map(|c| c.operands().into_iter().chain([&c.output])),
+            )
+            .filter_map(Signal::name)
+            .collect();
+
+        CircuitStats {
+            constraints: self.constraints.len(),
+            operations,
+            public_inputs: layout.num_public_inputs,
+            outputs: layout.num_public_outputs,
+            private_inputs: layout.num_private_inputs,
+            intermediate: named.len()
+                - layout.num_public_inputs
+                - layout.num_public_outputs
+                - layout.num_private_inputs,
+            multiplicative_depth: self.multiplicative_depth(),
+            r1cs_rows: self.r1cs_rows(),
+            plonk_gates,
+            max_fan_out: self.max_fan_out(),
+        }
+    }
+
+    fn multiplicative_depth(&self) -> Option<usize> {
+        let order = self.constraint_order().ok()?;
+        let mut depth: HashMap<&str, usize> = HashMap::new();
+        for i in order {
+            let c = &self.constraints[i];
+            let operands = c.operands();
+            let deepest = operands
+                .iter()
+                .filter_map(|s| s.name())
+                .map(|name| depth.get(name).copied().unwrap_or(0))
+                .max()
+                .unwrap_or(0);
+            let non_linear = match &c.operation {
+                Operation::Mul => !is_constant(&c.left) && !is_constant(&c.right),
+                Operation::Select(_) | Operation::Mux(_) | Operation::Lookup(..) => true,
+                Operation::Custom(gate, _) => gate.polynomial.degree() > 1,
+                Operation::Add | Operation::Sub | Operation::Eq => false,
+            };
+            if let Some(name) = c.output.name() {
+                depth.insert(name, deepest + non_linear as usize);
+            }
+        }
+        Some(depth.values().copied().max().unwrap_or(0))
+    }
+
+    fn max_fan_out(&self) -> Option<(String, usize)> {
+        let mut readers: HashMap<&str, usize> = HashMap::new();
+        for c in &self.constraints {
+            let read: HashSet<&str> = c.operands().into_iter().filter_map(Signal::name).collect();
+            for name in read {
+                *readers.entry(name).or_default() += 1;
+            }
+        }
+        // the first name wins a tie, so the report is stable
+        readers
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+            .map(|(name, count)| (name.to_string(), count))
+    }
+}
+
+fn is_constant(sig: &Signal) -> bool {
+    match sig {
+        Signal::Constant(_) => true,
+        Signal::Linear(lc) => lc.terms.is_empty(),
+        _ => false,
+    }
+}
+
+/// Gates `q_l a + q_r b + q_o c + q_m a b + q_c = 0` needed for one constraint.
+/// Summing up a linear combination of `n` terms takes `n - 1` more.
+fn plonk_cost(c: &Constraint) -> usize {
+    let mut operands = vec![&c.left, &c.right, &c.output];
+    match &c.operation {
+        Operation::Select(cond) => operands.push(cond),
+        Operation::Mux(options) | Operation::Custom(_, options) | Operation::Lookup(_, options) => {
+            operands.extend(options)
+        }
+        _ => {}
+    }
+    let sums: usize = operands
+        .into_iter()
+        .map(|sig| match sig {
+            Signal::Linear(lc) => lc.terms.len().saturating_sub(1),
+            _ => 0,
+        })
+        .sum();
+    sums + match &c.operation {
+        Operation::Add | Operation::Sub | Operation::Mul | Operation::Eq => 1,
+        // booleanity of c, then - else, c * (then - else), + else
+        Operation::Select(_) => 4,
+        // a boolean selector and a product per option, plus three sums over the
+        // selectors: they add up to 1, weighted they give the index, and the
+        // products add up to the output
+        Operation::Mux(options) => (5 * options.len()).saturating_sub(3),
+        // a backend with the gate checks it in one row
+        Operation::Custom(..) => 1,
+        // one row of the lookup argument
+        Operation::Lookup(..) => 1,
+    }
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ops = &self.operations;
+        let depth = match self.multiplicative_depth {
+            Some(depth) => depth.to_string(),
+            None => "cyclic".to_string(),
+        };
+        let fan_out = match &self.max_fan_out {
+            Some((name, count)) => format!("{} ({})", count, name),
+            None => "0".to_string(),
+        };
+        let rows = [
+            ("constraints", self.constraints.to_string()),
+            ("  add", ops.add.to_string()),
+            ("  sub", ops.sub.to_string()),
+            ("  mul", ops.mul.to_string()),
+            ("  eq", ops.eq.to_string()),
+            ("  select", ops.select.to_string()),
+            ("  mux", ops.mux.to_string()),
+            ("  custom", ops.custom.to_string()),
+            ("  lookup", ops.lookup.to_string()),
+            ("public inputs", self.public_inputs.to_string()),
+            ("outputs", self.outputs.to_string()),
+            ("private inputs", self.private_inputs.to_string()),
+            ("intermediate signals", self.intermediate.to_string()),
+            ("multiplicative depth", depth),
+            ("R1CS rows", self.r1cs_rows.to_string()),
+            ("PLONK gates (est.)", self.plonk_gates.to_string()),
+            ("max fan-out", fan_out),
+        ];
+        for (label, value) in rows {
+            writeln!(f, "{:<22}{:>8}", label, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    circuit::Circuit,
+    constraint::{Constraint, Operation},
+    field_element::FieldElement,
+    hint::Hint,
+    linear::LinearCombination,
+    signal::Signal,
+    sym::CircuitSym,
+    PRIME,
+};
+
+/// Builds a `Circuit`. Additions, subtractions and multiplications by a constant
+/// cost nothing: they give a `Signal::Linear`, which later constraints read as
+/// an operand. Every other operation is one constraint, and its result is a
+/// private witness named `$t0`, `$t1`, ... Declared signals cannot start with
+/// `$`, so temporaries never clash with them.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBuilder {
+    inputs: Vec<Signal>,
+    outputs: Vec<Signal>,
+    constraints: Vec<Constraint>,
+    hints: Vec<Hint>,
+    temps: usize,
+    /// Temporaries that became outputs, renamed when the circuit is built
+    aliases: HashMap<String, Signal>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn build(self) -> Circuit {
+        let aliases = self.aliases;
+        let rename = |sig: &Signal| match sig.name().and_then(|n| aliases.get(n)) {
+            Some(output) => output.clone(),
+            None => sig.clone(),
+        };
+        let rename_all = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .map(|n| match aliases.get(n).and_then(Signal::name) {
+                    Some(output) => output.to_string(),
+                    None => n.clone(),
+                })
+                .collect()
+        };
+
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|c| c.map_signals(rename))
+            .collect();
+        let mut circuit = Circuit::new(self.inputs, self.outputs, constraints);
+        for hint in self.hints {
+            circuit = circuit.with_hint(Hint {
+                inputs: rename_all(&hint.inputs),
+                outputs: rename_all(&hint.outputs),
+                function: hint.function,
+            });
+        }
+        circuit
+    }
+
+    fn temp(&mut self) -> Signal {
+        let sig = Signal::Witness(format!("$t{}", self.temps).into());
+        self.temps += 1;
+        sig
+    }
+
+    fn is_temp(&self, sig: &Signal) -> bool {
+        match sig {
+            Signal::Witness(name) => name
+                .strip_prefix("$t")
+                .and_then(|n| n.parse::<usize>().ok())
+                .is_some_and(|n| n < self.temps),
+            _ => false,
+        }
+    }
+
+    /// A linear combination as the simplest signal: a constant if it has no
+    /// terms, the signal itself if it is a single one
+    fn linear(lc: LinearCombination) -> Signal {
+        match (lc.as_constant(), lc.terms.as_slice()) {
+            (Some(value), _) => Signal::Constant(value),
+            (None, [(sig, 1)]) if lc.constant == 0 => sig.clone(),
+            _ => Signal::Linear(lc),
+        }
+    }
+
+    /// A named signal holding `value`, copying a linear combination into a temporary
+    fn named(&mut self, value: &Signal) -> Signal {
+        match value {
+            Signal::Linear(_) => self.push(value, &Signal::Constant(0), Operation::Add),
+            named => named.clone(),
+        }
+    }
+
+    fn push(&mut self, left: &Signal, right: &Signal, operation: Operation) -> Signal {
+        let output = self.temp();
+        self.constraints.push(Constraint {
+            left: left.clone(),
+            right: right.clone(),
+            output: output.clone(),
+            operation,
+        });
+        output
+    }
+}
+
+impl CircuitSym for CircuitBuilder {
+    type Repr = Signal;
+
+    fn input(&mut self, name: &str) -> Signal {
+        check_name(name);
+        let sig = Signal::Input(name.into());
+        if !self.inputs.iter().any(|s| s.name() == Some(name)) {
+            self.inputs.push(sig.clone());
+        }
+        sig
+    }
+
+    fn witness(&mut self, name: &str) -> Signal {
+        check_name(name);
+        Signal::Witness(name.into())
+    }
+
+    fn constant(&mut self, value: u128) -> Signal {
+        Signal::Constant(value)
+    }
+
+    fn add(&mut self, a: &Signal, b: &Signal) -> Signal {
+        Self::linear(LinearCombination::from_signal(a).add(&LinearCombination::from_signal(b)))
+    }
+
+    fn sub(&mut self, a: &Signal, b: &Signal) -> Signal {
+        Self::linear(LinearCombination::from_signal(a).sub(&LinearCombination::from_signal(b)))
+    }
+
+    fn mul(&mut self, a: &Signal, b: &Signal) -> Signal {
+        let (a, b) = (
+            LinearCombination::from_signal(a),
+            LinearCombination::from_signal(b),
+        );
+        match (a.as_constant(), b.as_constant()) {
+            (Some(k), _) => Self::linear(b.scale(k)),
+            (_, Some(k)) => Self::linear(a.scale(k)),
+            _ => self.push(&Self::linear(a), &Self::linear(b), Operation::Mul),
+        }
+    }
+
+    fn select(&mut self, cond: &Signal, then: &Signal, otherwise: &Signal) -> Signal {
+        let output = self.temp();
+        self.constraints.push(Constraint::select(
+            cond.clone(),
+            then.clone(),
+            otherwise.clone(),
+            output.clone(),
+        ));
+        output
+    }
+
+    fn bits(&mut self, value: &Signal, count: usize) -> Vec<Signal> {
+        let value = self.named(value);
+        let Some(name) = value.name() else {
+            let Signal::Constant(v) = value else {
+                unreachable!("only constants have no name")
+            };
+            return FieldElement::new(v, PRIME)
+                .to_bits(count)
+                .into_iter()
+                .map(|b| Signal::Constant(b.value))
+                .collect();
+        };
+        let name = name.to_string();
+        let bits: Vec<Signal> = (0..count).map(|_| self.temp()).collect();
+        self.hints.push(Hint {
+            inputs: vec![name],
+            outputs: bits
+                .iter()
+                .filter_map(|b| b.name().map(str::to_string))
+                .collect(),
+            function: Arc::new(move |x| x[0].to_bits(count)),
+        });
+        bits
+    }
+
+    fn assert_eq(&mut self, a: &Signal, b: &Signal) {
+        self.push(a, b, Operation::Eq);
+    }
+
+    fn output(&mut self, name: &str, value: &Signal) {
+        check_name(name);
+        let output = Signal::Output(name.into());
+        let renamed = match value.name() {
+            Some(temp) if self.is_temp(value) && !self.aliases.contains_key(temp) => {
+                self.aliases.insert(temp.to_string(), output.clone());
+                true
+            }
+            _ => false,
+        };
+        if !renamed {
+            // inputs, constants, linear combinations and values already exposed need a copy
+            self.constraints.push(Constraint {
+                left: value.clone(),
+                right: Signal::Constant(0),
+                output: output.clone(),
+                operation: Operation::Add,
+            });
+        }
+        self.outputs.push(output);
+    }
+}
+
+fn check_name(name: &str) {
+    assert!(
+        !name.starts_with('$'),
+        "`{}`: names starting with `$` are reserved for temporaries",
+        name
+    );
+}
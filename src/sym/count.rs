@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use crate::{sym::CircuitSym, PRIME};
+
+/// Counts the constraints `CircuitBuilder` would emit, without building anything.
+/// Bits come from hints and cost nothing until they are constrained. Terms
+/// cancelling out, as in `x - x`, are not noticed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintCounter {
+    /// Additions, which are linear and cost nothing
+    pub add: usize,
+    /// Subtractions, which are linear and cost nothing
+    pub sub: usize,
+    /// Multiplications where neither side is a constant
+    pub mul: usize,
+    pub select: usize,
+    pub assert_eq: usize,
+    /// Copies into a named signal: outputs that are inputs, constants, linear
+    /// combinations or exposed twice, and linear combinations split into bits
+    pub copies: usize,
+    computed: usize,
+    exposed: HashSet<usize>,
+}
+
+/// What the counter knows about a value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Counted {
+    Constant(u128),
+    /// An input or a witness
+    Named,
+    /// A linear combination of several values
+    Linear,
+    /// The result of a constraint, with an id
+    Computed(usize),
+}
+
+impl ConstraintCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.mul + self.select + self.assert_eq + self.copies
+    }
+
+    fn computed(&mut self) -> Counted {
+        self.computed += 1;
+        Counted::Computed(self.computed)
+    }
+}
+
+impl CircuitSym for ConstraintCounter {
+    type Repr = Counted;
+
+    fn input(&mut self, _name: &str) -> Counted {
+        Counted::Named
+    }
+
+    fn witness(&mut self, _name: &str) -> Counted {
+        Counted::Named
+    }
+
+    fn constant(&mut self, value: u128) -> Counted {
+        Counted::Constant(value % PRIME)
+    }
+
+    fn add(&mut self, a: &Counted, b: &Counted) -> Counted {
+        self.add += 1;
+        match (a, b) {
+            (Counted::Constant(x), Counted::Constant(y)) => Counted::Constant((x + y) % PRIME),
+            (value, Counted::Constant(0)) | (Counted::Constant(0), value) => *value,
+            _ => Counted::Linear,
+        }
+    }
+
+    fn sub(&mut self, a: &Counted, b: &Counted) -> Counted {
+        self.sub += 1;
+        match (a, b) {
+            (Counted::Constant(x), Counted::Constant(y)) => {
+                Counted::Constant((x + PRIME - y) % PRIME)
+            }
+            (value, Counted::Constant(0)) => *value,
+            _ => Counted::Linear,
+        }
+    }
+
+    fn mul(&mut self, a: &Counted, b: &Counted) -> Counted {
+        match (a, b) {
+            (Counted::Constant(x), Counted::Constant(y)) => Counted::Constant(x * y % PRIME),
+            (_, Counted::Constant(0)) | (Counted::Constant(0), _) => Counted::Constant(0),
+            (value, Counted::Constant(1)) | (Counted::Constant(1), value) => *value,
+            (_, Counted::Constant(_)) | (Counted::Constant(_), _) => Counted::Linear,
+            _ => {
+                self.mul += 1;
+                self.computed()
+            }
+        }
+    }
+
+    fn select(&mut self, _cond: &Counted, _then: &Counted, _otherwise: &Counted) -> Counted {
+        self.select += 1;
+        self.computed()
+    }
+
+    fn bits(&mut self, value: &Counted, count: usize) -> Vec<Counted> {
+        match value {
+            Counted::Constant(v) => (0..count).map(|i| Counted::Constant(v >> i & 1)).collect(),
+            Counted::Named | Counted::Linear | Counted::Computed(_) => {
+                // the hint needs a named signal
+                if *value == Counted::Linear {
+                    self.copies += 1;
+                }
+                (0..count).map(|_| self.computed()).collect()
+            }
+        }
+    }
+
+    fn assert_eq(&mut self, _a: &Counted, _b: &Counted) {
+        self.assert_eq += 1;
+        self.computed();
+    }
+
+    fn output(&mut self, _name: &str, value: &Counted) {
+        match value {
+            Counted::Computed(id) if self.exposed.insert(*id) => {}
+            _ => self.copies += 1,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::{field_element::FieldElement, sym::CircuitSym, witness::Witness, PRIME};
+
+/// Runs the circuit directly on field elements
+#[derive(Clone, Debug)]
+pub struct Evaluator {
+    values: HashMap<String, FieldElement>,
+    pub outputs: Vec<(String, FieldElement)>,
+    /// Every `assert_eq` or `select` whose check did not hold
+    pub failures: Vec<String>,
+}
+
+impl Evaluator {
+    /// `witness` holds the public inputs and private values by name
+    pub fn new(witness: &Witness) -> Self {
+        Self {
+            values: witness.values.clone(),
+            outputs: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn output_value(&self, name: &str) -> Option<&FieldElement> {
+        self.outputs.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    fn value(&self, name: &str) -> FieldElement {
+        self.values
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Missing value for signal '{}'", name))
+    }
+}
+
+impl CircuitSym for Evaluator {
+    type Repr = FieldElement;
+
+    fn input(&mut self, name: &str) -> FieldElement {
+        self.value(name)
+    }
+
+    fn witness(&mut self, name: &str) -> FieldElement {
+        self.value(name)
+    }
+
+    fn constant(&mut self, value: u128) -> FieldElement {
+        FieldElement::new(value, PRIME)
+    }
+
+    fn add(&mut self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        a.add(b)
+    }
+
+    fn sub(&mut self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        a.sub(b)
+    }
+
+    fn mul(&mut self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        a.mul(b)
+    }
+
+    fn select(
+        &mut self,
+        cond: &FieldElement,
+        then: &FieldElement,
+        otherwise: &FieldElement,
+    ) -> FieldElement {
+        match cond.value {
+            1 => then.clone(),
+            0 => otherwise.clone(),
+            other => {
+                self.failures
+                    .push(format!("select condition must be 0 or 1, got {}", other));
+                otherwise.clone()
+            }
+        }
+    }
+
+    fn bits(&mut self, value: &FieldElement, count: usize) -> Vec<FieldElement> {
+        value.to_bits(count)
+    }
+
+    fn assert_eq(&mut self, a: &FieldElement, b: &FieldElement) {
+        if !a.equals(b) {
+            self.failures
+                .push(format!("assertion failed: {} != {}", a.value, b.value));
+        }
+    }
+
+    fn output(&mut self, name: &str, value: &FieldElement) {
+        self.outputs.push((name.to_string(), value.clone()));
+    }
+}
@@ -0,0 +1,59 @@
+//! Tagless-final interface for writing a circuit once as a generic function
+//! and interpreting it in several ways. `Field`, `Bool` and `UInt` add types on top.
+//!
+//! ```ignore
+//!   fn affine<S: CircuitSym>(s: &mut S) {
+//!       let x = s.input("x");
+//!       let w = s.witness("w");
+//!       let sum = s.add(&w, &x);
+//!       let out = s.mul(&sum, &x);
+//!       s.output("out", &out);
+//!   }
+//! ```
+
+mod builder;
+mod count;
+mod eval;
+mod partial;
+mod pretty;
+mod typed;
+mod wrapping;
+
+pub use builder::CircuitBuilder;
+pub use count::{ConstraintCounter, Counted};
+pub use eval::Evaluator;
+pub use partial::{Partial, PartialEvaluator};
+pub use pretty::PrettyPrinter;
+pub use typed::{Bool, Field, Typed, UInt};
+pub use wrapping::{le_bits, UInt32, UInt64, WrappingUInt};
+
+/// The operations a circuit is written in, independent of what they mean
+pub trait CircuitSym {
+    /// What a value is to this interpreter: a field element, a signal, a string...
+    type Repr: Clone;
+
+    /// A public input
+    fn input(&mut self, name: &str) -> Self::Repr;
+    /// A private value supplied by the prover
+    fn witness(&mut self, name: &str) -> Self::Repr;
+    fn constant(&mut self, value: u128) -> Self::Repr;
+
+    fn add(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr;
+    fn sub(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr;
+    fn mul(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr;
+    /// `then` if `cond` is 1, `otherwise` if it is 0
+    fn select(
+        &mut self,
+        cond: &Self::Repr,
+        then: &Self::Repr,
+        otherwise: &Self::Repr,
+    ) -> Self::Repr;
+
+    /// The lowest `count` bits of `value`, least significant first. Nothing checks
+    /// them: callers constrain the bits themselves, as `UInt` does.
+    fn bits(&mut self, value: &Self::Repr, count: usize) -> Vec<Self::Repr>;
+
+    fn assert_eq(&mut self, a: &Self::Repr, b: &Self::Repr);
+    /// Exposes `value` as the output `name`
+    fn output(&mut self, name: &str, value: &Self::Repr);
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::{field_element::FieldElement, sym::CircuitSym, PRIME};
+
+/// A value the partial evaluator either knows at construction time or has to
+/// leave to the underlying interpreter
+#[derive(Clone, Debug)]
+pub enum Partial<R> {
+    Known(FieldElement),
+    Dynamic(R),
+}
+
+/// Folds operations on constants (and on inputs fixed with `with_known`) before
+/// handing whatever is left to another interpreter, e.g. a `CircuitBuilder`
+#[derive(Clone, Debug)]
+pub struct PartialEvaluator<S> {
+    inner: S,
+    known: HashMap<String, FieldElement>,
+}
+
+impl<S: CircuitSym> PartialEvaluator<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            known: HashMap::new(),
+        }
+    }
+
+    /// Specializes the circuit for a fixed value of the input `name`
+    pub fn with_known(mut self, name: &str, value: u128) -> Self {
+        self.known
+            .insert(name.to_string(), FieldElement::new(value, PRIME));
+        self
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn lower(&mut self, value: &Partial<S::Repr>) -> S::Repr {
+        match value {
+            Partial::Known(v) => self.inner.constant(v.value),
+            Partial::Dynamic(r) => r.clone(),
+        }
+    }
+}
+
+impl<S: CircuitSym> CircuitSym for PartialEvaluator<S> {
+    type Repr = Partial<S::Repr>;
+
+    fn input(&mut self, name: &str) -> Self::Repr {
+        match self.known.get(name) {
+            Some(value) => Partial::Known(value.clone()),
+            None => Partial::Dynamic(self.inner.input(name)),
+        }
+    }
+
+    fn witness(&mut self, name: &str) -> Self::Repr {
+        Partial::Dynamic(self.inner.witness(name))
+    }
+
+    fn constant(&mut self, value: u128) -> Self::Repr {
+        Partial::Known(FieldElement::new(value, PRIME))
+    }
+
+    fn add(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr {
+        match (a, b) {
+            (Partial::Known(x), Partial::Known(y)) => Partial::Known(x.add(y)),
+            (Partial::Known(zero), other) | (other, Partial::Known(zero)) if zero.value == 0 => {
+                other.clone()
+            }
+            _ => {
+                let (a, b) = (self.lower(a), self.lower(b));
+                Partial::Dynamic(self.inner.add(&a, &b))
+            }
+        }
+    }
+
+    fn sub(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr {
+        match (a, b) {
+            (Partial::Known(x), Partial::Known(y)) => Partial::Known(x.sub(y)),
+            (other, Partial::Known(zero)) if zero.value == 0 => other.clone(),
+            _ => {
+                let (a, b) = (self.lower(a), self.lower(b));
+                Partial::Dynamic(self.inner.sub(&a, &b))
+            }
+        }
+    }
+
+    fn mul(&mut self, a: &Self::Repr, b: &Self::Repr) -> Self::Repr {
+        match (a, b) {
+            (Partial::Known(x), Partial::Known(y)) => Partial::Known(x.mul(y)),
+            (Partial::Known(zero), _) | (_, Partial::Known(zero)) if zero.value == 0 => {
+                Partial::Known(zero.clone())
+            }
+            (Partial::Known(one), other) | (other, Partial::Known(one)) if one.value == 1 => {
+                other.clone()
+            }
+            _ => {
+                let (a, b) = (self.lower(a), self.lower(b));
+                Partial::Dynamic(self.inner.mul(&a, &b))
+            }
+        }
+    }
+
+    fn select(
+        &mut self,
+        cond: &Self::Repr,
+        then: &Self::Repr,
+        otherwise: &Self::Repr,
+    ) -> Self::Repr {
+        match cond {
+            Partial::Known(c) if c.value == 1 => then.clone(),
+            Partial::Known(c) if c.value == 0 => otherwise.clone(),
+            Partial::Known(c) => panic!("Select condition must be 0 or 1, got {}", c.value),
+            Partial::Dynamic(_) => {
+                let (cond, then, otherwise) =
+                    (self.lower(cond), self.lower(then), self.lower(otherwise));
+                Partial::Dynamic(self.inner.select(&cond, &then, &otherwise))
+            }
+        }
+    }
+
+    fn bits(&mut self, value: &Self::Repr, count: usize) -> Vec<Self::Repr> {
+        match value {
+            Partial::Known(v) => v.to_bits(count).into_iter().map(Partial::Known).collect(),
+            Partial::Dynamic(r) => self
+                .inner
+                .bits(r, count)
+                .into_iter()
+                .map(Partial::Dynamic)
+                .collect(),
+        }
+    }
+
+    fn assert_eq(&mut self, a: &Self::Repr, b: &Self::Repr) {
+        match (a, b) {
+            (Partial::Known(x), Partial::Known(y)) => assert!(
+                x.equals(y),
+                "Assertion between constants failed: {} != {}",
+                x.value,
+                y.value
+            ),
+            _ => {
+                let (a, b) = (self.lower(a), self.lower(b));
+                self.inner.assert_eq(&a, &b);
+            }
+        }
+    }
+
+    fn output(&mut self, name: &str, value: &Self::Repr) {
+        let value = self.lower(value);
+        self.inner.output(name, &value);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::sym::CircuitSym;
+
+/// Renders the circuit as readable statements, with values as nested expressions
+#[derive(Clone, Debug, Default)]
+pub struct PrettyPrinter {
+    lines: Vec<String>,
+}
+
+impl PrettyPrinter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn finish(self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+impl CircuitSym for PrettyPrinter {
+    type Repr = String;
+
+    fn input(&mut self, name: &str) -> String {
+        self.lines.push(format!("input {}", name));
+        name.to_string()
+    }
+
+    fn witness(&mut self, name: &str) -> String {
+        self.lines.push(format!("witness {}", name));
+        name.to_string()
+    }
+
+    fn constant(&mut self, value: u128) -> String {
+        value.to_string()
+    }
+
+    fn add(&mut self, a: &String, b: &String) -> String {
+        format!("({} + {})", a, b)
+    }
+
+    fn sub(&mut self, a: &String, b: &String) -> String {
+        format!("({} - {})", a, b)
+    }
+
+    fn mul(&mut self, a: &String, b: &String) -> String {
+        format!("({} * {})", a, b)
+    }
+
+    fn select(&mut self, cond: &String, then: &String, otherwise: &String) -> String {
+        format!("({} ? {} : {})", cond, then, otherwise)
+    }
+
+    fn bits(&mut self, value: &String, count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("bit{}({})", i, value)).collect()
+    }
+
+    fn assert_eq(&mut self, a: &String, b: &String) {
+        self.lines.push(format!("assert {} == {}", a, b));
+    }
+
+    fn output(&mut self, name: &str, value: &String) {
+        self.lines.push(format!("output {} = {}", name, value));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{sym::CircuitSym, PRIME};
+
+/// A value with a type. Wrapping a raw value never adds constraints, so
+/// `from_repr_unchecked` is only for values already known to have the type.
+pub trait Typed<R>: Sized {
+    fn repr(&self) -> &R;
+    fn from_repr_unchecked(repr: R) -> Self;
+}
+
+/// Any field element
+#[derive(Clone, Debug)]
+pub struct Field<R>(R);
+
+/// A field element constrained to 0 or 1.
+///
+/// Booleans and field elements don't mix without an explicit conversion:
+///
+/// ```compile_fail
+/// # use ghayb::{Bool, CircuitBuilder, Field};
+/// let mut s = CircuitBuilder::new();
+/// let flag = Bool::witness(&mut s, "flag");
+/// let hash = Field::input(&mut s, "hash");
+/// flag.and(&mut s, &hash);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Bool<R>(R);
+
+/// An integer in `0..2^N`, range checked through its bits. `2^N` must not
+/// exceed the field, and arithmetic fails instead of wrapping around: `add`
+/// needs sums up to `2^(N+1)` and `mul` products up to `2^(2N)` to fit in the
+/// field too, so that only the `N`-bit check can fail.
+///
+/// Integers of different widths don't mix:
+///
+/// ```compile_fail
+/// # use ghayb::{CircuitBuilder, UInt};
+/// let mut s = CircuitBuilder::new();
+/// let a = UInt::<_, 8>::input(&mut s, "a");
+/// let b = UInt::<_, 4>::input(&mut s, "b");
+/// a.add(&mut s, &b);
+/// ```
+#[derive(Clone, Debug)]
+pub struct UInt<R, const N: usize>(R);
+
+impl<R: Clone> Typed<R> for Field<R> {
+    fn repr(&self) -> &R {
+        &self.0
+    }
+
+    fn from_repr_unchecked(repr: R) -> Self {
+        Self(repr)
+    }
+}
+
+impl<R: Clone> Typed<R> for Bool<R> {
+    fn repr(&self) -> &R {
+        &self.0
+    }
+
+    fn from_repr_unchecked(repr: R) -> Self {
+        Self(repr)
+    }
+}
+
+impl<R: Clone, const N: usize> Typed<R> for UInt<R, N> {
+    fn repr(&self) -> &R {
+        &self.0
+    }
+
+    fn from_repr_unchecked(repr: R) -> Self {
+        Self(repr)
+    }
+}
+
+impl<R: Clone> Field<R> {
+    pub fn input<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        Self(s.input(name))
+    }
+
+    pub fn witness<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        Self(s.witness(name))
+    }
+
+    pub fn constant<S: CircuitSym<Repr = R>>(s: &mut S, value: u128) -> Self {
+        Self(s.constant(value))
+    }
+
+    pub fn add<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self(s.add(&self.0, &other.0))
+    }
+
+    pub fn sub<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self(s.sub(&self.0, &other.0))
+    }
+
+    pub fn mul<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self(s.mul(&self.0, &other.0))
+    }
+
+    pub fn assert_eq<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) {
+        s.assert_eq(&self.0, &other.0);
+    }
+
+    pub fn output<S: CircuitSym<Repr = R>>(&self, s: &mut S, name: &str) {
+        s.output(name, &self.0);
+    }
+}
+
+impl<R: Clone> Bool<R> {
+    /// A public input, constrained to 0 or 1
+    pub fn input<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        let value = s.input(name);
+        Self::checked(s, value)
+    }
+
+    /// A private value, constrained to 0 or 1
+    pub fn witness<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        let value = s.witness(name);
+        Self::checked(s, value)
+    }
+
+    pub fn constant<S: CircuitSym<Repr = R>>(s: &mut S, value: bool) -> Self {
+        Self(s.constant(value as u128))
+    }
+
+    /// Constrains `value` to 0 or 1: `value * value == value`
+    pub fn checked<S: CircuitSym<Repr = R>>(s: &mut S, value: R) -> Self {
+        let square = s.mul(&value, &value);
+        s.assert_eq(&square, &value);
+        Self(value)
+    }
+
+    pub fn and<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self(s.mul(&self.0, &other.0))
+    }
+
+    /// `a + b - a * b`
+    pub fn or<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        let sum = s.add(&self.0, &other.0);
+        let both = s.mul(&self.0, &other.0);
+        Self(s.sub(&sum, &both))
+    }
+
+    /// `a + b - 2 * a * b`
+    pub fn xor<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        let sum = s.add(&self.0, &other.0);
+        let both = s.mul(&self.0, &other.0);
+        let twice = s.add(&both, &both);
+        Self(s.sub(&sum, &twice))
+    }
+
+    pub fn not<S: CircuitSym<Repr = R>>(&self, s: &mut S) -> Self {
+        let one = s.constant(1);
+        Self(s.sub(&one, &self.0))
+    }
+
+    /// `then` if the value is true, `otherwise` if false. Both branches have the
+    /// same type, so the result has it too.
+    pub fn select<S: CircuitSym<Repr = R>, T: Typed<R>>(
+        &self,
+        s: &mut S,
+        then: &T,
+        otherwise: &T,
+    ) -> T {
+        T::from_repr_unchecked(s.select(&self.0, then.repr(), otherwise.repr()))
+    }
+
+    pub fn to_field(&self) -> Field<R> {
+        Field(self.0.clone())
+    }
+
+    pub fn assert_eq<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) {
+        s.assert_eq(&self.0, &other.0);
+    }
+
+    pub fn output<S: CircuitSym<Repr = R>>(&self, s: &mut S, name: &str) {
+        s.output(name, &self.0);
+    }
+}
+
+impl<R: Clone, const N: usize> UInt<R, N> {
+    /// A public input, range checked to `N` bits
+    pub fn input<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        let value = s.input(name);
+        Self::checked(s, value)
+    }
+
+    /// A private value, range checked to `N` bits
+    pub fn witness<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        let value = s.witness(name);
+        Self::checked(s, value)
+    }
+
+    pub fn constant<S: CircuitSym<Repr = R>>(s: &mut S, value: u128) -> Self {
+        assert!(
+            value < Self::bound(),
+            "Constant {} does not fit in UInt<{}>",
+            value,
+            N
+        );
+        Self(s.constant(value))
+    }
+
+    /// Constrains `value` to `N` bits: every bit is boolean and they add up to it
+    pub fn checked<S: CircuitSym<Repr = R>>(s: &mut S, value: R) -> Self {
+        let value = Self(value);
+        value.to_bits(s);
+        value
+    }
+
+    /// Fails to prove if the sum does not fit in `N` bits
+    pub fn add<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self::no_wrap("sums", N + 1);
+        let sum = s.add(&self.0, &other.0);
+        Self::checked(s, sum)
+    }
+
+    /// Fails to prove if the product does not fit in `N` bits
+    pub fn mul<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self::no_wrap("products", 2 * N);
+        let product = s.mul(&self.0, &other.0);
+        Self::checked(s, product)
+    }
+
+    /// The bits of the value, least significant first
+    pub fn to_bits<S: CircuitSym<Repr = R>>(&self, s: &mut S) -> Vec<Bool<R>> {
+        Self::bound();
+        let bits: Vec<Bool<R>> = s
+            .bits(&self.0, N)
+            .into_iter()
+            .map(|bit| Bool::checked(s, bit))
+            .collect();
+        let sum = Self::recompose(s, &bits);
+        s.assert_eq(&sum, &self.0);
+        bits
+    }
+
+    pub fn to_field(&self) -> Field<R> {
+        Field(self.0.clone())
+    }
+
+    pub fn assert_eq<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) {
+        s.assert_eq(&self.0, &other.0);
+    }
+
+    pub fn output<S: CircuitSym<Repr = R>>(&self, s: &mut S, name: &str) {
+        s.output(name, &self.0);
+    }
+
+    /// `2^N`, which has to fit in the field for the bits to be unique
+    fn bound() -> u128 {
+        assert!(
+            N > 0 && N < 128 && 1u128 << N <= PRIME,
+            "UInt<{}> does not fit in the field of size {}",
+            N,
+            PRIME
+        );
+        1 << N
+    }
+
+    /// Results below `2^bits` are the integers themselves, not reduced by the
+    /// field, so checking that they fit in `N` bits is enough
+    fn no_wrap(results: &str, bits: usize) {
+        assert!(
+            bits < 128 && 1u128 << bits <= PRIME,
+            "UInt<{}> {} can wrap around the field of size {}",
+            N,
+            results,
+            PRIME
+        );
+    }
+
+    /// `sum(bits[i] * 2^i)`
+    fn recompose<S: CircuitSym<Repr = R>>(s: &mut S, bits: &[Bool<R>]) -> R {
+        let mut sum = bits[0].0.clone();
+        for (i, bit) in bits.iter().enumerate().skip(1) {
+            let weight = s.constant(1 << i);
+            let term = s.mul(&bit.0, &weight);
+            sum = s.add(&sum, &term);
+        }
+        sum
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    field_element::FieldElement,
+    signal::Signal,
+    sym::{Bool, CircuitSym, Typed},
+};
+
+/// A machine integer of `N` bits kept as `N` booleans, least significant first.
+/// Arithmetic wraps around like `u32::wrapping_add`. The field is far too small to
+/// hold such a value in one element, so inputs are arrays of bits: a `UInt32`
+/// input `x` is read from the signals `x[0]` to `x[31]`.
+#[derive(Clone, Debug)]
+pub struct WrappingUInt<R, const N: usize> {
+    bits: Vec<Bool<R>>,
+}
+
+pub type UInt32<R> = WrappingUInt<R, 32>;
+pub type UInt64<R> = WrappingUInt<R, 64>;
+
+/// The bits of `value`, least significant first, e.g. to assign a `UInt32` input
+/// with `witness! { x = le_bits::<32>(value) }`
+pub fn le_bits<const N: usize>(value: u128) -> [u128; N] {
+    std::array::from_fn(|i| (value >> i) & 1)
+}
+
+impl<R: Clone, const N: usize> WrappingUInt<R, N> {
+    /// A public input, one boolean signal per bit
+    pub fn input<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        Self::from_bits(
+            (0..N)
+                .map(|i| Bool::input(s, &Signal::element_name(name, &[i])))
+                .collect(),
+        )
+    }
+
+    /// A private value, one boolean signal per bit
+    pub fn witness<S: CircuitSym<Repr = R>>(s: &mut S, name: &str) -> Self {
+        Self::from_bits(
+            (0..N)
+                .map(|i| Bool::witness(s, &Signal::element_name(name, &[i])))
+                .collect(),
+        )
+    }
+
+    pub fn constant<S: CircuitSym<Repr = R>>(s: &mut S, value: u128) -> Self {
+        Self::from_bits(
+            le_bits::<N>(value)
+                .iter()
+                .map(|&bit| Bool::constant(s, bit == 1))
+                .collect(),
+        )
+    }
+
+    pub fn from_bits(bits: Vec<Bool<R>>) -> Self {
+        assert_eq!(
+            bits.len(),
+            N,
+            "UInt of {} bits built from {} bits",
+            N,
+            bits.len()
+        );
+        Self { bits }
+    }
+
+    /// Least significant first
+    pub fn bits(&self) -> &[Bool<R>] {
+        &self.bits
+    }
+
+    pub fn add<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        Self::from_bits(add_bits(s, &self.bits, &other.bits))
+    }
+
+    /// Shift-and-add, keeping the low `N` bits of every partial product
+    pub fn mul<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        let mut acc: Vec<Bool<R>> = self.bits.iter().map(|a| a.and(s, &other.bits[0])).collect();
+        for i in 1..N {
+            let row: Vec<Bool<R>> = self.bits[..N - i]
+                .iter()
+                .map(|a| a.and(s, &other.bits[i]))
+                .collect();
+            // the low i bits of the accumulator can't change any more
+            let high = add_bits(s, &acc[i..], &row);
+            acc.truncate(i);
+            acc.extend(high);
+        }
+        Self::from_bits(acc)
+    }
+
+    pub fn and<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        self.bitwise(s, other, Bool::and)
+    }
+
+    pub fn or<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        self.bitwise(s, other, Bool::or)
+    }
+
+    pub fn xor<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) -> Self {
+        self.bitwise(s, other, Bool::xor)
+    }
+
+    pub fn not<S: CircuitSym<Repr = R>>(&self, s: &mut S) -> Self {
+        Self::from_bits(self.bits.iter().map(|b| b.not(s)).collect())
+    }
+
+    /// `self << by`, shifting in zeros. Costs no constraints.
+    pub fn shl<S: CircuitSym<Repr = R>>(&self, s: &mut S, by: usize) -> Self {
+        let by = by.min(N);
+        let zero = Bool::constant(s, false);
+        let mut bits = vec![zero; by];
+        bits.extend_from_slice(&self.bits[..N - by]);
+        Self::from_bits(bits)
+    }
+
+    /// `self >> by`, shifting in zeros. Costs no constraints.
+    pub fn shr<S: CircuitSym<Repr = R>>(&self, s: &mut S, by: usize) -> Self {
+        let by = by.min(N);
+        let zero = Bool::constant(s, false);
+        let mut bits = self.bits[by..].to_vec();
+        bits.resize(N, zero);
+        Self::from_bits(bits)
+    }
+
+    /// Costs no constraints
+    pub fn rotl(&self, by: usize) -> Self {
+        let mut bits = self.bits.clone();
+        bits.rotate_right(by % N);
+        Self::from_bits(bits)
+    }
+
+    /// Costs no constraints
+    pub fn rotr(&self, by: usize) -> Self {
+        let mut bits = self.bits.clone();
+        bits.rotate_left(by % N);
+        Self::from_bits(bits)
+    }
+
+    pub fn assert_eq<S: CircuitSym<Repr = R>>(&self, s: &mut S, other: &Self) {
+        for (a, b) in self.bits.iter().zip(&other.bits) {
+            a.assert_eq(s, b);
+        }
+    }
+
+    /// Exposes the bits as the outputs `name[0]` to `name[N - 1]`
+    pub fn output<S: CircuitSym<Repr = R>>(&self, s: &mut S, name: &str) {
+        for (i, bit) in self.bits.iter().enumerate() {
+            bit.output(s, &Signal::element_name(name, &[i]));
+        }
+    }
+
+    fn bitwise<S: CircuitSym<Repr = R>>(
+        &self,
+        s: &mut S,
+        other: &Self,
+        op: fn(&Bool<R>, &mut S, &Bool<R>) -> Bool<R>,
+    ) -> Self {
+        Self::from_bits(
+            self.bits
+                .iter()
+                .zip(&other.bits)
+                .map(|(a, b)| op(a, s, b))
+                .collect(),
+        )
+    }
+}
+
+impl<const N: usize> WrappingUInt<FieldElement, N> {
+    /// The integer, when the circuit runs on concrete values
+    pub fn value(&self) -> u128 {
+        self.bits
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| bit.repr().value << i)
+            .sum()
+    }
+}
+
+/// Ripple-carry addition of two equally long bit strings, dropping the last carry
+fn add_bits<S: CircuitSym>(
+    s: &mut S,
+    a: &[Bool<S::Repr>],
+    b: &[Bool<S::Repr>],
+) -> Vec<Bool<S::Repr>> {
+    let mut carry: Option<Bool<S::Repr>> = None;
+    let mut sum = Vec::with_capacity(a.len());
+    for (i, (x, y)) in a.iter().zip(b).enumerate() {
+        let half = x.xor(s, y);
+        sum.push(match &carry {
+            Some(c) => half.xor(s, c),
+            None => half.clone(),
+        });
+        if i + 1 < a.len() {
+            let both = x.and(s, y);
+            carry = Some(match &carry {
+                // x & y and c & (x ^ y) are never both set, so adding them is an or
+                Some(c) => {
+                    let propagated = c.and(s, &half);
+                    Bool::from_repr_unchecked(s.add(both.repr(), propagated.repr()))
+                }
+                None => both,
+            });
+        }
+    }
+    sum
+}
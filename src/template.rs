@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{circuit::Circuit, hint::Hint, signal::Signal};
+
+/// Builds the body of a template from its parameters
+pub type TemplateFn = Arc<dyn Fn(&[u128]) -> Circuit + Send + Sync>;
+
+/// A reusable sub-circuit, like a Circom template: defined once, parameterized
+/// by compile-time values and instantiated under a namespace as often as needed
+#[derive(Clone)]
+pub struct Template {
+    pub name: String,
+    body: TemplateFn,
+}
+
+impl Template {
+    pub fn new<F>(name: &str, body: F) -> Self
+    where
+        F: Fn(&[u128]) -> Circuit + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            body: Arc::new(body),
+        }
+    }
+
+    /// A template without parameters
+    pub fn from_circuit(name: &str, circuit: Circuit) -> Self {
+        Self::new(name, move |_| circuit.clone())
+    }
+
+    /// The body of the template for `params`, with its own signal names
+    pub fn circuit(&self, params: &[u128]) -> Circuit {
+        (self.body)(params)
+    }
+}
+
+impl fmt::Debug for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Template")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Circuit {
+    /// Adds an instance of `template` to this circuit.
+    ///
+    /// Every signal of the instance is renamed to a private `namespace.name` witness
+    /// (e.g. `hasher[0].out`), except the template inputs and outputs listed in
+    /// `bindings`, which are replaced by the given signals of this circuit. The
+    /// other arrays the template declares are declared as witness arrays.
+    pub fn instantiate(
+        &mut self,
+        template: &Template,
+        namespace: &str,
+        params: &[u128],
+        bindings: &[(&str, Signal)],
+    ) {
+        let body = template.circuit(params);
+
+        for (name, _) in bindings {
+            let declared = body
+                .inputs
+                .iter()
+                .chain(&body.outputs)
+                .any(|s| s.name() == Some(*name));
+            assert!(
+                declared,
+                "Template '{}' has no input or output named '{}'",
+                template.name, name
+            );
+        }
+        let bindings: HashMap<&str, &Signal> = bindings.iter().map(|(n, s)| (*n, s)).collect();
+        let rename_name = |name: &str| match bindings.get(name) {
+            Some(bound) => (*bound).clone(),
+            None => Signal::Witness(format!("{}.{}", namespace, name).into()),
+        };
+        let rename = |sig: &Signal| match sig.name() {
+            Some(name) => rename_name(name),
+            None => sig.clone(),
+        };
+
+        // the arrays of the instance become witness arrays of this circuit
+        let unbound = |sig: &&Signal| {
+            let indexed = sig
+                .array_index()
+                .is_some_and(|(_, indices)| !indices.is_empty());
+            indexed && sig.name().is_some_and(|n| !bindings.contains_key(n))
+        };
+        let declared = body
+            .inputs
+            .iter()
+            .chain(&body.outputs)
+            .chain(&body.witnesses);
+        self.witnesses.extend(declared.filter(unbound).map(rename));
+
+        for (i, constraint) in body.constraints.iter().enumerate() {
+            if let Some(source) = body.sources.get(&i) {
+                self.sources.insert(self.constraints.len(), source.clone());
+            }
+            self.constraints.push(constraint.map_signals(rename));
+        }
+        for hint in &body.hints {
+            let rename_all = |names: &[String]| -> Vec<String> {
+                names
+                    .iter()
+                    .map(|n| {
+                        rename_name(n)
+                            .name()
+                            .expect("hint signals can only be bound to named signals")
+                            .to_string()
+                    })
+                    .collect()
+            };
+            self.hints.push(Hint {
+                inputs: rename_all(&hint.inputs),
+                outputs: rename_all(&hint.outputs),
+                function: hint.function.clone(),
+            });
+        }
+    }
+}
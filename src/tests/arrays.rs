@@ -0,0 +1,137 @@
+use crate::{
+    circuit, constraint, prove, verify, witness, Circuit, DecodeError, FieldElement, Signal,
+    WitnessError, WitnessGenerator, PRIME,
+};
+
+#[test]
+fn array_inputs_and_indexed_constraints() {
+    let c = circuit! {
+        [[xs; 3]] -> [out],
+        constraints = [
+            constraint!((xs[0]@Input + xs[1]@Input) -> acc[0]@Witness),
+            constraint!((acc[0]@Witness + xs[2]@Input) -> acc[1]@Witness),
+            constraint!((acc[1]@Witness * w@Witness) -> out@Output)
+        ]
+    };
+
+    let names: Vec<&str> = c.inputs.iter().filter_map(|s| s.name()).collect();
+    assert_eq!(names, vec!["xs[0]", "xs[1]", "xs[2]"]);
+
+    let w = witness! { xs = [1, 2, 3], w = 2 };
+    let proof = prove!(c, w);
+    assert_eq!(proof.revealed_witness["acc[1]"].0.value, 6);
+
+    let is_ok = verify!(
+        c,
+        [
+            FieldElement::new(1, PRIME),
+            FieldElement::new(2, PRIME),
+            FieldElement::new(3, PRIME)
+        ],
+        proof
+    );
+    assert!(is_ok);
+}
+
+#[test]
+fn two_dimensional_arrays() {
+    let i = 1;
+    let c = circuit! {
+        [grid[2][2]] -> [diag],
+        constraints = [
+            constraint!((grid[0][0]@Input + grid[i][i]@Input) -> diag@Output)
+        ]
+    };
+
+    let names: Vec<&str> = c.inputs.iter().filter_map(|s| s.name()).collect();
+    assert_eq!(
+        names,
+        vec!["grid[0][0]", "grid[0][1]", "grid[1][0]", "grid[1][1]"]
+    );
+
+    let w = witness! { grid = [[4, 0], [0, 5]] };
+    assert_eq!(w.values["grid[1][1]"].value, 5);
+
+    let proof = prove!(c, w);
+    let inputs: Vec<FieldElement> = [4, 0, 0, 5]
+        .iter()
+        .map(|v| FieldElement::new(*v, PRIME))
+        .collect();
+    assert!(crate::Verifier::verify(&c, &inputs, &proof));
+}
+
+#[test]
+fn indexed_witness_assignments() {
+    let w = witness! { acc[0] = 7, bits[1][2] = 1, x = 3 };
+    assert_eq!(w.values["acc[0]"].value, 7);
+    assert_eq!(w.values["bits[1][2]"].value, 1);
+    assert_eq!(w.values["x"].value, 3);
+}
+
+#[test]
+fn out_of_bounds_index_is_rejected() {
+    // literal indices are checked at compile time, see the `circuit!` docs
+    let last = 3;
+    let c = circuit! {
+        [xs[3]] -> [out],
+        constraints = [
+            constraint!((xs[0]@Input + xs[last]@Input) -> out@Output)
+        ]
+    };
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { xs = [1, 2, 3] })
+        .unwrap_err();
+    assert_eq!(err.constraint(), Some(0));
+    assert_eq!(
+        err.to_string(),
+        "Signal 'xs[3]' is out of bounds for array 'xs' of shape [3] (constraint #0)"
+    );
+}
+
+#[test]
+fn witness_arrays_are_bounds_checked() {
+    let c = circuit! {
+        [[xs; 3]] -> [out],
+        witnesses = [acc[2]],
+        constraints = {
+            constraint!((xs[0]@Input + xs[1]@Input) -> acc[0]@Witness);
+            for i in 1..3 {
+                constraint!((acc[i - 1]@Witness + xs[i]@Input) -> acc[i]@Witness);
+            }
+            constraint!((acc[1]@Witness + xs[0]@Input) -> out@Output);
+        }
+    };
+    assert_eq!(c.witnesses.len(), 2);
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { xs = [1, 2, 3] })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::OutOfBounds { constraint: 2, ref signal, ref shape }
+            if signal == "acc[2]" && shape == &[2]
+    ));
+
+    // the declaration is written out, and reading it back checks it too
+    let text = c.to_text();
+    assert!(text.starts_with("[xs[3]] -> [out],\nwitnesses = [acc[2]],\n"));
+    let err = Circuit::from_text(&text).unwrap_err();
+    assert_eq!(
+        err.message,
+        "Signal 'acc[2]' is out of bounds for array 'acc' of shape [2]"
+    );
+    assert!(matches!(
+        Circuit::from_bytes(&c.to_bytes()),
+        Err(DecodeError::InvalidCircuit(_))
+    ));
+}
+
+#[test]
+fn structured_names() {
+    let sig = Signal::Input("grid[3][4]".into());
+    assert_eq!(sig.array_index(), Some(("grid", &[3, 4][..])));
+
+    let sig = Signal::Witness("hasher[0].out".into());
+    assert_eq!(sig.array_index(), Some(("hasher[0].out", &[][..])));
+
+    assert_eq!(Signal::element_names("b", &[2]), vec!["b[0]", "b[1]"]);
+}
@@ -0,0 +1,216 @@
+use crate::{
+    prove, read_wtns, witness, write_wtns, CircomError, Circuit, Constraint, FieldElement,
+    Operation, R1cs, Signal, Verifier, Witness, PRIME,
+};
+
+// out = a * b + 3 * a with a public: wires [one, out, a, b, a * b]
+const R1CS: &[u8] = include_bytes!("fixtures/multiplier.r1cs");
+const WTNS: &[u8] = include_bytes!("fixtures/multiplier.wtns");
+
+#[test]
+fn reads_r1cs_header_and_constraints() {
+    let r1cs = R1cs::from_bytes(R1CS).unwrap();
+    assert_eq!(r1cs.num_wires, 5);
+    assert_eq!(
+        (
+            r1cs.num_public_outputs,
+            r1cs.num_public_inputs,
+            r1cs.num_private_inputs
+        ),
+        (1, 1, 1)
+    );
+    assert_eq!(r1cs.constraints.len(), 2);
+    assert!(r1cs.constraints[1].a.is_empty());
+    assert_eq!(r1cs.constraints[1].c[1].1.value, PRIME - 3);
+    assert_eq!(r1cs.wire_labels, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn imported_circuit_proves_and_verifies() {
+    let r1cs = R1cs::from_bytes(R1CS).unwrap();
+    let wires = read_wtns(WTNS).unwrap();
+    assert_eq!(
+        wires.iter().map(|w| w.value).collect::<Vec<_>>(),
+        vec![1, 24, 3, 5, 15]
+    );
+
+    let c = r1cs.to_circuit();
+    let names: Vec<_> = c.inputs.iter().filter_map(|s| s.name()).collect();
+    assert_eq!(names, ["w1", "w2"]);
+
+    let proof = prove!(c, r1cs.witness(&wires));
+    assert!(Verifier::verify(
+        &c,
+        &r1cs.public_signals(&wires).unwrap(),
+        &proof
+    ));
+
+    // values for fewer wires than the header declares
+    assert_eq!(
+        r1cs.public_signals(&wires[..1]).unwrap_err(),
+        CircomError::WireCount {
+            expected: 5,
+            got: 1
+        }
+    );
+}
+
+#[test]
+fn wrong_witness_does_not_satisfy_the_circuit() {
+    let r1cs = R1cs::from_bytes(R1CS).unwrap();
+    let mut wires = read_wtns(WTNS).unwrap();
+    wires[1] = crate::FieldElement::new(25, PRIME);
+    let c = r1cs.to_circuit();
+    assert!(crate::WitnessGenerator::new(&c)
+        .complete(&r1cs.witness(&wires))
+        .is_err());
+}
+
+#[test]
+fn rejects_foreign_fields_and_bad_files() {
+    // the header section starts after magic, version, section count and section header
+    let prime_at = 4 + 4 + 4 + 4 + 8 + 4;
+
+    let mut bn254 = R1CS.to_vec();
+    bn254[prime_at + 31] = 0x30;
+    assert_eq!(
+        R1cs::from_bytes(&bn254).unwrap_err(),
+        CircomError::PrimeTooLarge
+    );
+
+    let mut other = R1CS.to_vec();
+    other[prime_at] ^= 2;
+    assert!(matches!(
+        R1cs::from_bytes(&other).unwrap_err(),
+        CircomError::PrimeMismatch {
+            expected: PRIME,
+            ..
+        }
+    ));
+
+    assert_eq!(
+        R1cs::from_bytes(WTNS).unwrap_err(),
+        CircomError::BadMagic { expected: "r1cs" }
+    );
+    assert_eq!(
+        R1cs::from_bytes(&R1CS[..R1CS.len() - 3]).unwrap_err(),
+        CircomError::UnexpectedEnd
+    );
+
+    let mut not_one = WTNS.to_vec();
+    let first_value = WTNS.len() - 5 * 32;
+    not_one[first_value] = 2;
+    assert_eq!(
+        read_wtns(&not_one).unwrap_err(),
+        CircomError::FirstWireNotOne
+    );
+}
+
+#[test]
+fn writes_files_back_unchanged() {
+    let r1cs = R1cs::from_bytes(R1CS).unwrap();
+    assert_eq!(r1cs.to_bytes(), R1CS);
+    assert_eq!(write_wtns(&read_wtns(WTNS).unwrap()), WTNS);
+}
+
+// out = if c { x * y } else { table[i] } + 5, with x - y == d
+fn exportable() -> (Circuit, Witness) {
+    let w = |name: &str| Signal::Witness(name.into());
+    let constraints = vec![
+        Constraint {
+            left: Signal::Input("x".into()),
+            right: w("y"),
+            output: w("xy"),
+            operation: Operation::Mul,
+        },
+        Constraint {
+            left: Signal::Input("x".into()),
+            right: w("y"),
+            output: w("diff"),
+            operation: Operation::Sub,
+        },
+        Constraint {
+            left: w("diff"),
+            right: w("d"),
+            output: w("checked"),
+            operation: Operation::Eq,
+        },
+        Constraint::mux(
+            w("i"),
+            vec![w("t0"), Signal::Constant(7), w("t2")],
+            w("picked"),
+        ),
+        Constraint::select(w("c"), w("xy"), w("picked"), w("chosen")),
+        Constraint {
+            left: w("chosen"),
+            right: Signal::Constant(5),
+            output: Signal::Output("out".into()),
+            operation: Operation::Add,
+        },
+    ];
+    let circuit = Circuit::new(
+        vec![Signal::Input("x".into())],
+        vec![Signal::Output("out".into())],
+        constraints,
+    );
+    let witness = witness! { x = 9, y = 4, d = 5, i = 1, t0 = 11, t2 = 13, c = 0 };
+    (circuit, witness)
+}
+
+#[test]
+fn exported_circuit_is_satisfied_and_reimports() {
+    let (circuit, witness) = exportable();
+    let export = circuit.to_r1cs();
+    let r1cs = &export.r1cs;
+    assert_eq!(
+        (
+            r1cs.num_public_outputs,
+            r1cs.num_public_inputs,
+            r1cs.num_private_inputs
+        ),
+        (1, 1, 6)
+    );
+
+    let wires = export.wire_values(&witness).unwrap();
+    assert_eq!(wires[0].value, 1);
+    assert_eq!(wires[1].value, 12);
+    assert!(r1cs.is_satisfied(&wires));
+
+    // a wrong output, or a wrong auxiliary wire of the mux, breaks a row
+    for tampered in [1, wires.len() - 1] {
+        let mut cheat = wires.clone();
+        cheat[tampered] = cheat[tampered].add(&FieldElement::new(1, PRIME));
+        assert!(!r1cs.is_satisfied(&cheat));
+    }
+
+    let reread = R1cs::from_bytes(&r1cs.to_bytes()).unwrap();
+    let rewires = read_wtns(&export.wtns_bytes(&witness).unwrap()).unwrap();
+    let c = reread.to_circuit();
+    let proof = prove!(c, reread.witness(&rewires));
+    assert!(Verifier::verify(
+        &c,
+        &reread.public_signals(&rewires).unwrap(),
+        &proof
+    ));
+}
+
+#[test]
+fn exports_snarkjs_json() {
+    let (circuit, witness) = exportable();
+    let export = circuit.to_r1cs();
+    let json: serde_json::Value = serde_json::from_str(&export.r1cs.to_json()).unwrap();
+    assert_eq!(json["prime"], PRIME.to_string());
+    assert_eq!(json["nVars"], export.r1cs.num_wires);
+    assert_eq!(json["nOutputs"], 1);
+    let rows = json["constraints"].as_array().unwrap();
+    assert_eq!(rows.len(), export.r1cs.constraints.len());
+    // x * y = xy
+    assert_eq!(
+        rows[0],
+        serde_json::json!([{"2": "1"}, {"3": "1"}, {"9": "1"}])
+    );
+
+    let values: Vec<String> =
+        serde_json::from_str(&export.witness_json(&witness).unwrap()).unwrap();
+    assert_eq!(values[..3], ["1", "12", "9"]);
+}
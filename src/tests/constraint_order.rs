@@ -0,0 +1,80 @@
+use crate::{circuit, constraint, prove, verify, witness, FieldElement, PRIME};
+
+#[test]
+fn constraints_declared_out_of_order() {
+    let c = circuit! {
+        [x, y] -> [out],
+        constraints = [
+            constraint!((tmp2@Witness - w3@Witness) -> out@Output),
+            constraint!((tmp1@Witness * y@Input) -> tmp2@Witness),
+            constraint!((w1@Witness + x@Input) -> tmp1@Witness)
+        ]
+    };
+
+    assert_eq!(c.constraint_order().unwrap(), vec![2, 1, 0]);
+
+    let w = witness! {
+        w1 = 2,
+        w3 = 1,
+        x = 3,
+        y = 4
+    };
+
+    let proof = prove!(c, w);
+    assert_eq!(proof.revealed_witness["tmp2"].0.value, 20);
+
+    let is_ok = verify!(
+        c,
+        [FieldElement::new(3, PRIME), FieldElement::new(4, PRIME)],
+        proof
+    );
+    assert!(is_ok);
+}
+
+#[test]
+fn independent_constraints_keep_declaration_order() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((a@Witness + x@Input) -> t1@Witness),
+            constraint!((b@Witness + x@Input) -> t2@Witness),
+            constraint!((t1@Witness * t2@Witness) -> out@Output)
+        ]
+    };
+
+    assert_eq!(c.constraint_order().unwrap(), vec![0, 1, 2]);
+}
+
+#[test]
+fn cycle_is_reported() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((x@Input + b@Witness) -> a@Witness),
+            constraint!((a@Witness * x@Input) -> b@Witness),
+            constraint!((a@Witness + b@Witness) -> out@Output)
+        ]
+    };
+
+    let cycle = c.constraint_order().unwrap_err();
+    assert_eq!(
+        cycle.to_string(),
+        "dependency cycle between constraints: #0 needs 'b' from #1, #1 needs 'a' from #0"
+    );
+}
+
+#[test]
+#[should_panic(expected = "dependency cycle")]
+fn prover_rejects_cycle() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((out@Output + x@Input) -> out@Output),
+            constraint!((x@Input + tmp@Witness) -> out2@Witness),
+            constraint!((out2@Witness * x@Input) -> tmp@Witness)
+        ]
+    };
+    let w = witness! { x = 1, out = 2 };
+
+    prove!(c, w);
+}
@@ -0,0 +1,181 @@
+use crate::{
+    circuit, constraint, prove, verify, witness, Circuit, DecodeError, FieldElement, Gate,
+    WitnessError, WitnessGenerator, PRIME,
+};
+
+fn sbox() -> Gate {
+    Gate::new("sbox", &["v"], "v^5").unwrap()
+}
+
+fn mix() -> Gate {
+    Gate::new("mix", &["a", "b", "c", "d", "e"], "a*b + c*d - e").unwrap()
+}
+
+// out = x^5 + k
+fn hashed() -> Circuit {
+    let sbox = sbox();
+    circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((sbox(x@Input)) -> y@Witness),
+            constraint!((y@Witness + k@Witness) -> out@Output)
+        ]
+    }
+}
+
+#[test]
+fn gates_are_parsed_into_polynomials() {
+    let mix = mix();
+    assert_eq!(mix.polynomial.degree(), 2);
+    let values = [2, 3, 4, 5, 26].map(|v| FieldElement::new(v, PRIME));
+    assert_eq!(mix.evaluate(&values).value, 0);
+    assert_eq!(mix.to_string(), "mix(a, b, c, d, e) = a*b + c*d - e");
+
+    let square = Gate::new("square", &["x"], "(x + 1)^2 - 1").unwrap();
+    assert_eq!(square.to_string(), "square(x) = x^2 + 2*x");
+
+    let err = Gate::new("bad", &["x"], "x * y").unwrap_err();
+    assert_eq!(err.message, "unknown wire `y`");
+    assert_eq!(err.column, 5);
+}
+
+#[test]
+fn gate_degree_is_bounded() {
+    for expression in ["(v^65536)^65536", "v^4000000000", "v^40 * v^40", "(v^8)^9"] {
+        let err = Gate::new("big", &["v"], expression).unwrap_err();
+        assert_eq!(
+            err.message, "gates are limited to degree 64",
+            "{}",
+            expression
+        );
+    }
+    let gate = Gate::new("big", &["v"], "v^64 + 2^4000000000").unwrap();
+    assert_eq!(gate.polynomial.degree(), Gate::MAX_DEGREE);
+    let two = FieldElement::new(2, PRIME);
+    let expected = two.pow(64).add(&two.pow(4000000000));
+    assert_eq!(gate.evaluate(&[two]).value, expected.value);
+
+    // v^5 with the power patched to u32::MAX
+    let mut bytes = hashed().to_bytes();
+    let power = bytes
+        .windows(6)
+        .position(|w| w == [1, 0, 5, 0, 0, 0])
+        .unwrap()
+        + 2;
+    bytes[power..power + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Circuit::from_bytes(&bytes),
+        Err(DecodeError::InvalidCircuit(message)) if message == "gate `sbox` has degree above 64"
+    ));
+}
+
+#[test]
+fn gate_terms_are_bounded() {
+    let wires = ["a", "b", "c", "d"];
+    for expression in [
+        "(a+b+c+d)^64",
+        "(a+b+c+d)^8 * (a+b+c+d)^8",
+        "(a+b+1)^20 + (c+d+1)^20",
+    ] {
+        let err = Gate::new("wide", &wires, expression).unwrap_err();
+        assert_eq!(
+            err.message, "gates are limited to 256 terms",
+            "{}",
+            expression
+        );
+    }
+    let err = Gate::new("wide", &wires, "a + (a+b+c+d)^10").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 5));
+
+    // (a + b)^64 - a^64 - b^64 only has middle terms left
+    let gate = Gate::new("binomial", &["a", "b"], "(a+b)^64 - a^64 - b^64").unwrap();
+    assert_eq!(gate.polynomial.terms.len(), 63);
+}
+
+#[test]
+fn gate_computes_its_output() {
+    let c = hashed();
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 3, k = 1 })
+        .unwrap();
+    assert_eq!(full.values["y"].value, 243);
+    assert_eq!(full.values["out"].value, 244);
+
+    let proof = prove!(c, witness! { x = 3, k = 1 });
+    assert!(verify!(c, [FieldElement::new(3, PRIME)], proof));
+
+    let stats = c.stats();
+    assert_eq!(stats.operations.custom, 1);
+    assert_eq!(stats.multiplicative_depth, Some(1));
+}
+
+#[test]
+fn identity_gate_is_enforced() {
+    let mix = mix();
+    // a * b + c * d = e
+    let c = circuit! {
+        [e] -> [],
+        constraints = [
+            constraint!((mix(a@Witness, b@Witness, c@Witness, d@Witness, e@Input)) -> {0})
+        ]
+    };
+    assert!(WitnessGenerator::new(&c)
+        .complete(&witness! { a = 2, b = 3, c = 4, d = 5, e = 26 })
+        .is_ok());
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 2, b = 3, c = 4, d = 5, e = 27 })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::ConstraintFailed { constraint: 0, .. }
+    ));
+
+    let proof = prove!(c, witness! { a = 2, b = 3, c = 4, d = 5, e = 26 });
+    assert!(verify!(c, [FieldElement::new(26, PRIME)], proof));
+    assert!(!verify!(c, [FieldElement::new(27, PRIME)], proof));
+
+    // a * b and c * d, then the sum
+    let export = c.to_r1cs();
+    assert_eq!(export.r1cs.constraints.len(), 3);
+    let wires = export
+        .wire_values(&witness! { a = 2, b = 3, c = 4, d = 5, e = 26 })
+        .unwrap();
+    assert!(export.r1cs.is_satisfied(&wires));
+}
+
+#[test]
+fn r1cs_multiplies_terms_up() {
+    let c = hashed();
+    let export = c.to_r1cs();
+    // four products for v^5, the sum of the gate and the addition
+    assert_eq!(export.r1cs.constraints.len(), 6);
+    let wires = export.wire_values(&witness! { x = 3, k = 1 }).unwrap();
+    assert!(export.r1cs.is_satisfied(&wires));
+
+    let mut cheat = wires.clone();
+    let y = cheat.iter().position(|w| w.value == 243).unwrap();
+    cheat[y] = FieldElement::new(242, PRIME);
+    assert!(!export.r1cs.is_satisfied(&cheat));
+}
+
+#[test]
+fn gates_survive_text_and_binary_formats() {
+    let c = hashed();
+    let text = c.to_text();
+    assert!(text.contains("gates = [\n    sbox(v) = v^5,\n],\n"));
+    assert!(text.contains("(sbox(x@Input)) -> y@Witness"));
+    let reparsed = Circuit::from_text(&text).unwrap();
+    assert_eq!(reparsed.to_text(), text);
+
+    let decoded = Circuit::from_bytes(&c.to_bytes()).unwrap();
+    assert_eq!(decoded.to_text(), text);
+
+    let err = Circuit::from_text("[x] -> [],\nconstraints = [\n    (sbox(x@Input)) -> 0,\n]")
+        .unwrap_err();
+    assert_eq!(err.message, "unknown gate or table `sbox`");
+    let err = Circuit::from_text(
+        "[x] -> [],\ngates = [\n    sbox(v) = v^5,\n],\nconstraints = [\n    (sbox(x@Input, x@Input)) -> 0,\n]",
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "`sbox` takes 1 inputs, got 2");
+}
@@ -0,0 +1,58 @@
+use crate::{
+    circuit, constraint, hint, witness, Circuit, Constraint, Operation, Signal, WitnessGenerator,
+};
+
+#[test]
+fn dot_has_signals_gates_and_edges() {
+    let mut c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((x@Input * y@Witness) -> t@Witness)
+        ]
+    };
+    c.constraints.push(Constraint {
+        left: Signal::Witness("t".into()),
+        right: Signal::Constant(3),
+        output: Signal::Output("out".into()),
+        operation: Operation::Add,
+    });
+    c.constraints.push(Constraint::select(
+        Signal::Witness("c".into()),
+        Signal::Input("x".into()),
+        Signal::Witness("t".into()),
+        Signal::Witness("s".into()),
+    ));
+    let dot = c.to_dot();
+    assert!(dot.starts_with("digraph circuit {"));
+    assert!(dot.contains("\"s:x\" [fillcolor=lightblue, label=\"x\"];"));
+    assert!(dot.contains("\"s:t\" [fillcolor=khaki, label=\"t\"];"));
+    assert!(dot.contains("\"s:out\" [fillcolor=palegreen, label=\"out\"];"));
+    assert!(dot.contains("\"g:0\" [shape=box, fillcolor=lightgrey, label=\"#0 *\"];"));
+    assert!(dot.contains("\"c:0\" [shape=plaintext, fillcolor=white, label=\"3\"];"));
+    assert!(dot.contains("\"s:y\" -> \"g:0\";"));
+    assert!(dot.contains("\"g:1\" -> \"s:out\";"));
+    assert!(dot.contains("\"s:c\" -> \"g:2\" [label=\"cond\"];"));
+    // every signal is drawn once however often it's used
+    assert_eq!(dot.matches("\"s:x\" [").count(), 1);
+}
+
+#[test]
+fn dot_shows_witness_values_and_hints() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((r@Witness * r@Witness) -> out@Output)
+        ],
+        hints = [
+            hint!(r = |x| x.sqrt())
+        ]
+    };
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 4 })
+        .unwrap();
+    let dot = c.to_dot_with_values(&full);
+    assert!(dot.contains("label=\"out\\n= 4\""));
+    assert!(dot.contains("\"h:0\" [shape=box, style=dashed, label=\"hint\"];"));
+    assert!(dot.contains("\"s:x\" -> \"h:0\" [style=dashed];"));
+    assert!(dot.contains("\"h:0\" -> \"s:r\" [style=dashed];"));
+}
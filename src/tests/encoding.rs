@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use crate::{
+    circuit, constraint, prove, witness, Circuit, Constraint, DecodeError, FieldElement, Operation,
+    Proof, Signal, Verifier, PRIME,
+};
+
+fn sample() -> (Circuit, Proof) {
+    let c = circuit! {
+        [x, [bits; 2]] -> [out],
+        constraints = [
+            constraint!((w@Witness * x@Input) -> t@Witness),
+            constraint!((bits[0]@Input ? t@Witness : x@Input) -> u@Witness),
+            constraint!((bits[1]@Input ? [u@Witness, t@Witness]) -> out@Output)
+        ]
+    };
+    let proof = prove!(c, witness! { x = 9, w = 4, bits = [1, 0] });
+    (c, proof)
+}
+
+/// Offset of the first revealed value: header, commitments, then the first
+/// revealed entry's section tag, lengths and name
+fn first_value_offset(proof: &Proof) -> usize {
+    let header = 4 + 1 + 1 + 16;
+    let commitments = 1
+        + 4
+        + 4
+        + proof
+            .commitments
+            .keys()
+            .map(|k| 4 + k.len() + 4 + 32)
+            .sum::<usize>();
+    let mut names: Vec<&String> = proof.revealed_witness.keys().collect();
+    names.sort();
+    header + commitments + 1 + 4 + 4 + 4 + names[0].len()
+}
+
+#[test]
+fn proof_round_trips_and_verifies() {
+    let (c, proof) = sample();
+    let bytes = proof.to_bytes();
+    assert_eq!(&bytes[..6], b"GHYB\x03\x01");
+
+    let decoded = Proof::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    let public = [9, 1, 0].map(|v| FieldElement::new(v, PRIME));
+    assert!(Verifier::verify(&c, &public, &decoded));
+}
+
+#[test]
+fn encoding_is_deterministic() {
+    let (_, proof) = sample();
+    let mut names: Vec<String> = proof.commitments.keys().cloned().collect();
+    names.reverse();
+    let reordered = Proof {
+        commitments: names
+            .iter()
+            .map(|n| (n.clone(), proof.commitments[n].clone()))
+            .collect::<HashMap<_, _>>(),
+        revealed_witness: proof.revealed_witness.clone(),
+    };
+    assert_eq!(reordered.to_bytes(), proof.to_bytes());
+}
+
+#[test]
+fn circuit_round_trips() {
+    let (c, proof) = sample();
+    let bytes = c.to_bytes();
+    let decoded = Circuit::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(decoded.to_text(), c.to_text());
+
+    let public = [9, 1, 0].map(|v| FieldElement::new(v, PRIME));
+    assert!(Verifier::verify(&decoded, &public, &proof));
+}
+
+#[test]
+fn circuit_constants_are_reduced() {
+    let c = Circuit::new(
+        vec![Signal::Input("x".into())],
+        vec![Signal::Output("y".into())],
+        vec![Constraint {
+            left: Signal::Input("x".into()),
+            right: Signal::Constant(PRIME + 3),
+            output: Signal::Output("y".into()),
+            operation: Operation::Add,
+        }],
+    );
+    let bytes = c.to_bytes();
+    let decoded = Circuit::from_bytes(&bytes).unwrap();
+    assert!(matches!(decoded.constraints[0].right, Signal::Constant(3)));
+
+    // the constant is a tag and two bytes, like any element
+    let at = bytes.windows(3).position(|w| w == [3, 3, 0]).unwrap() + 1;
+    let mut unreduced = bytes.clone();
+    unreduced[at..at + 2].copy_from_slice(&(PRIME as u16 + 3).to_le_bytes());
+    assert_eq!(
+        Circuit::from_bytes(&unreduced).unwrap_err(),
+        DecodeError::NonCanonicalElement(PRIME + 3)
+    );
+}
+
+#[test]
+fn strict_decoding() {
+    let (c, proof) = sample();
+    let bytes = proof.to_bytes();
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        Proof::from_bytes(&trailing).unwrap_err(),
+        DecodeError::TrailingBytes(1)
+    );
+
+    assert_eq!(
+        Proof::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+        DecodeError::UnexpectedEnd
+    );
+
+    let mut unreduced = bytes.clone();
+    let at = first_value_offset(&proof);
+    unreduced[at..at + 2].copy_from_slice(&(PRIME as u16).to_le_bytes());
+    assert_eq!(
+        Proof::from_bytes(&unreduced).unwrap_err(),
+        DecodeError::NonCanonicalElement(PRIME)
+    );
+
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert_eq!(
+        Proof::from_bytes(&magic).unwrap_err(),
+        DecodeError::BadMagic
+    );
+
+    let mut version = bytes.clone();
+    version[4] = 1;
+    assert_eq!(
+        Proof::from_bytes(&version).unwrap_err(),
+        DecodeError::UnsupportedVersion(1)
+    );
+
+    let mut field = bytes.clone();
+    field[6] ^= 1;
+    assert!(matches!(
+        Proof::from_bytes(&field).unwrap_err(),
+        DecodeError::FieldMismatch { .. }
+    ));
+
+    assert_eq!(
+        Proof::from_bytes(&c.to_bytes()).unwrap_err(),
+        DecodeError::WrongKind {
+            expected: 1,
+            got: 2
+        }
+    );
+}
+
+/// A circuit with one lookup into a listed table claiming `rows` rows, none of
+/// which follow
+fn table_claiming(columns: &[&str], rows: u32) -> Vec<u8> {
+    let zero = [3u8, 0, 0];
+    let mut constraints = 1u32.to_le_bytes().to_vec();
+    constraints.push(7);
+    for _ in 0..3 {
+        constraints.extend_from_slice(&zero);
+    }
+    constraints.extend_from_slice(&1u32.to_le_bytes());
+    constraints.push(b't');
+    constraints.extend_from_slice(&(columns.len() as u32).to_le_bytes());
+    for column in columns {
+        constraints.extend_from_slice(&(column.len() as u32).to_le_bytes());
+        constraints.extend_from_slice(column.as_bytes());
+    }
+    constraints.push(3);
+    constraints.extend_from_slice(&rows.to_le_bytes());
+
+    let mut bytes = b"GHYB\x03\x02".to_vec();
+    bytes.extend_from_slice(&PRIME.to_le_bytes());
+    let sections = [
+        (1, &[0u8; 4][..]),
+        (2, &[0; 4]),
+        (3, &constraints),
+        (4, &[0; 4]),
+    ];
+    for (tag, payload) in sections {
+        bytes.push(tag);
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(payload);
+    }
+    bytes
+}
+
+#[test]
+fn table_row_counts_are_bounded_by_the_input() {
+    assert_eq!(
+        Circuit::from_bytes(&table_claiming(&[], u32::MAX)).unwrap_err(),
+        DecodeError::InvalidCircuit("table `t` has no columns".to_string())
+    );
+    assert_eq!(
+        Circuit::from_bytes(&table_claiming(&["v"], u32::MAX)).unwrap_err(),
+        DecodeError::UnexpectedEnd
+    );
+}
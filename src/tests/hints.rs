@@ -0,0 +1,85 @@
+use crate::{circuit, constraint, hint, prove, verify, witness, FieldElement, WitnessError, PRIME};
+
+#[test]
+fn square_root_hint() {
+    // the prover shows it knows a square root of x without being handed one
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((r@Witness * r@Witness) -> sq@Witness),
+            constraint!((sq@Witness == x@Input) -> out@Output)
+        ],
+        hints = [
+            hint!(r = |x| x.sqrt())
+        ]
+    };
+
+    let proof = prove!(c, witness! { x = 9 });
+    let r = &proof.revealed_witness["r"].0;
+    assert!(r.value == 3 || r.value == PRIME - 3);
+    assert!(verify!(c, [FieldElement::new(9, PRIME)], proof));
+}
+
+#[test]
+fn bit_decomposition_hint() {
+    // b0 + 2*b1 + 4*b2 == x, with the powers of two supplied as inputs
+    let c = circuit! {
+        [x, two, four] -> [out],
+        constraints = [
+            constraint!((b1@Witness * two@Input) -> t1@Witness),
+            constraint!((b2@Witness * four@Input) -> t2@Witness),
+            constraint!((b0@Witness + t1@Witness) -> t3@Witness),
+            constraint!((t3@Witness + t2@Witness) -> sum@Witness),
+            constraint!((sum@Witness == x@Input) -> out@Output)
+        ],
+        hints = [
+            hint!([b0, b1, b2] = |x| x.to_bits(3))
+        ]
+    };
+
+    let proof = prove!(c, witness! { x = 6, two = 2, four = 4 });
+    let bits: Vec<u128> = ["b0", "b1", "b2"]
+        .iter()
+        .map(|b| proof.revealed_witness[*b].0.value)
+        .collect();
+    assert_eq!(bits, vec![0, 1, 1]);
+
+    let inputs = [6, 2, 4].map(|v| FieldElement::new(v, PRIME));
+    assert!(verify!(
+        c,
+        [inputs[0].clone(), inputs[1].clone(), inputs[2].clone()],
+        proof
+    ));
+}
+
+#[test]
+fn hint_without_a_value_is_an_error() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((r@Witness * r@Witness) -> out@Output)
+        ],
+        hints = [
+            hint!(r = |x| x.sqrt())
+        ]
+    };
+
+    // 7 is not a square modulo PRIME
+    assert!(FieldElement::new(7, PRIME).sqrt().is_none());
+    let err = crate::WitnessGenerator::new(&c)
+        .complete(&witness! { x = 7 })
+        .unwrap_err();
+    assert!(matches!(err, WitnessError::HintOutputCount { got: 0, .. }));
+}
+
+#[test]
+fn sqrt_and_inverse_roundtrip() {
+    for v in 1..200 {
+        let x = FieldElement::new(v, PRIME);
+        assert_eq!(x.mul(&x.inverse().unwrap()).value, 1);
+
+        let sq = x.mul(&x);
+        let root = sq.sqrt().unwrap();
+        assert!(root.mul(&root).equals(&sq));
+    }
+}
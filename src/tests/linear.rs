@@ -0,0 +1,116 @@
+use crate::{
+    circuit, constraint, linear, prove, verify, witness, Circuit, CircuitBuilder, CircuitSym,
+    ConstraintCounter, Evaluator, FieldElement, Signal, WitnessError, WitnessGenerator, PRIME,
+};
+
+#[test]
+fn linear_macro_builds_combinations() {
+    let i = 1;
+    let lc = linear!(2 * acc[i]@Witness - bits[i]@Input + (1 << 3) * x@Input - 1);
+    let terms: Vec<_> = lc
+        .terms
+        .iter()
+        .map(|(s, coeff)| (s.name().unwrap(), *coeff))
+        .collect();
+    assert_eq!(terms, [("acc[1]", 2), ("bits[1]", PRIME - 1), ("x", 8)]);
+    assert_eq!(lc.constant, PRIME - 1);
+
+    // the same signal twice is one term
+    let lc = linear!(a@Input + a@Input + 3);
+    assert_eq!(lc.terms.len(), 1);
+    assert_eq!((lc.terms[0].1, lc.constant), (2, 3));
+}
+
+#[test]
+fn sum_of_four_is_one_constraint() {
+    let c = circuit! {
+        [a, b, c, d] -> [out],
+        constraints = [
+            constraint!(({a@Input + b@Input + c@Input + d@Input} * k@Witness) -> out@Output)
+        ]
+    };
+    assert_eq!(c.constraints.len(), 1);
+
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 1, b = 2, c = 3, d = 4, k = 5 })
+        .unwrap();
+    assert_eq!(full.values["out"].value, 50);
+
+    // (a + b + c + d) * k = out
+    let export = c.to_r1cs();
+    assert_eq!(export.r1cs.constraints.len(), 1);
+    assert_eq!(export.r1cs.constraints[0].a.len(), 4);
+    let wires = export
+        .wire_values(&witness! { a = 1, b = 2, c = 3, d = 4, k = 5 })
+        .unwrap();
+    assert!(export.r1cs.is_satisfied(&wires));
+}
+
+#[test]
+fn linear_output_is_checked() {
+    // x * x = 2 * y + 1
+    let c = circuit! {
+        [y] -> [],
+        constraints = [
+            constraint!((x@Witness * x@Witness) -> {2 * y@Input + 1})
+        ]
+    };
+    assert_eq!(
+        c.to_text(),
+        Circuit::from_text(&c.to_text()).unwrap().to_text()
+    );
+
+    assert!(WitnessGenerator::new(&c)
+        .complete(&witness! { x = 3, y = 4 })
+        .is_ok());
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 3, y = 5 })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::ConstraintFailed { constraint: 0, .. }
+    ));
+
+    let proof = prove!(c, witness! { x = 3, y = 4 });
+    assert!(verify!(c, [FieldElement::new(4, PRIME)], proof));
+    assert!(!verify!(c, [FieldElement::new(5, PRIME)], proof));
+
+    let export = c.to_r1cs();
+    let wires = export.wire_values(&witness! { x = 3, y = 4 }).unwrap();
+    assert!(export.r1cs.is_satisfied(&wires));
+}
+
+/// out = (a + b + c + d) * e - 2 * a
+fn weighted<S: CircuitSym>(s: &mut S) {
+    let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|name| s.input(name));
+    let sum = s.add(&a, &b);
+    let sum = s.add(&sum, &c);
+    let sum = s.add(&sum, &d);
+    let prod = s.mul(&sum, &e);
+    let two = s.constant(2);
+    let twice = s.mul(&two, &a);
+    let out = s.sub(&prod, &twice);
+    s.output("out", &out);
+}
+
+#[test]
+fn builder_keeps_additions_linear() {
+    let mut builder = CircuitBuilder::new();
+    weighted(&mut builder);
+    let c = builder.build();
+    // the product, and copying the final combination into the output
+    assert_eq!(c.constraints.len(), 2);
+    assert!(matches!(c.constraints[0].left, Signal::Linear(_)));
+
+    let mut counter = ConstraintCounter::new();
+    weighted(&mut counter);
+    assert_eq!((counter.add, counter.mul), (3, 1));
+    assert_eq!(counter.total(), c.constraints.len());
+
+    let inputs = witness! { a = 1, b = 2, c = 3, d = 4, e = 5 };
+    let mut eval = Evaluator::new(&inputs);
+    weighted(&mut eval);
+    let full = WitnessGenerator::new(&c).complete(&inputs).unwrap();
+    assert_eq!(full.values["out"].value, 48);
+    assert_eq!(eval.output_value("out").unwrap().value, 48);
+}
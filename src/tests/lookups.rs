@@ -0,0 +1,215 @@
+use crate::{
+    circuit, constraint, prove, witness, Circuit, Constraint, FieldElement, LookupArgument, Signal,
+    Table, Verifier, WitnessError, WitnessGenerator, PRIME,
+};
+
+// c = a ^ b for bytes, and x is a byte
+fn xor_circuit() -> Circuit {
+    let xor8 = Table::xor("xor8", 8);
+    let byte = Table::range("byte", 8);
+    circuit! {
+        [a, b] -> [c],
+        constraints = [
+            constraint!((xor8(a@Input, b@Input)) -> c@Output),
+            constraint!((byte(x@Witness)) -> {0})
+        ]
+    }
+}
+
+fn xor12_circuit() -> Circuit {
+    let xor12 = Table::xor("xor12", 12);
+    circuit! {
+        [a, b] -> [c],
+        constraints = [constraint!((xor12(a@Input, b@Input)) -> c@Output)]
+    }
+}
+
+#[test]
+fn tables_find_rows() {
+    let xor8 = Table::xor("xor8", 8);
+    assert_eq!(xor8.len(), 1 << 16);
+    assert_eq!(xor8.lookup(&[0b1100, 0b1010]), Some(0b0110));
+    assert_eq!(
+        xor8.position(&[0b1100, 0b1010, 0b0110]),
+        Some(0b1100_0000_1010)
+    );
+    assert_eq!(xor8.position(&[0b1100, 0b1010, 0]), None);
+    assert_eq!(xor8.lookup(&[256, 0]), None);
+
+    let byte = Table::range("byte", 8);
+    assert_eq!(byte.position(&[255]), Some(255));
+    assert_eq!(byte.position(&[256]), None);
+
+    let squares = Table::new("squares", &["x", "y"], vec![vec![2, 4], vec![3, 9]]);
+    assert_eq!(squares.lookup(&[3]), Some(9));
+    assert_eq!(squares.lookup(&[4]), None);
+    assert_eq!(squares.row(1), [3, 9]);
+}
+
+#[test]
+fn lookups_compute_and_check() {
+    let c = xor_circuit();
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 0b1100, b = 0b1010, x = 200 })
+        .unwrap();
+    assert_eq!(full.values["c"].value, 0b0110);
+
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 0b1100, b = 0b1010, x = 300 })
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        WitnessError::NotInTable { constraint: 1, table, .. } if table == "byte"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "(300) of constraint #1 is not in table `byte`"
+    );
+
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 256, b = 0, x = 1 })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::NotInTable { constraint: 0, .. }
+    ));
+
+    let stats = c.stats();
+    assert_eq!(stats.operations.lookup, 2);
+    assert_eq!(stats.plonk_gates, 2);
+}
+
+#[test]
+fn logup_argument_proves_the_lookups() {
+    let c = xor_circuit();
+    let inputs = [FieldElement::new(12, PRIME), FieldElement::new(10, PRIME)];
+    let known = witness! { a = 12, b = 10, x = 200 };
+    let proof = prove!(c, known.clone());
+    assert!(Verifier::verify(&c, &inputs, &proof));
+
+    let arguments = LookupArgument::prove(&c, &known).unwrap();
+    assert_eq!(arguments.len(), 2);
+    assert_eq!(arguments[0].table, "xor8");
+    assert_eq!(arguments[0].multiplicities, [(12 << 8 | 10, 1)]);
+    assert_eq!(arguments[1].multiplicities, [(200, 1)]);
+    assert!(Verifier::verify_lookups(&c, &inputs, &proof, &arguments));
+
+    // claiming a different row
+    let mut forged = arguments.clone();
+    forged[1].multiplicities = vec![(201, 1)];
+    assert!(!Verifier::verify_lookups(&c, &inputs, &proof, &forged));
+    // or leaving a table out
+    assert!(!Verifier::verify_lookups(
+        &c,
+        &inputs,
+        &proof,
+        &arguments[..1]
+    ));
+}
+
+#[test]
+fn repeated_lookups_are_counted() {
+    let byte = Table::range("byte", 8);
+    let c = circuit! {
+        [x, y] -> [],
+        constraints = [
+            constraint!((byte(x@Input)) -> {0}),
+            constraint!((byte(y@Input)) -> {0}),
+            constraint!((byte(z@Witness)) -> {0})
+        ]
+    };
+    let known = witness! { x = 7, y = 9, z = 7 };
+    let arguments = LookupArgument::prove(&c, &known).unwrap();
+    assert_eq!(arguments[0].multiplicities, [(7, 2), (9, 1)]);
+
+    let inputs = [FieldElement::new(7, PRIME), FieldElement::new(9, PRIME)];
+    let proof = prove!(c, known);
+    assert!(Verifier::verify_lookups(&c, &inputs, &proof, &arguments));
+    let mut forged = arguments.clone();
+    forged[0].multiplicities = vec![(7, 1), (9, 2)];
+    assert!(!Verifier::verify_lookups(&c, &inputs, &proof, &forged));
+}
+
+#[test]
+fn tables_survive_text_and_binary_formats() {
+    let c = xor_circuit();
+    let text = c.to_text();
+    assert!(text.contains("tables = [\n    xor8(a, b, c) = xor(8),\n    byte(v) = range(8),\n],\n"));
+    assert!(text.contains("(xor8(a@Input, b@Input)) -> c@Output"));
+    assert_eq!(Circuit::from_text(&text).unwrap().to_text(), text);
+    assert_eq!(Circuit::from_bytes(&c.to_bytes()).unwrap().to_text(), text);
+
+    let listed = Circuit::from_text(
+        "[x] -> [y],\ntables = [\n    squares(x, y) = [(2, 4), (3, 9)],\n],\nconstraints = [\n    (squares(x@Input)) -> y@Output,\n]",
+    )
+    .unwrap();
+    let full = WitnessGenerator::new(&listed)
+        .complete(&witness! { x = 3 })
+        .unwrap();
+    assert_eq!(full.values["y"].value, 9);
+    assert_eq!(
+        Circuit::from_bytes(&listed.to_bytes()).unwrap().to_text(),
+        listed.to_text()
+    );
+
+    let err = Circuit::from_text(
+        "[x] -> [],\ntables = [\n    t(v) = list(8),\n],\nconstraints = [\n    (t(x@Input)) -> 0,\n]",
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "unknown table kind `list`");
+    let err = Circuit::from_text(
+        "[x] -> [],\ntables = [\n    t(a, b) = [(1, 2), (3)],\n],\nconstraints = [\n    (t(x@Input)) -> 0,\n]",
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "table `t` has 2 columns");
+    let err = Circuit::from_text(
+        "[x] -> [],\ntables = [\n    t(a, b, c) = xor(4),\n],\nconstraints = [\n    (t(x@Input)) -> 0,\n]",
+    )
+    .unwrap_err();
+    assert_eq!(err.message, "`t` takes 2 or 3 inputs, got 1");
+}
+
+#[test]
+fn r1cs_selects_a_row() {
+    let squares = Table::new("squares", &["x", "y"], vec![vec![2, 4], vec![3, 9]]);
+    let c = Circuit::new(
+        vec![Signal::Input("x".into())],
+        vec![Signal::Output("y".into())],
+        vec![Constraint::lookup(
+            &squares,
+            vec![Signal::Input("x".into())],
+            Signal::Output("y".into()),
+        )],
+    );
+    let export = c.to_r1cs();
+    // two booleans, their sum and a sum per column
+    assert_eq!(export.r1cs.constraints.len(), 5);
+    assert_eq!(c.stats().r1cs_rows, 5);
+    let wires = export.wire_values(&witness! { x = 3 }).unwrap();
+    assert!(export.r1cs.is_satisfied(&wires));
+
+    let mut cheat = wires.clone();
+    cheat[1] = FieldElement::new(4, PRIME);
+    assert!(!export.r1cs.is_satisfied(&cheat));
+}
+
+#[test]
+fn large_tables_are_counted_not_lowered() {
+    let c = xor_circuit();
+    let stats = c.stats();
+    // the rows of both tables, their sums and a sum per column
+    assert_eq!(stats.r1cs_rows, (1 << 16) + 1 + 3 + (1 << 8) + 1 + 1);
+    assert_eq!(stats.public_inputs, 2);
+    assert_eq!(stats.outputs, 1);
+    assert_eq!(stats.private_inputs, 1);
+
+    assert_eq!(xor12_circuit().stats().r1cs_rows, (1 << 24) + 1 + 3);
+}
+
+#[test]
+#[should_panic(
+    expected = "table `xor12` has 16777216 rows, more than the 65536 R1CS can select from"
+)]
+fn r1cs_refuses_large_tables() {
+    xor12_circuit().to_r1cs();
+}
@@ -0,0 +1,72 @@
+use crate::{circuit, constraint, prove, verify, witness, FieldElement, Verifier, PRIME};
+
+const N: usize = 5;
+
+#[test]
+fn sum_of_vector() {
+    let c = circuit! {
+        [[xs; N]] -> [out],
+        constraints = {
+            constraint!((xs[0]@Input + zero@Witness) -> acc[0]@Witness);
+            for i in 1..N {
+                constraint!((acc[i - 1]@Witness + xs[i]@Input) -> acc[i]@Witness);
+            }
+            constraint!((acc[N - 1]@Witness * scale@Witness) -> out@Output);
+        }
+    };
+    assert_eq!(c.constraints.len(), N + 1);
+
+    let w = witness! { xs = [1, 2, 3, 4, 5], zero = 0, scale = 2 };
+    let proof = prove!(c, w);
+    assert_eq!(proof.revealed_witness["acc[4]"].0.value, 15);
+
+    let inputs: Vec<FieldElement> = (1..=5).map(|v| FieldElement::new(v, PRIME)).collect();
+    assert!(Verifier::verify(&c, &inputs, &proof));
+}
+
+#[test]
+fn nested_loops_over_grid() {
+    // every row of the grid sums to the public `total`
+    let c = circuit! {
+        [grid[3][3], total] -> [],
+        constraints = {
+            for row in 0..3 {
+                constraint!((grid[row][0]@Input + grid[row][1]@Input) -> partial[row]@Witness);
+                constraint!((partial[row]@Witness + grid[row][2]@Input) -> sum[row]@Witness);
+                constraint!((sum[row]@Witness == total@Input) -> ok[row]@Witness);
+            }
+        }
+    };
+    assert_eq!(c.constraints.len(), 9);
+
+    let w = witness! {
+        grid = [[2, 7, 6], [9, 5, 1], [4, 3, 8]],
+        total = 15
+    };
+    let proof = prove!(c, w);
+
+    let mut inputs: Vec<FieldElement> = [2, 7, 6, 9, 5, 1, 4, 3, 8]
+        .iter()
+        .map(|v| FieldElement::new(*v, PRIME))
+        .collect();
+    inputs.push(FieldElement::new(15, PRIME));
+    assert!(Verifier::verify(&c, &inputs, &proof));
+}
+
+#[test]
+fn inclusive_ranges_and_mixed_statements() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = {
+            constraint!((x@Input * x@Input) -> pow[2]@Witness);
+            for k in 3..=4 {
+                constraint!((pow[k - 1]@Witness * x@Input) -> pow[k]@Witness);
+            }
+            constraint!((pow[4]@Witness + x@Input) -> out@Output);
+        }
+    };
+
+    let proof = prove!(c, witness! { x = 2 });
+    assert_eq!(proof.revealed_witness["pow[4]"].0.value, 16);
+    assert!(verify!(c, [FieldElement::new(2, PRIME)], proof));
+}
@@ -1,5 +1,30 @@
+mod arrays;
+mod circom;
 mod complex_arith;
+mod constraint_order;
+mod custom_gates;
+mod dot;
+mod encoding;
+mod hints;
+mod linear;
+mod lookups;
+mod loops;
 mod mul_test;
 mod multi_constraints;
+mod optimize;
+mod poseidon;
+mod proc_macro;
+mod select;
+#[cfg(feature = "serde")]
+mod serialization;
 mod simple_arith;
 mod simple_arith_with_macros;
+mod stats;
+mod tagless;
+mod templates;
+mod text_format;
+mod trace;
+mod typed;
+mod underconstrained;
+mod witness_generation;
+mod wrapping;
@@ -0,0 +1,170 @@
+use crate::{
+    circuit, constraint, prove, verify, witness, Circuit, Constraint, FieldElement, Operation,
+    OptimizationReport, Signal, WitnessGenerator, PRIME,
+};
+
+fn w(name: &str) -> Signal {
+    Signal::Witness(name.into())
+}
+
+// out = (x * y + y * x) * 1 + 3 * 4, with an unused square
+fn redundant() -> Circuit {
+    let mut c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((x@Input * y@Witness) -> p@Witness),
+            constraint!((y@Witness * x@Input) -> q@Witness),
+            constraint!((p@Witness + q@Witness) -> s@Witness),
+            constraint!((x@Input * x@Input) -> unused@Witness)
+        ]
+    };
+    c.constraints.push(Constraint {
+        left: w("s"),
+        right: Signal::Constant(1),
+        output: w("t"),
+        operation: Operation::Mul,
+    });
+    c.constraints.push(Constraint {
+        left: Signal::Constant(3),
+        right: Signal::Constant(4),
+        output: w("k"),
+        operation: Operation::Mul,
+    });
+    c.constraints.push(Constraint {
+        left: w("t"),
+        right: w("k"),
+        output: Signal::Output("out".into()),
+        operation: Operation::Add,
+    });
+    c
+}
+
+fn output(c: &Circuit, x: u128, y: u128) -> u128 {
+    WitnessGenerator::new(c)
+        .complete(&witness! { x = x, y = y })
+        .unwrap()
+        .values["out"]
+        .value
+}
+
+#[test]
+fn every_pass_saves_constraints() {
+    let (optimized, report) = redundant().optimize();
+    assert_eq!(
+        report,
+        OptimizationReport {
+            before: 7,
+            after: 2,
+            folded: 2,
+            merged: 1,
+            collapsed: 1,
+            eliminated: 1,
+        }
+    );
+    assert_eq!(optimized.constraints.len(), 2);
+    assert_eq!(
+        report.to_string(),
+        "7 -> 2 constraints (5 saved): 2 folded, 1 merged, 1 collapsed, 1 eliminated"
+    );
+    // sources follow the constraints they came from
+    assert_eq!(
+        optimized.sources[&0],
+        "constraint!((x@Input * y@Witness) -> p@Witness)"
+    );
+    assert_eq!(optimized.sources.len(), 1);
+}
+
+#[test]
+fn optimized_circuit_computes_the_same_outputs() {
+    let original = redundant();
+    let (optimized, _) = original.optimize();
+    for (x, y) in [(0, 0), (1, 2), (5, 7), (PRIME - 1, 3)] {
+        assert_eq!(output(&optimized, x, y), output(&original, x, y));
+    }
+
+    let proof = prove!(optimized, witness! { x = 5, y = 7 });
+    assert!(verify!(optimized, [FieldElement::new(5, PRIME)], proof));
+}
+
+#[test]
+fn linear_chain_collapses_into_one_combination() {
+    // out = ((a + b) - c) * 3 + a
+    let mut c = circuit! {
+        [a, b, c] -> [out],
+        constraints = [
+            constraint!((a@Input + b@Input) -> s1@Witness),
+            constraint!((s1@Witness - c@Input) -> s2@Witness)
+        ]
+    };
+    c.constraints.push(Constraint {
+        left: w("s2"),
+        right: Signal::Constant(3),
+        output: w("s3"),
+        operation: Operation::Mul,
+    });
+    c.constraints.push(Constraint {
+        left: w("s3"),
+        right: Signal::Input("a".into()),
+        output: Signal::Output("out".into()),
+        operation: Operation::Add,
+    });
+
+    let (optimized, report) = c.optimize();
+    assert_eq!((report.collapsed, report.after), (3, 1));
+    let Signal::Linear(lc) = &optimized.constraints[0].left else {
+        panic!("expected a linear combination");
+    };
+    let terms: Vec<_> = lc
+        .terms
+        .iter()
+        .map(|(s, coeff)| (s.name().unwrap(), *coeff))
+        .collect();
+    assert_eq!(terms, [("a", 4), ("b", 3), ("c", PRIME - 3)]);
+
+    let inputs = witness! { a = 2, b = 10, c = 1 };
+    let expected = WitnessGenerator::new(&c).complete(&inputs).unwrap();
+    let actual = WitnessGenerator::new(&optimized).complete(&inputs).unwrap();
+    assert_eq!(actual.values["out"].value, 35);
+    assert_eq!(actual.values["out"].value, expected.values["out"].value);
+    // the optimized circuit survives the text format
+    let reparsed = Circuit::from_text(&optimized.to_text()).unwrap();
+    assert_eq!(reparsed.to_text(), optimized.to_text());
+}
+
+#[test]
+fn assertions_and_pinned_signals_are_kept() {
+    // the select result is unused, but it still checks that c is boolean
+    let c = circuit! {
+        [a, b] -> [out],
+        constraints = [
+            constraint!((c@Witness ? a@Input : b@Input) -> chosen@Witness),
+            constraint!((a@Input * b@Input) -> out@Output),
+            constraint!((a@Input * b@Input) -> dup@Output)
+        ]
+    };
+    let (optimized, report) = c.optimize();
+    assert_eq!(report.saved(), 0);
+
+    for cond in [0, 1, 2] {
+        let inputs = witness! { a = 3, b = 4, c = cond };
+        let expected = WitnessGenerator::new(&c).complete(&inputs);
+        let actual = WitnessGenerator::new(&optimized).complete(&inputs);
+        assert_eq!(actual.is_ok(), expected.is_ok());
+        assert_eq!(actual.is_ok(), cond != 2);
+    }
+}
+
+#[test]
+fn cyclic_circuit_is_left_alone() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((a@Witness + x@Input) -> b@Witness),
+            constraint!((b@Witness + x@Input) -> a@Witness),
+            constraint!((a@Witness * a@Witness) -> out@Output)
+        ]
+    };
+    let (optimized, report) = c.optimize();
+    assert_eq!(report.saved(), 0);
+    assert_eq!(optimized.to_text(), c.to_text());
+}
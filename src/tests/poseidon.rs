@@ -0,0 +1,114 @@
+use crate::{
+    witness, CircuitBuilder, CircuitSym, ConstraintCounter, Evaluator, FieldElement, Poseidon,
+    Prover, Verifier, WitnessGenerator, PRIME,
+};
+
+fn elements(values: &[u128]) -> Vec<FieldElement> {
+    values
+        .iter()
+        .map(|v| FieldElement::new(*v, PRIME))
+        .collect()
+}
+
+/// The preimage `(x, y)` of the public digest `d`
+fn preimage<S: CircuitSym>(s: &mut S, poseidon: &Poseidon) {
+    let d = s.input("d");
+    let x = s.witness("x");
+    let y = s.witness("y");
+    let digest = poseidon.hash_sym(s, &[x, y]);
+    s.assert_eq(&digest, &d);
+}
+
+#[test]
+fn parameters_are_generated_deterministically() {
+    let poseidon = Poseidon::default();
+    assert_eq!(poseidon, Poseidon::new(3, 8, 57));
+    assert_eq!(poseidon.alpha, 5);
+    assert_eq!(poseidon.round_constants.len(), 65);
+    assert!(poseidon
+        .round_constants
+        .iter()
+        .all(|round| round.len() == 3 && round.iter().all(|c| *c < PRIME)));
+    assert!(poseidon.mds.iter().flatten().all(|m| *m != 0 && *m < PRIME));
+    assert_ne!(
+        poseidon.round_constants,
+        Poseidon::new(3, 8, 56).round_constants[..]
+    );
+    assert_ne!(poseidon.mds, Poseidon::new(4, 8, 57).mds);
+}
+
+#[test]
+fn gadget_matches_native_permutation() {
+    let poseidon = Poseidon::default();
+    for state in [[0, 0, 0], [1, 2, 3], [7488, 1234, 42]] {
+        let native = poseidon.permute(&elements(&state));
+
+        let known = witness! { s = state };
+        let mut eval = Evaluator::new(&known);
+        let inputs: Vec<_> = (0..3).map(|i| eval.input(&format!("s[{}]", i))).collect();
+        let evaluated = poseidon.permute_sym(&mut eval, &inputs);
+        assert_eq!(
+            evaluated.iter().map(|v| v.value).collect::<Vec<_>>(),
+            native.iter().map(|v| v.value).collect::<Vec<_>>()
+        );
+
+        let mut builder = CircuitBuilder::new();
+        let inputs: Vec<_> = (0..3)
+            .map(|i| builder.input(&format!("s[{}]", i)))
+            .collect();
+        let out = poseidon.permute_sym(&mut builder, &inputs);
+        for (i, o) in out.iter().enumerate() {
+            builder.output(&format!("out[{}]", i), o);
+        }
+        let full = WitnessGenerator::new(&builder.build())
+            .complete(&known)
+            .unwrap();
+        for (i, expected) in native.iter().enumerate() {
+            assert_eq!(full.values[&format!("out[{}]", i)].value, expected.value);
+        }
+    }
+}
+
+#[test]
+fn sponge_hashes_any_length() {
+    let poseidon = Poseidon::default();
+    let digests: Vec<u128> = [&[][..], &[1], &[1, 0], &[1, 2], &[1, 2, 3, 4, 5]]
+        .iter()
+        .map(|inputs| poseidon.hash(&elements(inputs)).value)
+        .collect();
+    // distinct lengths are separated, so padding with zeros changes the digest
+    for (i, d) in digests.iter().enumerate() {
+        assert!(!digests[..i].contains(d), "{:?}", digests);
+    }
+
+    let inputs = [3, 1, 4, 1, 5];
+    let known = witness! { m = inputs };
+    let mut eval = Evaluator::new(&known);
+    let message: Vec<_> = (0..5).map(|i| eval.witness(&format!("m[{}]", i))).collect();
+    let digest = poseidon.hash_sym(&mut eval, &message);
+    assert_eq!(digest.value, poseidon.hash(&elements(&inputs)).value);
+}
+
+#[test]
+fn proves_a_hash_preimage() {
+    let poseidon = Poseidon::default();
+    let mut builder = CircuitBuilder::new();
+    preimage(&mut builder, &poseidon);
+    let c = builder.build();
+
+    // three multiplications per S-box, on the whole state in full rounds,
+    // except the first S-box on the capacity, which is a constant
+    let mut counter = ConstraintCounter::new();
+    preimage(&mut counter, &poseidon);
+    assert_eq!(counter.mul, 3 * (8 * 3 + 57 - 1));
+    assert_eq!(counter.total(), c.constraints.len());
+
+    let d = poseidon.hash(&elements(&[11, 22]));
+    let proof = Prover::prove(&c, &witness! { d = d.value, x = 11, y = 22 });
+    let wrong = FieldElement::new(d.value + 1, PRIME);
+    assert!(!Verifier::verify(&c, &[wrong], &proof));
+
+    let err = WitnessGenerator::new(&c).complete(&witness! { d = d.value, x = 11, y = 23 });
+    assert!(err.is_err());
+    assert!(Verifier::verify(&c, &[d], &proof));
+}
@@ -0,0 +1,70 @@
+use crate::{macros::circuit, prove, verify, Verifier};
+
+#[circuit]
+fn affine(x: Public<F>, w: Private<F>) -> Public<F> {
+    (w + x) * x
+}
+
+#[circuit]
+fn square_root(x: Public<F>, r: Private<F>) {
+    assert_eq!(r * r, x);
+}
+
+#[circuit]
+fn clamp(flag: Private<F>, a: Public<F>, b: Public<F>) -> Public<F> {
+    assert_eq!(flag * flag, flag);
+    let diff = a - b;
+    if flag {
+        diff * 2
+    } else {
+        -diff
+    }
+}
+
+#[test]
+fn attribute_generates_circuit_and_witness() {
+    let c = affine::circuit();
+    assert_eq!(c.inputs.len(), 1);
+    // w + x is a linear combination read by the product
+    assert_eq!(c.constraints.len(), 1);
+
+    let proof = prove!(c, affine::witness(3, 4));
+    let public = affine::PublicInputs { x: 3 };
+    assert!(Verifier::verify(&c, &public.to_vec(), &proof));
+    assert_eq!(
+        affine::evaluate(3, 4).output_value("out").unwrap().value,
+        21
+    );
+}
+
+#[test]
+fn assertions_without_output() {
+    let c = square_root::circuit();
+    assert!(c.outputs.is_empty());
+    assert!(square_root::evaluate(9, 3).is_satisfied());
+    assert!(!square_root::evaluate(9, 4).is_satisfied());
+
+    let proof = prove!(c, square_root::witness(9, 3));
+    assert!(Verifier::verify(
+        &c,
+        &square_root::PublicInputs { x: 9 }.to_vec(),
+        &proof
+    ));
+}
+
+#[test]
+fn let_bindings_and_if_become_select() {
+    assert_eq!(
+        clamp::evaluate(1, 10, 4).output_value("out").unwrap().value,
+        12
+    );
+    assert_eq!(
+        clamp::evaluate(0, 10, 4).output_value("out").unwrap().value,
+        crate::PRIME - 6
+    );
+
+    let c = clamp::circuit();
+    let proof = prove!(c, clamp::witness(1, 10, 4));
+    let public = clamp::PublicInputs { a: 10, b: 4 };
+    assert!(Verifier::verify(&c, &public.to_vec(), &proof));
+}
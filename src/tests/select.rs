@@ -0,0 +1,124 @@
+use crate::{
+    circuit, constraint, prove, verify, witness, Constraint, FieldElement, Operation, Signal,
+    WitnessError, WitnessGenerator, PRIME,
+};
+
+#[test]
+fn select_between_inputs() {
+    let c = circuit! {
+        [a, b] -> [out],
+        constraints = [
+            constraint!((c@Witness ? a@Input : b@Input) -> out@Output)
+        ]
+    };
+    assert!(matches!(c.constraints[0].operation, Operation::Select(_)));
+
+    let inputs = [FieldElement::new(10, PRIME), FieldElement::new(20, PRIME)];
+    for (cond, expected) in [(1, 10), (0, 20)] {
+        let proof = prove!(c, witness! { a = 10, b = 20, c = cond });
+        assert_eq!(proof.revealed_witness["c"].0.value, cond);
+        let full = WitnessGenerator::new(&c)
+            .generate(&inputs, &witness! { c = cond })
+            .unwrap();
+        assert_eq!(full.values["out"].value, expected);
+        assert!(verify!(c, [inputs[0].clone(), inputs[1].clone()], proof));
+    }
+}
+
+#[test]
+fn select_condition_must_be_boolean() {
+    let c = circuit! {
+        [a, b] -> [out],
+        constraints = [
+            constraint!((c@Witness ? a@Input : b@Input) -> out@Output)
+        ]
+    };
+
+    let err = WitnessGenerator::new(&c)
+        .complete(&witness! { a = 10, b = 20, c = 2 })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::NotBoolean { constraint: 0, .. }
+    ));
+}
+
+#[test]
+fn multiplexer_picks_indexed_option() {
+    let c = circuit! {
+        [[xs; 4]] -> [out],
+        constraints = [
+            constraint!((idx@Witness ? [xs[0]@Input, xs[1]@Input, xs[2]@Input, xs[3]@Input]) -> picked@Witness),
+            constraint!((picked@Witness + idx@Witness) -> out@Output)
+        ]
+    };
+
+    let inputs: Vec<FieldElement> = [5, 6, 7, 8]
+        .iter()
+        .map(|v| FieldElement::new(*v, PRIME))
+        .collect();
+    let proof = prove!(c, witness! { xs = [5, 6, 7, 8], idx = 2 });
+    assert_eq!(proof.revealed_witness["picked"].0.value, 7);
+    assert!(crate::Verifier::verify(&c, &inputs, &proof));
+
+    let err = WitnessGenerator::new(&c)
+        .generate(&inputs, &witness! { idx = 4 })
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        WitnessError::IndexOutOfRange {
+            constraint: 0,
+            options: 4,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn select_with_constants() {
+    // out = c ? 1 : x
+    let c = crate::Circuit::new(
+        vec![Signal::Input("x".into())],
+        vec![Signal::Output("out".into())],
+        vec![Constraint::select(
+            Signal::Witness("c".into()),
+            Signal::Constant(1),
+            Signal::Input("x".into()),
+            Signal::Output("out".into()),
+        )],
+    );
+
+    let proof = prove!(c, witness! { x = 9, c = 1 });
+    assert!(verify!(c, [FieldElement::new(9, PRIME)], proof));
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 9, c = 1 })
+        .unwrap();
+    assert_eq!(full.values["out"].value, 1);
+}
+
+#[test]
+fn verifier_rejects_non_boolean_condition() {
+    let c = circuit! {
+        [a, b] -> [out],
+        constraints = [
+            constraint!((c@Witness ? a@Input : b@Input) -> out@Output)
+        ]
+    };
+
+    let mut proof = prove!(c, witness! { a = 10, b = 20, c = 1 });
+    let blinding = proof.revealed_witness["c"].1.clone();
+    let forged = FieldElement::new(5, PRIME);
+    proof
+        .commitments
+        .insert("c".to_string(), crate::Commitment::new(&forged, &blinding));
+    proof
+        .revealed_witness
+        .insert("c".to_string(), (forged, blinding));
+
+    let is_ok = verify!(
+        c,
+        [FieldElement::new(10, PRIME), FieldElement::new(20, PRIME)],
+        proof
+    );
+    assert!(!is_ok);
+}
@@ -0,0 +1,123 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    circuit, constraint, hint, prove, witness, Circuit, Commitment, Constraint, FieldElement, Gate,
+    Operation, Proof, Signal, Table, Verifier, Witness, PRIME,
+};
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> (T, String) {
+    let json = serde_json::to_string(value).unwrap();
+    let back: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    (back, json)
+}
+
+fn sample() -> Circuit {
+    circuit! {
+        [x, [bits; 2]] -> [out],
+        constraints = [
+            constraint!((w@Witness * x@Input) -> t@Witness),
+            constraint!((bits[0]@Input ? t@Witness : x@Input) -> u@Witness),
+            constraint!((bits[1]@Input ? [u@Witness, t@Witness]) -> out@Output)
+        ],
+        hints = [
+            hint!(w = |x| x.sqrt())
+        ]
+    }
+}
+
+#[test]
+fn field_elements_are_canonical_strings() {
+    let (_, json) = round_trip(&FieldElement::new(42, PRIME));
+    assert_eq!(json, "\"42\"");
+
+    for bad in ["\"042\"", "\"7489\"", "\"-1\"", "\"\"", "\"0x2a\"", "42"] {
+        assert!(
+            serde_json::from_str::<FieldElement>(bad).is_err(),
+            "{}",
+            bad
+        );
+    }
+    assert!(serde_json::to_string(&FieldElement::new(3, 7)).is_err());
+}
+
+#[test]
+fn circuit_round_trips_without_hints() {
+    let c = sample();
+    let (back, json) = round_trip(&c);
+    assert!(json.contains("{\"Select\":{\"Input\":\"bits[0]\"}}"));
+    assert!(back.hints.is_empty());
+    assert_eq!(back.to_text(), c.to_text());
+}
+
+#[test]
+fn invalid_circuits_are_rejected() {
+    let json = serde_json::to_string(&sample()).unwrap();
+    let out_of_bounds = json.replace(
+        "{\"Select\":{\"Input\":\"bits[0]\"}}",
+        "{\"Select\":{\"Input\":\"bits[2]\"}}",
+    );
+    let err = serde_json::from_str::<Circuit>(&out_of_bounds).unwrap_err();
+    assert!(
+        err.to_string().contains("'bits[2]' is out of bounds"),
+        "{}",
+        err
+    );
+
+    let x = Signal::Input("x".into());
+    let sbox = Gate::new("sbox", &["v"], "v^5").unwrap();
+    let byte = Table::range("byte", 8);
+    let calls = [
+        (
+            Operation::Custom(sbox, vec![x.clone(), x.clone()]),
+            "gate `sbox` takes 1 inputs, got 2",
+        ),
+        (
+            Operation::Lookup(byte, vec![x.clone(); 3]),
+            "table `byte` takes 0 or 1 inputs, got 3",
+        ),
+    ];
+    for (operation, message) in calls {
+        let c = Circuit::new(
+            vec![x.clone()],
+            vec![],
+            vec![Constraint {
+                left: Signal::Constant(0),
+                right: Signal::Constant(0),
+                output: Signal::Constant(0),
+                operation,
+            }],
+        );
+        let json = serde_json::to_string(&c).unwrap();
+        let err = serde_json::from_str::<Circuit>(&json).unwrap_err();
+        assert!(err.to_string().contains(message), "{}", err);
+    }
+}
+
+#[test]
+fn witness_serializes_in_key_order() {
+    let w = witness! { z = 1, a = 2, m = 3 };
+    let (back, json) = round_trip(&w);
+    assert_eq!(json, "{\"values\":{\"a\":\"2\",\"m\":\"3\",\"z\":\"1\"}}");
+    assert_eq!(back.values["m"].value, 3);
+}
+
+#[test]
+fn commitment_is_hex() {
+    let c = Commitment::new(&FieldElement::new(5, PRIME), &FieldElement::new(9, PRIME));
+    let (back, json) = round_trip(&c);
+    assert_eq!(json.len(), "{\"committed_hash\":\"\"}".len() + 64);
+    assert_eq!(back.committed_hash, c.committed_hash);
+    assert!(serde_json::from_str::<Commitment>("{\"committed_hash\":\"ABCD\"}").is_err());
+}
+
+#[test]
+fn shipped_proof_still_verifies() {
+    let c = sample();
+    let proof = prove!(c, witness! { x = 9, bits = [1, 0] });
+    let (proof, _) = round_trip(&proof);
+    let (c, _) = round_trip(&c);
+
+    let public = [9, 1, 0].map(|v| FieldElement::new(v, PRIME));
+    assert!(Verifier::verify(&c, &public, &proof));
+}
@@ -6,9 +6,9 @@ use crate::{
 
 #[test]
 fn simple_zk_example() {
-    let x_signal = Signal::Input("x".to_string());
-    let w_signal = Signal::Witness("w".to_string());
-    let out_signal = Signal::Output("out".to_string());
+    let x_signal = Signal::Input("x".into());
+    let w_signal = Signal::Witness("w".into());
+    let out_signal = Signal::Output("out".into());
 
     // constraint: w + x = out
     let constraint = Constraint {
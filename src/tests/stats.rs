@@ -0,0 +1,89 @@
+use crate::{circuit, constraint, Circuit, Constraint, Operation, OperationCounts, Signal};
+
+// out = (if c { (x * y)^2 } else { 2 * x * y }) - x
+fn costed() -> Circuit {
+    let mut c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((x@Input * y@Witness) -> xy@Witness),
+            constraint!((xy@Witness * xy@Witness) -> sq@Witness)
+        ]
+    };
+    let w = |name: &str| Signal::Witness(name.into());
+    c.constraints.push(Constraint {
+        left: w("xy"),
+        right: Signal::Constant(2),
+        output: w("dbl"),
+        operation: Operation::Mul,
+    });
+    c.constraints
+        .push(Constraint::select(w("c"), w("sq"), w("dbl"), w("chosen")));
+    c.constraints.push(Constraint {
+        left: w("chosen"),
+        right: Signal::Input("x".into()),
+        output: Signal::Output("out".into()),
+        operation: Operation::Sub,
+    });
+    c
+}
+
+#[test]
+fn counts_operations_signals_and_costs() {
+    let stats = costed().stats();
+    assert_eq!(stats.constraints, 5);
+    assert_eq!(
+        stats.operations,
+        OperationCounts {
+            mul: 3,
+            sub: 1,
+            select: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        (
+            stats.public_inputs,
+            stats.outputs,
+            stats.private_inputs,
+            stats.intermediate
+        ),
+        (1, 1, 2, 4)
+    );
+    // x * y, squared, then selected; doubling is linear
+    assert_eq!(stats.multiplicative_depth, Some(3));
+    assert_eq!(stats.r1cs_rows, costed().to_r1cs().r1cs.constraints.len());
+    assert_eq!(stats.r1cs_rows, 6);
+    assert_eq!(stats.plonk_gates, 8);
+    // x and xy are both read twice, ties go to the first name
+    assert_eq!(stats.max_fan_out, Some(("x".to_string(), 2)));
+}
+
+#[test]
+fn display_is_a_table() {
+    let table = costed().stats().to_string();
+    assert_eq!(table.lines().count(), 17);
+    assert!(table.contains("multiplicative depth         3"));
+    assert!(table.contains("max fan-out              2 (x)"));
+
+    let cyclic = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((a@Witness + x@Input) -> b@Witness),
+            constraint!((b@Witness + x@Input) -> a@Witness)
+        ]
+    };
+    let stats = cyclic.stats();
+    assert_eq!(stats.multiplicative_depth, None);
+    assert!(stats.to_string().contains("cyclic"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn stats_serialize() {
+    let stats = costed().stats();
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["operations"]["mul"], 3);
+    assert_eq!(json["max_fan_out"], serde_json::json!(["x", 2]));
+    let back: crate::CircuitStats = serde_json::from_value(json).unwrap();
+    assert_eq!(back, stats);
+}
@@ -0,0 +1,102 @@
+use crate::{
+    prove, verify, witness, CircuitBuilder, CircuitSym, ConstraintCounter, Evaluator, FieldElement,
+    PartialEvaluator, PrettyPrinter, WitnessGenerator, PRIME,
+};
+
+/// out = (w + x) * x * scale, with w constrained to be x squared
+fn scaled<S: CircuitSym>(s: &mut S) {
+    let x = s.input("x");
+    let scale = s.input("scale");
+    let w = s.witness("w");
+    let sq = s.mul(&x, &x);
+    s.assert_eq(&w, &sq);
+    let sum = s.add(&w, &x);
+    let prod = s.mul(&sum, &x);
+    let out = s.mul(&prod, &scale);
+    s.output("out", &out);
+}
+
+#[test]
+fn evaluator_runs_the_circuit() {
+    let mut eval = Evaluator::new(&witness! { x = 3, scale = 2, w = 9 });
+    scaled(&mut eval);
+    assert!(eval.is_satisfied());
+    assert_eq!(eval.output_value("out").unwrap().value, 72);
+
+    let mut eval = Evaluator::new(&witness! { x = 3, scale = 2, w = 8 });
+    scaled(&mut eval);
+    assert!(!eval.is_satisfied());
+}
+
+#[test]
+fn built_circuit_proves_and_verifies() {
+    let mut builder = CircuitBuilder::new();
+    scaled(&mut builder);
+    let c = builder.build();
+
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 3, scale = 2, w = 9 })
+        .unwrap();
+    assert_eq!(full.values["out"].value, 72);
+
+    let proof = prove!(c, witness! { x = 3, scale = 2, w = 9 });
+    let inputs = [3, 2].map(|v| FieldElement::new(v, PRIME));
+    assert!(verify!(c, [inputs[0].clone(), inputs[1].clone()], proof));
+}
+
+#[test]
+fn pretty_printer_renders_expressions() {
+    let mut printer = PrettyPrinter::new();
+    scaled(&mut printer);
+    assert_eq!(
+        printer.finish(),
+        "input x\ninput scale\nwitness w\nassert w == (x * x)\noutput out = (((w + x) * x) * scale)"
+    );
+}
+
+#[test]
+fn counter_matches_builder() {
+    let mut counter = ConstraintCounter::default();
+    scaled(&mut counter);
+    let mut builder = CircuitBuilder::new();
+    scaled(&mut builder);
+
+    assert_eq!(counter.mul, 3);
+    assert_eq!(counter.total(), builder.build().constraints.len());
+}
+
+#[test]
+fn partial_evaluation_folds_known_inputs() {
+    let mut partial = PartialEvaluator::new(CircuitBuilder::new()).with_known("scale", 1);
+    scaled(&mut partial);
+    let c = partial.into_inner().build();
+
+    // the multiplication by scale = 1 disappears, and w + x costs nothing
+    assert_eq!(c.constraints.len(), 3);
+    let full = WitnessGenerator::new(&c)
+        .complete(&witness! { x = 3, w = 9 })
+        .unwrap();
+    assert_eq!(full.values["out"].value, 36);
+    let proof = prove!(c, witness! { x = 3, w = 9 });
+    assert!(verify!(c, [FieldElement::new(3, PRIME)], proof));
+}
+
+#[test]
+fn temporaries_do_not_clash_with_declared_signals() {
+    let mut builder = CircuitBuilder::new();
+    let x = builder.input("x");
+    let w = builder.witness("_t0");
+    let sq = builder.mul(&x, &x);
+    let out = builder.mul(&sq, &w);
+    builder.output("out", &out);
+    let full = WitnessGenerator::new(&builder.build())
+        .complete(&witness! { x = 3, _t0 = 2 })
+        .unwrap();
+    assert_eq!(full.values["out"].value, 18);
+}
+
+#[test]
+#[should_panic(expected = "names starting with `$` are reserved for temporaries")]
+fn temporary_names_are_reserved() {
+    CircuitBuilder::new().witness("$t0");
+}
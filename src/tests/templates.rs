@@ -0,0 +1,125 @@
+use crate::{
+    circuit, constraint, hint, prove, verify, witness, Circuit, Constraint, FieldElement,
+    Operation, Signal, Template, PRIME,
+};
+
+fn square() -> Template {
+    Template::from_circuit(
+        "Square",
+        circuit! {
+            [a] -> [out],
+            constraints = [
+                constraint!((a@Input * a@Input) -> out@Output)
+            ]
+        },
+    )
+}
+
+#[test]
+fn instantiate_template_twice() {
+    // y = (x^2)^2 built from two Square instances
+    let mut c = circuit! {
+        [x] -> [y],
+        constraints = []
+    };
+    let sq = square();
+    c.instantiate(&sq, "sq[0]", &[], &[("a", Signal::Input("x".into()))]);
+    c.instantiate(
+        &sq,
+        "sq[1]",
+        &[],
+        &[
+            ("a", Signal::Witness("sq[0].out".into())),
+            ("out", Signal::Output("y".into())),
+        ],
+    );
+
+    let proof = prove!(c, witness! { x = 3 });
+    assert_eq!(proof.revealed_witness["sq[0].out"].0.value, 9);
+    assert!(verify!(c, [FieldElement::new(3, PRIME)], proof));
+}
+
+#[test]
+fn parameterized_template() {
+    // Pow(n): out = a^n as a chain of n - 1 multiplications
+    let pow = Template::new("Pow", |params| {
+        let n = params[0] as usize;
+        let acc = |i: usize| Signal::Witness(format!("acc{}", i).into());
+        let mut constraints = Vec::new();
+        for i in 1..n {
+            let output = if i == n - 1 {
+                Signal::Output("out".into())
+            } else {
+                acc(i)
+            };
+            let left = if i == 1 {
+                Signal::Input("a".into())
+            } else {
+                acc(i - 1)
+            };
+            constraints.push(Constraint {
+                left,
+                right: Signal::Input("a".into()),
+                output,
+                operation: Operation::Mul,
+            });
+        }
+        Circuit::new(
+            vec![Signal::Input("a".into())],
+            vec![Signal::Output("out".into())],
+            constraints,
+        )
+    });
+
+    let mut c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((p5.out@Witness + x@Input) -> out@Output)
+        ]
+    };
+    c.instantiate(&pow, "p5", &[5], &[("a", Signal::Input("x".into()))]);
+
+    let proof = prove!(c, witness! { x = 2 });
+    assert_eq!(proof.revealed_witness["p5.out"].0.value, 32);
+    assert_eq!(proof.revealed_witness["p5.acc2"].0.value, 8);
+    assert!(verify!(c, [FieldElement::new(2, PRIME)], proof));
+}
+
+#[test]
+fn hints_are_namespaced() {
+    let root = Template::from_circuit(
+        "Root",
+        circuit! {
+            [a] -> [out],
+            constraints = [
+                constraint!((r@Witness * r@Witness) -> sq@Witness),
+                constraint!((sq@Witness == a@Input) -> out@Output)
+            ],
+            hints = [
+                hint!(r = |a| a.sqrt())
+            ]
+        },
+    );
+
+    let mut c = circuit! {
+        [x] -> [],
+        constraints = []
+    };
+    c.instantiate(&root, "root", &[], &[("a", Signal::Input("x".into()))]);
+
+    assert_eq!(c.hints[0].inputs, vec!["x"]);
+    assert_eq!(c.hints[0].outputs, vec!["root.r"]);
+
+    let proof = prove!(c, witness! { x = 4 });
+    assert!(verify!(c, [FieldElement::new(4, PRIME)], proof));
+}
+
+#[test]
+#[should_panic(expected = "has no input or output named 'b'")]
+fn unknown_binding_is_rejected() {
+    let mut c = circuit! {
+        [x] -> [],
+        constraints = []
+    };
+    c.instantiate(&square(), "sq", &[], &[("b", Signal::Input("x".into()))]);
+}
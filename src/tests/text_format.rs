@@ -0,0 +1,144 @@
+use crate::{circuit, constraint, prove, verify, Circuit, FieldElement, Gate, ParseError, PRIME};
+
+const TEXT: &str = "\
+// x^2 + bits[0] * x, picked from a table
+[x, bits[2], [table; 2]] -> [out],
+constraints = [
+    (x@Input * x@Input) -> sq.out@Witness,
+    (bits[0]@Input * x@Input) -> t@Witness,
+    (sq.out@Witness + t@Witness) -> h[0].out@Witness,
+    (bits[1]@Input ? h[0].out@Witness : 1) -> pick@Witness,
+    (bits[0]@Input ? [table[0]@Input, table[1]@Input]) -> row@Witness,
+    (pick@Witness == row@Witness) -> out@Output
+]
+";
+
+#[test]
+fn parses_circuit_macro_syntax() {
+    let c = Circuit::from_text(TEXT).unwrap();
+    let names: Vec<_> = c.inputs.iter().filter_map(|s| s.name()).collect();
+    assert_eq!(names, ["x", "bits[0]", "bits[1]", "table[0]", "table[1]"]);
+    assert_eq!(c.constraints.len(), 6);
+
+    let proof = prove!(c, crate::witness! { x = 3, bits = [1, 1], table = [5, 12] });
+    let inputs = [3, 1, 1, 5, 12].map(|v| FieldElement::new(v, PRIME));
+    assert!(verify!(
+        c,
+        [
+            inputs[0].clone(),
+            inputs[1].clone(),
+            inputs[2].clone(),
+            inputs[3].clone(),
+            inputs[4].clone()
+        ],
+        proof
+    ));
+}
+
+#[test]
+fn pretty_printer_round_trips() {
+    let c = Circuit::from_text(TEXT).unwrap();
+    let printed = c.to_text();
+    assert_eq!(
+        printed,
+        "\
+[x, bits[2], table[2]] -> [out],
+constraints = [
+    (x@Input * x@Input) -> sq.out@Witness,
+    (bits[0]@Input * x@Input) -> t@Witness,
+    (sq.out@Witness + t@Witness) -> h[0].out@Witness,
+    (bits[1]@Input ? h[0].out@Witness : 1) -> pick@Witness,
+    (bits[0]@Input ? [table[0]@Input, table[1]@Input]) -> row@Witness,
+    (pick@Witness == row@Witness) -> out@Output,
+]
+"
+    );
+    assert_eq!(Circuit::from_text(&printed).unwrap().to_text(), printed);
+}
+
+#[test]
+fn macro_circuits_round_trip() {
+    let c = circuit! {
+        [x, grid[2][2]] -> [out],
+        constraints = [
+            constraint!((grid[1][0]@Input - x@Input) -> d@Witness),
+            constraint!((d@Witness * d@Witness) -> out@Output)
+        ]
+    };
+    let printed = c.to_text();
+    assert!(printed.starts_with("[x, grid[2][2]] -> [out],\n"));
+    assert_eq!(Circuit::from_text(&printed).unwrap().to_text(), printed);
+}
+
+#[test]
+fn odd_names_are_quoted() {
+    let mut c = Circuit::from_text("[\"x[5]\"] -> [], constraints = []").unwrap();
+    assert_eq!(c.inputs[0].name(), Some("x[5]"));
+    c.constraints.push(crate::Constraint {
+        left: crate::Signal::Witness("my value".into()),
+        right: crate::Signal::Constant(0),
+        output: crate::Signal::Witness("x[5]".into()),
+        operation: crate::Operation::Add,
+    });
+    let printed = c.to_text();
+    assert!(printed.starts_with("[\"x[5]\"] -> [],"));
+    assert!(printed.contains("(\"my value\"@Witness + 0) -> x[5]@Witness"));
+    assert_eq!(Circuit::from_text(&printed).unwrap().to_text(), printed);
+}
+
+#[test]
+fn errors_point_at_line_and_column() {
+    let err = |text| Circuit::from_text(text).unwrap_err().to_string();
+
+    assert_eq!(
+        err("[x] -> [out],\nconstraints = [\n    (x@Input / 2) -> out@Output\n]"),
+        "line 3, column 14: unexpected character `/`"
+    );
+    assert_eq!(
+        err("[x] -> [out],\nconstraints = [\n    (x@Input + 2) -> out@Outptu\n]"),
+        "line 3, column 26: expected `Input`, `Witness` or `Output`, found `Outptu`"
+    );
+    assert_eq!(
+        err("[x] -> [out],\nconstraints = [\n  (x@Input + x@Input) out@Output\n]"),
+        "line 3, column 23: expected `->`, found `out`"
+    );
+    assert_eq!(
+        err("[bits[2]] -> [out],\nconstraints = [\n  (bits[2]@Input + 1) -> out@Output\n]"),
+        "line 3, column 4: Signal 'bits[2]' is out of bounds for array 'bits' of shape [2]"
+    );
+    assert_eq!(
+        err("[x] -> [out]\nconstraints = []"),
+        "line 2, column 1: expected `,`, found `constraints`"
+    );
+    assert_eq!(
+        err("[x] -> [\"out]"),
+        "line 1, column 9: unterminated string"
+    );
+    // constants and coefficients are reduced, as the binary and serde formats require
+    assert_eq!(
+        err("[x] -> [out],\nconstraints = [\n    (x@Input + 9000) -> out@Output\n]"),
+        "line 3, column 16: 9000 is not a field element, it must be below 7489"
+    );
+    assert_eq!(
+        err("[x] -> [],\nconstraints = [\n    (x@Input == {7489*x@Input + 1}) -> 0\n]"),
+        "line 3, column 18: 7489 is not a field element, it must be below 7489"
+    );
+    assert_eq!(
+        err("[x] -> [],\nconstraints = [\n    (x@Input == {x@Input - 7490}) -> 0\n]"),
+        "line 3, column 28: 7490 is not a field element, it must be below 7489"
+    );
+
+    // at the end of the input the error points at the end, not the last token
+    assert_eq!(
+        err("["),
+        "line 1, column 2: expected a signal name, found end of input"
+    );
+    assert_eq!(
+        err("[x] -> [],\nconstraints = [\n    (x@Input == {x@Input"),
+        "line 3, column 25: expected `+`, `-` or `}`, found end of input"
+    );
+    assert_eq!(
+        Gate::new("g", &["v"], "v^").unwrap_err().to_string(),
+        "line 1, column 3: expected an exponent, found end of input"
+    );
+}
@@ -0,0 +1,107 @@
+use crate::{circuit, constraint, witness, WitnessError, WitnessGenerator};
+
+// out = (r * r == x), with r * r computed through an intermediate
+fn square_check() -> crate::Circuit {
+    circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((r@Witness * r@Witness) -> sq@Witness),
+            constraint!((sq@Witness + zero@Witness) -> padded@Witness),
+            constraint!((padded@Witness == x@Input) -> out@Output)
+        ]
+    }
+}
+
+#[test]
+fn trace_records_every_step() {
+    let c = square_check();
+    let trace = WitnessGenerator::new(&c).trace(&witness! { x = 9, r = 3, zero = 0 });
+    assert!(trace.failure.is_none());
+    let steps: Vec<_> = trace
+        .steps
+        .iter()
+        .map(|s| (s.constraint, s.value.value))
+        .collect();
+    assert_eq!(steps, [(0, 9), (1, 9), (2, 9)]);
+    assert_eq!(trace.values["padded"].value, 9);
+}
+
+#[test]
+fn failure_reports_source_operands_and_chain() {
+    let c = square_check();
+    let trace = WitnessGenerator::new(&c).trace(&witness! { x = 8, r = 3, zero = 0 });
+    let failure = trace.failure.unwrap();
+    assert!(matches!(
+        failure.error,
+        WitnessError::ConstraintFailed { constraint: 2, .. }
+    ));
+    assert_eq!(
+        failure.source.as_deref(),
+        Some("constraint!((padded@Witness == x@Input) -> out@Output)")
+    );
+    let operands: Vec<_> = failure
+        .operands
+        .iter()
+        .map(|(s, v)| (s.name().unwrap(), v.as_ref().unwrap().value))
+        .collect();
+    assert_eq!(operands, [("padded", 9), ("x", 8)]);
+    let chain: Vec<_> = failure.chain.iter().map(|(s, _)| s.constraint).collect();
+    assert_eq!(chain, [0, 1]);
+    // the intermediate values leading up to the failure are kept
+    assert_eq!(trace.values["sq"].value, 9);
+}
+
+#[test]
+fn report_lists_operands_and_how_they_were_computed() {
+    let c = square_check();
+    let failure = WitnessGenerator::new(&c)
+        .trace(&witness! { x = 8, r = 3, zero = 0 })
+        .failure
+        .unwrap();
+    let report = failure.to_string();
+    assert!(report.starts_with("Constraint equation failed! constraint #2: 9 != 8\n  source: "));
+    assert!(report.contains("\n  constraint: (padded@Witness == x@Input) -> out@Output"));
+    assert!(report.contains("\n  operands:\n    padded@Witness = 9\n    x@Input = 8"));
+    assert!(report.ends_with(
+        "\n  computed by:\n    #0 (r@Witness * r@Witness) -> sq@Witness = 9\n    \
+         #1 (sq@Witness + zero@Witness) -> padded@Witness = 9"
+    ));
+}
+
+#[test]
+#[should_panic(expected = "computed by:\n    #0 (r@Witness * r@Witness) -> sq@Witness = 9")]
+fn prover_panics_with_the_report() {
+    let c = square_check();
+    crate::prove!(c, witness! { x = 8, r = 3, zero = 0 });
+}
+
+#[test]
+fn missing_values_show_as_unknown() {
+    let c = square_check();
+    let failure = WitnessGenerator::new(&c)
+        .trace(&witness! { x = 9, r = 3 })
+        .failure
+        .unwrap();
+    assert!(matches!(
+        failure.error,
+        WitnessError::MissingValue { constraint: 1, .. }
+    ));
+    assert!(failure.to_string().contains("zero@Witness = ?"));
+    assert!(failure.source.is_some());
+}
+
+#[test]
+fn circuit_blocks_record_sources_per_constraint() {
+    let c = circuit! {
+        [[xs; 3]] -> [out],
+        constraints = {
+            for i in 0..2 {
+                constraint!((xs[i]@Input * xs[i]@Input) -> sq[i]@Witness);
+            }
+            constraint!((sq[0]@Witness + sq[1]@Witness) -> out@Output);
+        }
+    };
+    assert_eq!(c.sources.len(), 3);
+    assert_eq!(c.sources[&1], c.sources[&0]);
+    assert!(c.sources[&2].contains("sq[0]@Witness + sq[1]@Witness"));
+}
@@ -0,0 +1,130 @@
+use crate::{
+    prove, verify, witness, Bool, CircuitBuilder, CircuitSym, Evaluator, FieldElement, Typed, UInt,
+    WitnessGenerator, PRIME,
+};
+
+/// out = a + b as 4-bit integers, or b if the flag is off
+fn masked_sum<S: CircuitSym>(s: &mut S) {
+    let flag = Bool::witness(s, "flag");
+    let a = UInt::<_, 4>::input(s, "a");
+    let b = UInt::<_, 4>::input(s, "b");
+    let sum = a.add(s, &b);
+    let out = flag.select(s, &sum, &b);
+    out.output(s, "out");
+}
+
+fn evaluate(values: crate::Witness) -> Evaluator {
+    let mut eval = Evaluator::new(&values);
+    masked_sum(&mut eval);
+    eval
+}
+
+#[test]
+fn typed_values_insert_their_constraints() {
+    let eval = evaluate(witness! { flag = 1, a = 3, b = 4 });
+    assert!(eval.is_satisfied());
+    assert_eq!(eval.output_value("out").unwrap().value, 7);
+
+    // not a boolean
+    assert!(!evaluate(witness! { flag = 2, a = 3, b = 4 }).is_satisfied());
+    // inputs out of range
+    assert!(!evaluate(witness! { flag = 1, a = 16, b = 0 }).is_satisfied());
+    // 9 + 8 overflows 4 bits
+    assert!(!evaluate(witness! { flag = 1, a = 9, b = 8 }).is_satisfied());
+}
+
+#[test]
+fn built_circuit_checks_types() {
+    let mut builder = CircuitBuilder::new();
+    masked_sum(&mut builder);
+    let c = builder.build();
+
+    let proof = prove!(c, witness! { flag = 0, a = 3, b = 4 });
+    let inputs = [3, 4].map(|v| FieldElement::new(v, PRIME));
+    assert!(verify!(c, [inputs[0].clone(), inputs[1].clone()], proof));
+
+    let generator = WitnessGenerator::new(&c);
+    assert!(generator
+        .complete(&witness! { flag = 1, a = 9, b = 8 })
+        .is_err());
+    assert!(generator
+        .complete(&witness! { flag = 2, a = 3, b = 4 })
+        .is_err());
+}
+
+#[test]
+fn boolean_operations() {
+    for (x, y) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let mut s = Evaluator::new(&witness! { x = x, y = y });
+        let a = Bool::input(&mut s, "x");
+        let b = Bool::input(&mut s, "y");
+        let and = a.and(&mut s, &b);
+        let or = a.or(&mut s, &b);
+        let xor = a.xor(&mut s, &b);
+        let not = a.not(&mut s);
+        for (name, value) in [("and", and), ("or", or), ("xor", xor), ("not", not)] {
+            value.output(&mut s, name);
+        }
+
+        let value = |name| s.output_value(name).unwrap().value;
+        assert_eq!(value("and"), x & y);
+        assert_eq!(value("or"), x | y);
+        assert_eq!(value("xor"), x ^ y);
+        assert_eq!(value("not"), 1 - x);
+    }
+}
+
+#[test]
+fn uint_bits() {
+    let mut s = Evaluator::new(&witness! { v = 6 });
+    let v = UInt::<_, 3>::witness(&mut s, "v");
+    let bits: Vec<u128> = v.to_bits(&mut s).iter().map(|b| b.repr().value).collect();
+    assert_eq!(bits, vec![0, 1, 1]);
+    assert!(s.is_satisfied());
+}
+
+#[test]
+#[should_panic(expected = "UInt<13> does not fit in the field of size 7489")]
+fn uint_wider_than_the_field_is_rejected() {
+    let mut s = CircuitBuilder::new();
+    UInt::<_, 13>::input(&mut s, "x");
+}
+
+#[test]
+#[should_panic(expected = "Constant 16 does not fit in UInt<4>")]
+fn uint_constant_out_of_range_is_rejected() {
+    let mut s = CircuitBuilder::new();
+    UInt::<_, 4>::constant(&mut s, 16);
+}
+
+#[test]
+fn uint_product_overflow_is_rejected() {
+    let product = |x, y| {
+        let mut s = Evaluator::new(&witness! { x = x, y = y });
+        let x = UInt::<_, 6>::input(&mut s, "x");
+        let y = UInt::<_, 6>::input(&mut s, "y");
+        x.mul(&mut s, &y).output(&mut s, "p");
+        s
+    };
+    assert!(product(7, 9).is_satisfied());
+    assert!(!product(8, 9).is_satisfied());
+    assert!(!product(63, 63).is_satisfied());
+}
+
+#[test]
+#[should_panic(expected = "UInt<8> products can wrap around the field of size 7489")]
+fn uint_products_that_can_wrap_are_rejected() {
+    // 75 * 100 = 7500 is 11 in the field, which fits in 8 bits
+    let mut s = CircuitBuilder::new();
+    let x = UInt::<_, 8>::input(&mut s, "x");
+    let y = UInt::<_, 8>::input(&mut s, "y");
+    x.mul(&mut s, &y);
+}
+
+#[test]
+#[should_panic(expected = "UInt<12> sums can wrap around the field of size 7489")]
+fn uint_sums_that_can_wrap_are_rejected() {
+    let mut s = CircuitBuilder::new();
+    let x = UInt::<_, 12>::input(&mut s, "x");
+    x.add(&mut s, &x);
+}
@@ -0,0 +1,116 @@
+use crate::{circuit, constraint, witness, UnderconstrainedKind};
+
+#[test]
+fn well_constrained_circuit_has_no_findings() {
+    let c = circuit! {
+        [x, y] -> [out],
+        constraints = [
+            constraint!((w@Witness + x@Input) -> tmp@Witness),
+            constraint!((tmp@Witness * y@Input) -> out@Output)
+        ]
+    };
+
+    assert!(c.underconstrained_signals().is_empty());
+}
+
+#[test]
+fn detects_unused_and_unconstrained_signals() {
+    let c = circuit! {
+        [x] -> [out, other],
+        constraints = [
+            constraint!((w@Witness + x@Input) -> out@Output),
+            constraint!((w@Witness * unused@Witness) -> dangling@Witness),
+            constraint!((a@Witness == b@Witness) -> same@Witness)
+        ]
+    };
+
+    let findings = c.underconstrained_signals();
+    let kind_of = |name: &str| {
+        findings
+            .iter()
+            .find(|f| f.signal.name() == Some(name))
+            .map(|f| (f.kind.clone(), f.constraints.clone()))
+    };
+
+    assert_eq!(
+        kind_of("unused"),
+        Some((UnderconstrainedKind::Unused, vec![1]))
+    );
+    assert_eq!(
+        kind_of("dangling"),
+        Some((UnderconstrainedKind::Unused, vec![1]))
+    );
+    assert_eq!(
+        kind_of("a"),
+        Some((UnderconstrainedKind::OnlyInEquality, vec![2]))
+    );
+    assert_eq!(
+        kind_of("other"),
+        Some((UnderconstrainedKind::UnassignedOutput, vec![]))
+    );
+    assert_eq!(kind_of("w"), None);
+}
+
+#[test]
+fn witness_checked_through_an_intermediate_is_used() {
+    let c = circuit! {
+        [x] -> [],
+        constraints = [
+            constraint!((w@Witness * w@Witness) -> sq@Witness),
+            constraint!((sq@Witness == x@Input) -> ok@Witness)
+        ]
+    };
+
+    assert!(c
+        .underconstrained_signals()
+        .iter()
+        .all(|f| f.signal.name() != Some("w")));
+}
+
+#[test]
+fn detects_output_independent_of_inputs() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w@Witness * w@Witness) -> out@Output),
+            constraint!((x@Input == x@Input) -> check@Witness)
+        ]
+    };
+
+    let findings = c.underconstrained_signals();
+    assert!(findings
+        .iter()
+        .any(|f| f.signal.name() == Some("out")
+            && f.kind == UnderconstrainedKind::IndependentOfInputs));
+}
+
+#[test]
+fn randomized_check_finds_alternative_square_root() {
+    // out = w * w is satisfied by both w and -w
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w@Witness * w@Witness) -> sq@Witness),
+            constraint!((sq@Witness + x@Input) -> out@Output)
+        ]
+    };
+    let w = witness! { w = 3, x = 1 };
+
+    let alternative = c
+        .find_alternative_witness(&w, 100_000)
+        .expect("expected the negated root to be found");
+    assert_eq!(alternative.values["w"].value, crate::PRIME - 3);
+}
+
+#[test]
+fn randomized_check_on_injective_circuit_finds_nothing() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w@Witness + x@Input) -> out@Output)
+        ]
+    };
+    let w = witness! { w = 5, x = 10 };
+
+    assert!(c.find_alternative_witness(&w, 1_000).is_none());
+}
@@ -0,0 +1,87 @@
+use crate::{
+    circuit, constraint, prove, verify, witness, FieldElement, WitnessError, WitnessGenerator,
+    PRIME,
+};
+
+#[test]
+fn generates_intermediate_and_output_values() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w1@Witness + w2@Witness) -> tmp1@Witness),
+            constraint!((tmp1@Witness * x@Input) -> tmp2@Witness),
+            constraint!((tmp2@Witness - w3@Witness) -> out@Output)
+        ]
+    };
+    let private = witness! { w1 = 2, w2 = 3, w3 = 10 };
+
+    let full = WitnessGenerator::new(&c)
+        .generate(&[FieldElement::new(4, PRIME)], &private)
+        .unwrap();
+
+    assert_eq!(full.values["tmp1"].value, 5);
+    assert_eq!(full.values["tmp2"].value, 20);
+    assert_eq!(full.values["out"].value, 10);
+    assert!(c.check_witness(&full).is_ok());
+
+    let proof = prove!(c, full);
+    assert!(verify!(c, [FieldElement::new(4, PRIME)], proof));
+}
+
+#[test]
+fn check_witness_rejects_tampered_values() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w@Witness + x@Input) -> out@Output)
+        ]
+    };
+    let mut full = WitnessGenerator::new(&c)
+        .complete(&witness! { w = 5, x = 10 })
+        .unwrap();
+    full.values
+        .insert("out".to_string(), FieldElement::new(16, PRIME));
+
+    assert!(matches!(
+        c.check_witness(&full),
+        Err(WitnessError::ConstraintFailed { constraint: 0, .. })
+    ));
+}
+
+#[test]
+fn reports_missing_values() {
+    let c = circuit! {
+        [x] -> [out],
+        constraints = [
+            constraint!((w@Witness + x@Input) -> out@Output)
+        ]
+    };
+
+    let err = WitnessGenerator::new(&c)
+        .generate(&[FieldElement::new(1, PRIME)], &witness! {})
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Missing witness value for signal 'w' used by constraint #0"
+    );
+}
+
+#[test]
+fn hint_supplies_inverse() {
+    // x * inv = one, the prover only knows x
+    let c = circuit! {
+        [x] -> [one],
+        constraints = [
+            constraint!((x@Input * inv@Witness) -> one@Output)
+        ]
+    };
+
+    let full = WitnessGenerator::new(&c)
+        .with_hint(&["inv"], &["x"], |args| vec![args[0].inverse().unwrap()])
+        .generate(&[FieldElement::new(7, PRIME)], &witness! {})
+        .unwrap();
+
+    assert_eq!(full.values["one"].value, 1);
+    let proof = prove!(c, full);
+    assert!(verify!(c, [FieldElement::new(7, PRIME)], proof));
+}
@@ -0,0 +1,93 @@
+use crate::{
+    le_bits, prove, witness, CircuitBuilder, CircuitSym, Evaluator, FieldElement, UInt32, UInt64,
+    Verifier, Witness, PRIME,
+};
+
+fn values(a: u128, b: u128) -> Witness {
+    witness! { a = le_bits::<64>(a), b = le_bits::<64>(b) }
+}
+
+fn eval32(a: u32, b: u32) -> (Evaluator, UInt32<FieldElement>, UInt32<FieldElement>) {
+    let mut s = Evaluator::new(&values(a.into(), b.into()));
+    let x = UInt32::witness(&mut s, "a");
+    let y = UInt32::witness(&mut s, "b");
+    (s, x, y)
+}
+
+#[test]
+fn wrapping_arithmetic_matches_u32() {
+    for (a, b) in [
+        (0xffff_ffff, 2),
+        (123_456_789, 987_654_321),
+        (0x8000_0000, 0x8000_0000),
+    ] {
+        let (mut s, x, y) = eval32(a, b);
+        assert_eq!(x.add(&mut s, &y).value(), a.wrapping_add(b) as u128);
+        assert_eq!(x.mul(&mut s, &y).value(), a.wrapping_mul(b) as u128);
+        assert!(s.is_satisfied());
+    }
+}
+
+#[test]
+fn bitwise_operations_match_u32() {
+    let (a, b) = (0xdead_beef_u32, 0x0f0f_1234_u32);
+    let (mut s, x, y) = eval32(a, b);
+    assert_eq!(x.and(&mut s, &y).value(), (a & b) as u128);
+    assert_eq!(x.or(&mut s, &y).value(), (a | b) as u128);
+    assert_eq!(x.xor(&mut s, &y).value(), (a ^ b) as u128);
+    assert_eq!(x.not(&mut s).value(), (!a) as u128);
+    assert_eq!(x.shl(&mut s, 7).value(), (a << 7) as u128);
+    assert_eq!(x.shr(&mut s, 7).value(), (a >> 7) as u128);
+    assert_eq!(x.rotl(13).value(), a.rotate_left(13) as u128);
+    assert_eq!(x.rotr(13).value(), a.rotate_right(13) as u128);
+}
+
+#[test]
+fn uint64_arithmetic_matches_u64() {
+    let (a, b) = (0xffff_ffff_0000_1234_u64, 0x1234_5678_9abc_def0_u64);
+    let mut s = Evaluator::new(&values(a.into(), b.into()));
+    let x = UInt64::witness(&mut s, "a");
+    let y = UInt64::witness(&mut s, "b");
+    let c = UInt64::constant(&mut s, 0x0101_0101_0101_0101);
+
+    assert_eq!(x.add(&mut s, &y).value(), a.wrapping_add(b) as u128);
+    assert_eq!(
+        x.mul(&mut s, &c).value(),
+        a.wrapping_mul(0x0101_0101_0101_0101) as u128
+    );
+    assert_eq!(
+        x.xor(&mut s, &y).rotr(17).value(),
+        (a ^ b).rotate_right(17) as u128
+    );
+}
+
+#[test]
+fn wrapping_sum_proves_and_verifies() {
+    fn sum<S: CircuitSym>(s: &mut S) {
+        let a = UInt32::input(s, "a");
+        let b = UInt32::input(s, "b");
+        a.add(s, &b).output(s, "sum");
+    }
+    let mut builder = CircuitBuilder::new();
+    sum(&mut builder);
+    let c = builder.build();
+
+    let proof = prove!(c, values(0xffff_fff0, 0x20));
+    let public: Vec<FieldElement> = le_bits::<32>(0xffff_fff0)
+        .into_iter()
+        .chain(le_bits::<32>(0x20))
+        .map(|bit| FieldElement::new(bit, PRIME))
+        .collect();
+    assert!(Verifier::verify(&c, &public, &proof));
+}
+
+#[test]
+fn non_boolean_bits_are_rejected() {
+    let mut values = values(5, 6);
+    values
+        .values
+        .insert("a[3]".to_string(), FieldElement::new(2, PRIME));
+    let mut s = Evaluator::new(&values);
+    UInt32::witness(&mut s, "a");
+    assert!(!s.is_satisfied());
+}
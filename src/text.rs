@@ -0,0 +1,1066 @@
+//! The `.ghb` text format, mirroring the `circuit!` syntax:
+//!
+//! ```text
+//! // comments run to the end of the line
+//! [x, bits[4], [grid; 2]] -> [out],
+//! constraints = [
+//!     (a@Witness * x@Input) -> c@Witness,
+//!     (c@Witness + 1) -> d@Witness,
+//!     (bits[0]@Input ? c@Witness : d@Witness) -> e@Witness,
+//!     (e@Witness ? [grid[0]@Input, grid[1]@Input]) -> out@Output,
+//!     ({2*c@Witness - d@Witness + 1} * x@Input) -> f@Witness,
+//! ]
+//! ```
+//!
+//! Bare numbers are constants, and braces hold a linear combination. A quoted
+//! name like `"x[5]"` declares a single signal with that name instead of an
+//! array. Witness arrays may be declared after the header, as in
+//! `witnesses = [acc[4]],`, so that their indices are checked too. Hints are
+//! Rust closures and are not part of the format.
+//!
+//! Custom gates are declared before the constraints and called by name:
+//!
+//! ```text
+//! [x] -> [out],
+//! gates = [
+//!     sbox(v) = v^5,
+//! ],
+//! constraints = [
+//!     (sbox(x@Input)) -> out@Output,
+//! ]
+//! ```
+//!
+//! So are lookup tables, given by a kind (`range`, `xor` or `and` with a number
+//! of bits) or row by row. A lookup names the table like a gate:
+//!
+//! ```text
+//! [a, b] -> [c],
+//! tables = [
+//!     xor8(a, b, c) = xor(8),
+//!     small(v) = [(1), (2), (3)],
+//! ],
+//! constraints = [
+//!     (xor8(a@Input, b@Input)) -> c@Output,
+//!     (small(a@Input)) -> 0,
+//! ]
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    circuit::{array_shapes, out_of_bounds, Circuit},
+    constraint::{Constraint, Operation},
+    gate::{Gate, Polynomial},
+    linear::LinearCombination,
+    lookup::{Table, TableKind},
+    signal::{Signal, SignalName},
+    PRIME,
+};
+
+/// Where and why a `.ghb` text could not be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Circuit {
+    /// Reads a circuit written in the `.ghb` format
+    pub fn from_text(text: &str) -> Result<Circuit, ParseError> {
+        Parser::new(text)?.circuit()
+    }
+
+    /// Writes the circuit in the `.ghb` format, which `from_text` reads back
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "[{}] -> [{}],\n",
+            declarations(&self.inputs),
+            declarations(&self.outputs)
+        );
+        if !self.witnesses.is_empty() {
+            text.push_str(&format!(
+                "witnesses = [{}],\n",
+                declarations(&self.witnesses)
+            ));
+        }
+        let mut gates: Vec<&Gate> = Vec::new();
+        for c in &self.constraints {
+            if let Operation::Custom(gate, _) = &c.operation {
+                if !gates.contains(&gate) {
+                    gates.push(gate);
+                }
+            }
+        }
+        if !gates.is_empty() {
+            text.push_str("gates = [\n");
+            for gate in gates {
+                text.push_str(&format!("    {},\n", gate_text(gate)));
+            }
+            text.push_str("],\n");
+        }
+        let tables = self.tables();
+        if !tables.is_empty() {
+            text.push_str("tables = [\n");
+            for table in tables {
+                text.push_str(&format!("    {},\n", table_text(table)));
+            }
+            text.push_str("],\n");
+        }
+        text.push_str("constraints = [\n");
+        for constraint in &self.constraints {
+            text.push_str(&format!("    {},\n", constraint_text(constraint)));
+        }
+        text.push_str("]\n");
+        text
+    }
+}
+
+pub(crate) fn constraint_text(c: &Constraint) -> String {
+    let expr = match &c.operation {
+        Operation::Add => format!("{} + {}", operand(&c.left), operand(&c.right)),
+        Operation::Sub => format!("{} - {}", operand(&c.left), operand(&c.right)),
+        Operation::Mul => format!("{} * {}", operand(&c.left), operand(&c.right)),
+        Operation::Eq => format!("{} == {}", operand(&c.left), operand(&c.right)),
+        Operation::Select(cond) => format!(
+            "{} ? {} : {}",
+            operand(cond),
+            operand(&c.left),
+            operand(&c.right)
+        ),
+        Operation::Mux(options) => format!(
+            "{} ? [{}]",
+            operand(&c.left),
+            options.iter().map(operand).collect::<Vec<_>>().join(", ")
+        ),
+        Operation::Custom(gate, inputs) => format!(
+            "{}({})",
+            gate.name,
+            inputs.iter().map(operand).collect::<Vec<_>>().join(", ")
+        ),
+        Operation::Lookup(table, inputs) => format!(
+            "{}({})",
+            table.name,
+            inputs.iter().map(operand).collect::<Vec<_>>().join(", ")
+        ),
+    };
+    format!("({}) -> {}", expr, operand(&c.output))
+}
+
+pub(crate) fn operand(sig: &Signal) -> String {
+    let (name, role) = match sig {
+        Signal::Input(name) => (name, "Input"),
+        Signal::Witness(name) => (name, "Witness"),
+        Signal::Output(name) => (name, "Output"),
+        Signal::Constant(value) => return value.to_string(),
+        Signal::Linear(lc) => return linear_text(lc),
+    };
+    if is_plain_name(name) {
+        format!("{}@{}", name, role)
+    } else {
+        format!("{}@{}", quote(name), role)
+    }
+}
+
+/// `{2*a@Witness - b@Input + 1}`, writing coefficients above `PRIME / 2` as
+/// negative ones
+fn linear_text(lc: &LinearCombination) -> String {
+    let signed = |value: u128| {
+        if value > PRIME / 2 {
+            ("-", PRIME - value)
+        } else {
+            ("+", value)
+        }
+    };
+    let mut parts: Vec<(&str, String)> = lc
+        .terms
+        .iter()
+        .map(|(sig, coeff)| match signed(*coeff) {
+            (sign, 1) => (sign, operand(sig)),
+            (sign, c) => (sign, format!("{}*{}", c, operand(sig))),
+        })
+        .collect();
+    if lc.constant != 0 || parts.is_empty() {
+        let (sign, c) = signed(lc.constant);
+        parts.push((sign, c.to_string()));
+    }
+    let mut text = String::from("{");
+    for (i, (sign, part)) in parts.iter().enumerate() {
+        match (i, *sign) {
+            (0, "+") => {}
+            (0, _) => text.push('-'),
+            (_, sign) => text.push_str(&format!(" {} ", sign)),
+        }
+        text.push_str(part);
+    }
+    text.push('}');
+    text
+}
+
+/// `sbox(v) = v^5`, writing coefficients above `PRIME / 2` as negative ones
+pub(crate) fn gate_text(gate: &Gate) -> String {
+    let mut text = format!("{}({}) = ", gate.name, gate.wires.join(", "));
+    if gate.polynomial.terms.is_empty() {
+        text.push('0');
+    }
+    for (i, term) in gate.polynomial.terms.iter().enumerate() {
+        let (sign, coeff) = if term.coeff > PRIME / 2 {
+            ("-", PRIME - term.coeff)
+        } else {
+            ("+", term.coeff)
+        };
+        match (i, sign) {
+            (0, "+") => {}
+            (0, _) => text.push('-'),
+            (_, sign) => text.push_str(&format!(" {} ", sign)),
+        }
+        let mut factors: Vec<String> = gate
+            .wires
+            .iter()
+            .zip(&term.powers)
+            .filter(|(_, power)| **power > 0)
+            .map(|(wire, power)| match power {
+                1 => wire.clone(),
+                power => format!("{}^{}", wire, power),
+            })
+            .collect();
+        if coeff != 1 || factors.is_empty() {
+            factors.insert(0, coeff.to_string());
+        }
+        text.push_str(&factors.join("*"));
+    }
+    text
+}
+
+/// `name(a, b, c) = xor(8)`, or the rows as `[(0, 1), (1, 2)]`
+pub(crate) fn table_text(table: &Table) -> String {
+    let rows = match &table.kind {
+        TableKind::Range(bits) => format!("range({})", bits),
+        TableKind::Xor(bits) => format!("xor({})", bits),
+        TableKind::And(bits) => format!("and({})", bits),
+        TableKind::Rows(rows) => format!(
+            "[{}]",
+            rows.iter()
+                .map(|row| format!(
+                    "({})",
+                    row.iter()
+                        .map(u128::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    format!("{}({}) = {}", table.name, table.columns.join(", "), rows)
+}
+
+/// Reads a polynomial over the named wires, see `Gate::new`
+pub(crate) fn parse_polynomial(wires: &[String], text: &str) -> Result<Polynomial, ParseError> {
+    let mut parser = Parser::new(text)?;
+    let polynomial = parser.polynomial(wires)?;
+    if parser.peek().kind != TokenKind::End {
+        return Err(parser.unexpected("end of input"));
+    }
+    Ok(polynomial)
+}
+
+/// Declared signals, with whole arrays written as `bits[8]`
+fn declarations(signals: &[Signal]) -> String {
+    let shapes = array_shapes(signals);
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < signals.len() {
+        let name = signals[i].name().unwrap_or_default();
+        if let Some((base, dims)) = whole_array(&signals[i..], &shapes) {
+            items.push(Signal::element_name(base, &dims));
+            i += dims.iter().product::<usize>();
+        } else if is_identifier(name) {
+            items.push(name.to_string());
+            i += 1;
+        } else {
+            items.push(quote(name));
+            i += 1;
+        }
+    }
+    items.join(", ")
+}
+
+/// The array starting at `signals[0]`, if all of its elements follow in order
+fn whole_array<'a>(
+    signals: &'a [Signal],
+    shapes: &HashMap<String, Vec<usize>>,
+) -> Option<(&'a str, Vec<usize>)> {
+    let (base, indices) = signals[0].array_index()?;
+    let dims = shapes.get(base)?;
+    if indices.is_empty() || indices.iter().any(|&i| i != 0) || !is_identifier(base) {
+        return None;
+    }
+    let names = Signal::element_names(base, dims);
+    let complete = names.len() <= signals.len()
+        && names
+            .iter()
+            .zip(signals)
+            .all(|(name, sig)| sig.name() == Some(name.as_str()));
+    complete.then(|| (base, dims.clone()))
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether the name reads back unquoted: identifiers with indices, joined by dots
+fn is_plain_name(name: &str) -> bool {
+    name.split('.').all(|segment| {
+        let (ident, mut rest) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !is_identifier(ident) {
+            return false;
+        }
+        while !rest.is_empty() {
+            let Some(close) = rest.find(']') else {
+                return false;
+            };
+            let index = &rest[1..close];
+            let canonical = index.parse::<usize>().is_ok_and(|i| i.to_string() == index);
+            if !rest.starts_with('[') || !canonical {
+                return false;
+            }
+            rest = &rest[close + 1..];
+        }
+        true
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Ident(String),
+    Number(u128),
+    Str(String),
+    Punct(&'static str),
+    End,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(name) => write!(f, "`{}`", name),
+            TokenKind::Number(value) => write!(f, "`{}`", value),
+            TokenKind::Str(name) => write!(f, "{}", quote(name)),
+            TokenKind::Punct(p) => write!(f, "`{}`", p),
+            TokenKind::End => write!(f, "end of input"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+const PUNCTUATION: [&str; 18] = [
+    "->", "==", "=", "[", "]", "(", ")", "{", "}", ",", ";", "@", "+", "-", "*", "?", ":", "^",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+    let error = |line, column, message: String| ParseError {
+        line,
+        column,
+        message,
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = (line, column);
+        let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        let starts_name = |c: char| c.is_ascii_alphabetic() || c == '_';
+        let (kind, len) = if c == '\n' {
+            i += 1;
+            line += 1;
+            column = 1;
+            continue;
+        } else if c.is_whitespace() {
+            i += 1;
+            column += 1;
+            continue;
+        } else if rest == "//" {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        } else if starts_name(c) || (c == '.' && chars.get(i + 1).is_some_and(|c| starts_name(*c)))
+        {
+            // dotted segments stay in one token, e.g. `sq.out` or `.out` after `h[0]`
+            let len = 1 + chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '.')
+                .count();
+            (TokenKind::Ident(chars[i..i + len].iter().collect()), len)
+        } else if c.is_ascii_digit() {
+            let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+            let digits: String = chars[i..i + len].iter().collect();
+            let value = digits
+                .parse()
+                .map_err(|_| error(line, column, format!("number `{}` is too large", digits)))?;
+            (TokenKind::Number(value), len)
+        } else if c == '"' {
+            let mut name = String::new();
+            let mut len = 1;
+            loop {
+                match chars.get(i + len) {
+                    None | Some('\n') => {
+                        return Err(error(line, column, "unterminated string".to_string()))
+                    }
+                    Some('"') => break,
+                    Some('\\') if i + len + 1 < chars.len() => {
+                        name.push(chars[i + len + 1]);
+                        len += 2;
+                    }
+                    Some(c) => {
+                        name.push(*c);
+                        len += 1;
+                    }
+                }
+            }
+            (TokenKind::Str(name), len + 1)
+        } else if let Some(p) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+            (TokenKind::Punct(p), p.len())
+        } else {
+            return Err(error(line, column, format!("unexpected character `{}`", c)));
+        };
+        i += len;
+        column += len;
+        tokens.push(Token {
+            kind,
+            line: start.0,
+            column: start.1,
+        });
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        line,
+        column,
+    });
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    shapes: HashMap<String, Vec<usize>>,
+    gates: HashMap<String, Gate>,
+    tables: HashMap<String, Table>,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            pos: 0,
+            shapes: HashMap::new(),
+            gates: HashMap::new(),
+            tables: HashMap::new(),
+        })
+    }
+
+    fn circuit(mut self) -> Result<Circuit, ParseError> {
+        self.expect("[")?;
+        let inputs = self.declarations(Signal::Input)?;
+        self.expect("->")?;
+        self.expect("[")?;
+        let outputs = self.declarations(Signal::Output)?;
+        self.expect(",")?;
+        let mut witnesses = Vec::new();
+        if matches!(&self.peek().kind, TokenKind::Ident(word) if word == "witnesses") {
+            self.pos += 1;
+            self.expect("=")?;
+            self.expect("[")?;
+            witnesses = self.declarations(Signal::Witness)?;
+            self.expect(",")?;
+        }
+        self.shapes = array_shapes(inputs.iter().chain(&outputs).chain(&witnesses));
+
+        if matches!(&self.peek().kind, TokenKind::Ident(word) if word == "gates") {
+            self.pos += 1;
+            self.expect("=")?;
+            self.expect("[")?;
+            while !self.eat("]") {
+                self.gate()?;
+                if !self.eat(",") {
+                    self.expect("]")?;
+                    break;
+                }
+            }
+            self.expect(",")?;
+        }
+        if matches!(&self.peek().kind, TokenKind::Ident(word) if word == "tables") {
+            self.pos += 1;
+            self.expect("=")?;
+            self.expect("[")?;
+            while !self.eat("]") {
+                self.table()?;
+                if !self.eat(",") {
+                    self.expect("]")?;
+                    break;
+                }
+            }
+            self.expect(",")?;
+        }
+
+        match &self.peek().kind {
+            TokenKind::Ident(word) if word == "constraints" => self.pos += 1,
+            _ => return Err(self.unexpected("`constraints`")),
+        }
+        self.expect("=")?;
+        self.expect("[")?;
+        let mut constraints = Vec::new();
+        while !self.eat("]") {
+            constraints.push(self.constraint()?);
+            if !self.eat(",") {
+                self.expect("]")?;
+                break;
+            }
+        }
+        if self.peek().kind != TokenKind::End {
+            return Err(self.unexpected("end of input"));
+        }
+        Ok(Circuit::new(inputs, outputs, constraints).with_witnesses(witnesses))
+    }
+
+    /// Signals up to the closing `]`
+    fn declarations(&mut self, role: fn(SignalName) -> Signal) -> Result<Vec<Signal>, ParseError> {
+        let mut signals = Vec::new();
+        while !self.eat("]") {
+            let start = self.pos;
+            match self.next().kind {
+                TokenKind::Str(name) => signals.push(role(name.into())),
+                TokenKind::Ident(name) if is_identifier(&name) => {
+                    let mut dims = Vec::new();
+                    while self.eat("[") {
+                        dims.push(self.index()?);
+                        self.expect("]")?;
+                    }
+                    if dims.is_empty() {
+                        signals.push(role(name.into()));
+                    } else {
+                        signals.extend(SignalName::elements(&name, &dims).into_iter().map(role));
+                    }
+                }
+                TokenKind::Punct("[") => {
+                    let name = self.identifier()?;
+                    self.expect(";")?;
+                    let len = self.index()?;
+                    self.expect("]")?;
+                    signals.extend(SignalName::elements(&name, &[len]).into_iter().map(role));
+                }
+                _ => {
+                    self.pos = start;
+                    return Err(self.unexpected("a signal name"));
+                }
+            }
+            if !self.eat(",") {
+                self.expect("]")?;
+                break;
+            }
+        }
+        Ok(signals)
+    }
+
+    /// `name(a, b) = a*b + 1`
+    fn gate(&mut self) -> Result<(), ParseError> {
+        let (name, wires) = self.signature()?;
+        let polynomial = self.polynomial(&wires)?;
+        self.gates.insert(
+            name.clone(),
+            Gate {
+                name,
+                wires,
+                polynomial,
+            },
+        );
+        Ok(())
+    }
+
+    /// `name(a, b, c) = xor(8)` or `name(a, b) = [(0, 1), (1, 2)]`
+    fn table(&mut self) -> Result<(), ParseError> {
+        let (name, columns) = self.signature()?;
+        let start = self.pos;
+        let kind = if self.eat("[") {
+            let mut rows = Vec::new();
+            while !self.eat("]") {
+                let at = self.pos;
+                self.expect("(")?;
+                let mut row = Vec::new();
+                while !self.eat(")") {
+                    let number = self.pos;
+                    match self.next().kind {
+                        TokenKind::Number(value) => row.push(value),
+                        _ => {
+                            self.pos = number;
+                            return Err(self.unexpected("a number"));
+                        }
+                    }
+                    if !self.eat(",") {
+                        self.expect(")")?;
+                        break;
+                    }
+                }
+                if row.len() != columns.len() {
+                    return Err(self.error_at(
+                        at,
+                        format!("table `{}` has {} columns", name, columns.len()),
+                    ));
+                }
+                rows.push(row);
+                if !self.eat(",") {
+                    self.expect("]")?;
+                    break;
+                }
+            }
+            TableKind::Rows(rows)
+        } else {
+            let word = self.identifier()?;
+            self.expect("(")?;
+            let bits = self.index()?;
+            self.expect(")")?;
+            if bits >= 64 || 1u128 << bits > PRIME {
+                return Err(self.error_at(start, format!("{} bits don't fit the field", bits)));
+            }
+            let bits = bits as u32;
+            let (kind, width) = match word.as_str() {
+                "range" => (TableKind::Range(bits), 1),
+                "xor" => (TableKind::Xor(bits), 3),
+                "and" => (TableKind::And(bits), 3),
+                _ => return Err(self.error_at(start, format!("unknown table kind `{}`", word))),
+            };
+            if columns.len() != width {
+                return Err(
+                    self.error_at(start, format!("a `{}` table has {} columns", word, width))
+                );
+            }
+            kind
+        };
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        self.tables
+            .insert(name.clone(), Table::with_kind(&name, &columns, kind));
+        Ok(())
+    }
+
+    /// `name(a, b) =` of a gate or a table, whose name is new
+    fn signature(&mut self) -> Result<(String, Vec<String>), ParseError> {
+        let start = self.pos;
+        let name = self.identifier()?;
+        if self.gates.contains_key(&name) || self.tables.contains_key(&name) {
+            return Err(self.error_at(start, format!("`{}` is declared twice", name)));
+        }
+        self.expect("(")?;
+        let mut wires = Vec::new();
+        while !self.eat(")") {
+            let at = self.pos;
+            let wire = self.identifier()?;
+            if wires.contains(&wire) {
+                return Err(self.error_at(at, format!("`{}` is declared twice", wire)));
+            }
+            wires.push(wire);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        self.expect("=")?;
+        Ok((name, wires))
+    }
+
+    /// Terms joined by `+` and `-`, optionally starting with `-`
+    fn polynomial(&mut self, wires: &[String]) -> Result<Polynomial, ParseError> {
+        let negate = self.eat("-");
+        let first = self.product(wires)?;
+        let mut sum = if negate {
+            Polynomial::constant(wires.len(), 0).sub(&first)
+        } else {
+            first
+        };
+        loop {
+            let start = self.pos;
+            if self.eat("+") {
+                sum = sum.add(&self.product(wires)?);
+            } else if self.eat("-") {
+                sum = sum.sub(&self.product(wires)?);
+            } else {
+                return Ok(sum);
+            }
+            if sum.terms.len() > Gate::MAX_TERMS {
+                return Err(self.terms_error(start));
+            }
+        }
+    }
+
+    /// Factors joined by `*`, each a number, a wire or a parenthesized
+    /// polynomial, optionally raised to a constant power with `^`
+    fn product(&mut self, wires: &[String]) -> Result<Polynomial, ParseError> {
+        let mut product = Polynomial::constant(wires.len(), 1);
+        loop {
+            let start = self.pos;
+            let factor = match self.next().kind {
+                TokenKind::Number(value) => Polynomial::constant(wires.len(), value),
+                TokenKind::Ident(name) => match wires.iter().position(|w| *w == name) {
+                    Some(index) => Polynomial::wire(wires.len(), index),
+                    None => return Err(self.error_at(start, format!("unknown wire `{}`", name))),
+                },
+                TokenKind::Punct("(") => {
+                    let inner = self.polynomial(wires)?;
+                    self.expect(")")?;
+                    inner
+                }
+                _ => {
+                    self.pos = start;
+                    return Err(self.unexpected("a wire, a number or `(`"));
+                }
+            };
+            let factor = if self.eat("^") {
+                let exponent = self.pos;
+                match self.next().kind {
+                    TokenKind::Number(exponent) if exponent <= u32::MAX as u128 => {
+                        if factor.degree() as u128 * exponent > Gate::MAX_DEGREE as u128 {
+                            return Err(self.degree_error(start));
+                        }
+                        factor
+                            .bounded_pow(exponent as u32, Gate::MAX_TERMS)
+                            .ok_or_else(|| self.terms_error(start))?
+                    }
+                    _ => {
+                        self.pos = exponent;
+                        return Err(self.unexpected("an exponent"));
+                    }
+                }
+            } else {
+                factor
+            };
+            if product.degree() + factor.degree() > Gate::MAX_DEGREE {
+                return Err(self.degree_error(start));
+            }
+            product = product
+                .bounded_mul(&factor, Gate::MAX_TERMS)
+                .ok_or_else(|| self.terms_error(start))?;
+            if !self.eat("*") {
+                return Ok(product);
+            }
+        }
+    }
+
+    fn constraint(&mut self) -> Result<Constraint, ParseError> {
+        self.expect("(")?;
+        if let Some(constraint) = self.custom()? {
+            return Ok(constraint);
+        }
+        let first = self.operand()?;
+        let constraint = if self.eat("?") {
+            if self.eat("[") {
+                let mut options = vec![self.operand()?];
+                while self.eat(",") {
+                    if self.peek().kind == TokenKind::Punct("]") {
+                        break;
+                    }
+                    options.push(self.operand()?);
+                }
+                self.expect("]")?;
+                self.expect(")")?;
+                self.expect("->")?;
+                Constraint::mux(first, options, self.operand()?)
+            } else {
+                let then = self.operand()?;
+                self.expect(":")?;
+                let otherwise = self.operand()?;
+                self.expect(")")?;
+                self.expect("->")?;
+                Constraint::select(first, then, otherwise, self.operand()?)
+            }
+        } else {
+            let start = self.pos;
+            let operation = match self.next().kind {
+                TokenKind::Punct("+") => Operation::Add,
+                TokenKind::Punct("-") => Operation::Sub,
+                TokenKind::Punct("*") => Operation::Mul,
+                TokenKind::Punct("==") => Operation::Eq,
+                _ => {
+                    self.pos = start;
+                    return Err(self.unexpected("`+`, `-`, `*`, `==` or `?`"));
+                }
+            };
+            let right = self.operand()?;
+            self.expect(")")?;
+            self.expect("->")?;
+            Constraint {
+                left: first,
+                right,
+                output: self.operand()?,
+                operation,
+            }
+        };
+        Ok(constraint)
+    }
+
+    /// The rest of `(sbox(x@Input)) -> y@Witness` after the opening parenthesis,
+    /// if it calls a gate or looks up a table
+    fn custom(&mut self) -> Result<Option<Constraint>, ParseError> {
+        let start = self.pos;
+        let TokenKind::Ident(name) = self.peek().kind.clone() else {
+            return Ok(None);
+        };
+        if self.tokens[start + 1].kind != TokenKind::Punct("(") {
+            return Ok(None);
+        }
+        let gate = self.gates.get(&name).cloned();
+        let table = self.tables.get(&name).cloned();
+        if gate.is_none() && table.is_none() {
+            return Err(self.error_at(start, format!("unknown gate or table `{}`", name)));
+        }
+        self.pos += 2;
+        let mut inputs = Vec::new();
+        while !self.eat(")") {
+            inputs.push(self.operand()?);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        let arity_error = |expected: String| {
+            format!("`{}` takes {} inputs, got {}", name, expected, inputs.len())
+        };
+        match (&gate, &table) {
+            (Some(gate), _) if inputs.len() != gate.wires.len() => {
+                return Err(self.error_at(start, arity_error(gate.wires.len().to_string())));
+            }
+            (_, Some(table))
+                if inputs.is_empty()
+                    || inputs.len() > table.width()
+                    || inputs.len() + 1 < table.width() =>
+            {
+                let expected = format!("{} or {}", table.width() - 1, table.width());
+                return Err(self.error_at(start, arity_error(expected)));
+            }
+            _ => {}
+        }
+        self.expect(")")?;
+        self.expect("->")?;
+        let output = self.operand()?;
+        Ok(Some(match (gate, table) {
+            (Some(gate), _) => Constraint::custom(&gate, inputs, output),
+            (_, Some(table)) => Constraint::lookup(&table, inputs, output),
+            (None, None) => unreachable!("checked above"),
+        }))
+    }
+
+    /// `name@Role`, a constant or a linear combination
+    fn operand(&mut self) -> Result<Signal, ParseError> {
+        let start = self.pos;
+        let name = match self.next().kind {
+            TokenKind::Number(value) => return Ok(Signal::Constant(self.element(start, value)?)),
+            TokenKind::Punct("{") => return self.linear(),
+            TokenKind::Str(name) => name,
+            TokenKind::Ident(mut name) => {
+                // indices and further dotted segments, e.g. `h[0].out[1]`
+                while self.peek().kind == TokenKind::Punct("[") {
+                    self.pos += 1;
+                    name.push_str(&format!("[{}]", self.index()?));
+                    self.expect("]")?;
+                    if let TokenKind::Ident(rest) = &self.peek().kind {
+                        if rest.starts_with('.') {
+                            name.push_str(rest);
+                            self.pos += 1;
+                        }
+                    }
+                }
+                if !is_plain_name(&name) {
+                    self.pos = start;
+                    return Err(self.unexpected("a signal name"));
+                }
+                name
+            }
+            _ => {
+                self.pos = start;
+                return Err(self.unexpected("a signal or a number"));
+            }
+        };
+        self.expect("@")?;
+        let role = self.pos;
+        let signal = match self.next().kind {
+            TokenKind::Ident(role) if role == "Input" => Signal::Input(name.into()),
+            TokenKind::Ident(role) if role == "Witness" => Signal::Witness(name.into()),
+            TokenKind::Ident(role) if role == "Output" => Signal::Output(name.into()),
+            _ => {
+                self.pos = role;
+                return Err(self.unexpected("`Input`, `Witness` or `Output`"));
+            }
+        };
+        if let Some(message) = out_of_bounds(&self.shapes, &signal) {
+            return Err(self.error_at(start, message));
+        }
+        Ok(signal)
+    }
+
+    /// The rest of `{2*a@Witness - b@Input + 1}` after the opening brace
+    fn linear(&mut self) -> Result<Signal, ParseError> {
+        let mut lc = LinearCombination::default();
+        let mut negate = self.eat("-");
+        loop {
+            let start = self.pos;
+            let term = match self.next().kind {
+                TokenKind::Number(value) if self.eat("*") => {
+                    let coeff = self.element(start, value)?;
+                    LinearCombination::from_signal(&self.named_operand()?).scale(coeff)
+                }
+                TokenKind::Number(value) => {
+                    LinearCombination::constant(self.element(start, value)?)
+                }
+                _ => {
+                    self.pos = start;
+                    LinearCombination::from_signal(&self.named_operand()?)
+                }
+            };
+            lc = if negate { lc.sub(&term) } else { lc.add(&term) };
+            if self.eat("}") {
+                return Ok(Signal::Linear(lc));
+            }
+            let sign = self.pos;
+            negate = match self.next().kind {
+                TokenKind::Punct("+") => false,
+                TokenKind::Punct("-") => true,
+                _ => {
+                    self.pos = sign;
+                    return Err(self.unexpected("`+`, `-` or `}`"));
+                }
+            };
+        }
+    }
+
+    fn named_operand(&mut self) -> Result<Signal, ParseError> {
+        let start = self.pos;
+        match self.operand()? {
+            sig if sig.name().is_some() => Ok(sig),
+            _ => {
+                self.pos = start;
+                Err(self.unexpected("a signal name"))
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        match self.next().kind {
+            TokenKind::Ident(name) if is_identifier(&name) => Ok(name),
+            _ => {
+                self.pos = start;
+                Err(self.unexpected("a name"))
+            }
+        }
+    }
+
+    fn index(&mut self) -> Result<usize, ParseError> {
+        let start = self.pos;
+        match self.next().kind {
+            TokenKind::Number(value) if value <= usize::MAX as u128 => Ok(value as usize),
+            _ => {
+                self.pos = start;
+                Err(self.unexpected("an index"))
+            }
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = matches!(self.peek().kind, TokenKind::Punct(p) if p == punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), ParseError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        self.error_at(
+            self.pos,
+            format!("expected {}, found {}", expected, self.peek().kind),
+        )
+    }
+
+    /// `value` as a constant or coefficient, which like the other formats
+    /// only takes reduced field elements
+    fn element(&self, pos: usize, value: u128) -> Result<u128, ParseError> {
+        if value >= PRIME {
+            return Err(self.error_at(
+                pos,
+                format!(
+                    "{} is not a field element, it must be below {}",
+                    value, PRIME
+                ),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn degree_error(&self, pos: usize) -> ParseError {
+        self.error_at(
+            pos,
+            format!("gates are limited to degree {}", Gate::MAX_DEGREE),
+        )
+    }
+
+    fn terms_error(&self, pos: usize) -> ParseError {
+        self.error_at(
+            pos,
+            format!("gates are limited to {} terms", Gate::MAX_TERMS),
+        )
+    }
+
+    fn error_at(&self, pos: usize, message: String) -> ParseError {
+        ParseError {
+            line: self.tokens[pos].line,
+            column: self.tokens[pos].column,
+            message,
+        }
+    }
+}
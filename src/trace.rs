@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ptr;
+
+use crate::{
+    circuit::Circuit,
+    constraint::Operation,
+    field_element::FieldElement,
+    signal::Signal,
+    text::{constraint_text, operand},
+    witness::Witness,
+    witness_generator::{lookup, WitnessError, WitnessGenerator},
+};
+
+/// One constraint evaluated during witness generation
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    /// Index into `circuit.constraints`
+    pub constraint: usize,
+    /// The value of its output
+    pub value: FieldElement,
+}
+
+/// Everything witness generation computed, step by step, see `WitnessGenerator::trace`
+#[derive(Clone, Debug)]
+pub struct Trace {
+    /// The evaluated constraints, in evaluation order
+    pub steps: Vec<TraceStep>,
+    /// Every signal value known when generation stopped
+    pub values: HashMap<String, FieldElement>,
+    pub failure: Option<Failure>,
+}
+
+/// Why witness generation stopped, with the context to debug it
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub error: WitnessError,
+    /// The text of the failing constraint, e.g. as written in `circuit!`
+    pub source: Option<String>,
+    /// The failing constraint, as `.ghb` text
+    pub constraint: Option<String>,
+    /// Every operand of the failing constraint and its value, if it has one
+    pub operands: Vec<(Signal, Option<FieldElement>)>,
+    /// The steps that computed the operands, and the steps that computed theirs,
+    /// in evaluation order. Rendered with the constraint text.
+    pub chain: Vec<(TraceStep, String)>,
+}
+
+impl WitnessGenerator<'_> {
+    /// Generates the witness like `complete`, recording every step and, if a
+    /// constraint fails, what led to it
+    pub fn trace(&self, known: &Witness) -> Trace {
+        let mut values = known.values.clone();
+        let mut steps = Vec::new();
+        let failure = self
+            .run(&mut values, Some(&mut steps))
+            .err()
+            .map(|error| Failure::new(self.circuit, error, &steps, &values));
+        Trace {
+            steps,
+            values,
+            failure,
+        }
+    }
+}
+
+impl Failure {
+    fn new(
+        circuit: &Circuit,
+        error: WitnessError,
+        steps: &[TraceStep],
+        values: &HashMap<String, FieldElement>,
+    ) -> Self {
+        let Some(index) = error.constraint() else {
+            return Self {
+                error,
+                source: None,
+                constraint: None,
+                operands: Vec::new(),
+                chain: Vec::new(),
+            };
+        };
+        let failing = &circuit.constraints[index];
+
+        // the step that computed each signal
+        let producers: HashMap<&str, usize> = steps
+            .iter()
+            .enumerate()
+            .filter_map(|(k, step)| Some((circuit.constraints[step.constraint].output.name()?, k)))
+            .collect();
+        let mut chain = HashSet::new();
+        let mut todo: Vec<&Signal> = failing.operands();
+        while let Some(sig) = todo.pop() {
+            let Some(&k) = sig.name().and_then(|name| producers.get(name)) else {
+                continue;
+            };
+            if chain.insert(k) {
+                todo.extend(circuit.constraints[steps[k].constraint].operands());
+            }
+        }
+        let mut chain: Vec<usize> = chain.into_iter().collect();
+        chain.sort();
+
+        let mut operands: Vec<(Signal, Option<FieldElement>)> = Vec::new();
+        for sig in failing.operands() {
+            // a mux ignores its right operand
+            let unused =
+                matches!(failing.operation, Operation::Mux(_)) && ptr::eq(sig, &failing.right);
+            if !unused && !operands.iter().any(|(s, _)| same(s, sig)) {
+                operands.push((sig.clone(), lookup(sig, values)));
+            }
+        }
+
+        Self {
+            error,
+            source: circuit.sources.get(&index).cloned(),
+            constraint: Some(constraint_text(failing)),
+            operands,
+            chain: chain
+                .into_iter()
+                .map(|k| {
+                    let step = steps[k].clone();
+                    let text = constraint_text(&circuit.constraints[step.constraint]);
+                    (step, text)
+                })
+                .collect(),
+        }
+    }
+}
+
+fn same(a: &Signal, b: &Signal) -> bool {
+    match (a, b) {
+        (Signal::Constant(x), Signal::Constant(y)) => x == y,
+        _ => a.name().is_some() && a.name() == b.name(),
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(source) = &self.source {
+            write!(f, "\n  source: {}", source)?;
+        }
+        if let Some(constraint) = &self.constraint {
+            write!(f, "\n  constraint: {}", constraint)?;
+        }
+        if !self.operands.is_empty() {
+            write!(f, "\n  operands:")?;
+            for (sig, value) in &self.operands {
+                match value {
+                    Some(value) => write!(f, "\n    {} = {}", operand(sig), value.value)?,
+                    None => write!(f, "\n    {} = ?", operand(sig))?,
+                }
+            }
+        }
+        if !self.chain.is_empty() {
+            write!(f, "\n  computed by:")?;
+            for (step, text) in &self.chain {
+                write!(
+                    f,
+                    "\n    #{} {} = {}",
+                    step.constraint, text, step.value.value
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
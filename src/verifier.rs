@@ -3,10 +3,11 @@ use std::collections::HashMap;
 use crate::{
     circuit::Circuit,
     commitment::Commitment,
-    constraint::{Constraint, Operation},
     field_element::FieldElement,
+    lookup::LookupArgument,
     proof::Proof,
     signal::Signal,
+    witness_generator::{assign, evaluate_constraint, missing_operand},
 };
 
 pub struct Verifier;
@@ -15,8 +16,49 @@ impl Verifier {
     ///  - checks that the commitments match the revealed
     ///  - check constraints
     pub fn verify(circuit: &Circuit, public_inputs: &[FieldElement], proof: &Proof) -> bool {
-        if !Self::check_commitments(&proof.commitments, &proof.revealed_witness) {
+        Self::checked_values(circuit, public_inputs, proof).is_some()
+    }
+
+    /// `verify`, and checks the logUp arguments of `LookupArgument::prove`, one
+    /// per table the circuit uses
+    pub fn verify_lookups(
+        circuit: &Circuit,
+        public_inputs: &[FieldElement],
+        proof: &Proof,
+        arguments: &[LookupArgument],
+    ) -> bool {
+        let Some(values) = Self::checked_values(circuit, public_inputs, proof) else {
             return false;
+        };
+        let tables = circuit.tables();
+        if arguments.len() != tables.len() {
+            eprintln!(
+                "Expected {} lookup arguments, got {}",
+                tables.len(),
+                arguments.len()
+            );
+            return false;
+        }
+        for table in tables {
+            match arguments.iter().find(|a| a.table == table.name) {
+                Some(argument) if argument.verify(circuit, &values) => {}
+                _ => {
+                    eprintln!("Lookup argument for table '{}' failed", table.name);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Every signal's value once the commitments and constraints check out
+    fn checked_values(
+        circuit: &Circuit,
+        public_inputs: &[FieldElement],
+        proof: &Proof,
+    ) -> Option<HashMap<String, FieldElement>> {
+        if !Self::check_commitments(&proof.commitments, &proof.revealed_witness) {
+            return None;
         }
 
         // a local map from name -> FieldElement
@@ -31,15 +73,22 @@ impl Verifier {
             if let Some(pub_val) = public_inputs.get(i) {
                 // If sig is Input("x"), store "x" -> pub_val
                 if let Signal::Input(ref name) = sig {
-                    value_map.insert(name.clone(), pub_val.clone());
+                    value_map.insert(name.to_string(), pub_val.clone());
                 }
             }
         }
-        if !Self::constraints_satisfied(&circuit.constraints, &mut value_map) {
-            return false;
+        let order = match circuit.constraint_order() {
+            Ok(order) => order,
+            Err(cycle) => {
+                eprintln!("Verifier cannot order constraints: {}", cycle);
+                return None;
+            }
+        };
+        if !Self::constraints_satisfied(circuit, &order, &mut value_map) {
+            return None;
         }
 
-        true
+        Some(value_map)
     }
 
     /// For each named witness, reconstruct the commitment and compare
@@ -71,50 +120,34 @@ impl Verifier {
         true
     }
 
-    /// FIXME: This is exactly the same as the logic in Prover::is_satisfied
-    ///        Should we refactor this into a shared function?
+    /// Re-evaluates the constraints in dependency order, with the same rules
+    /// the witness generator used on the prover side
     fn constraints_satisfied(
-        constraints: &[Constraint],
+        circuit: &Circuit,
+        order: &[usize],
         value_map: &mut HashMap<String, FieldElement>,
     ) -> bool {
-        for constraint in constraints {
-            let left_val = Self::eval_signal(&constraint.left, value_map).unwrap();
-            let right_val = Self::eval_signal(&constraint.right, value_map).unwrap();
-
-            let result = match constraint.operation {
-                Operation::Add => left_val.add(&right_val),
-                Operation::Mul => left_val.mul(&right_val),
-                Operation::Sub => left_val.sub(&right_val),
-                Operation::Eq => {
-                    if !left_val.equals(&right_val) {
-                        eprintln!(
-                            "Verifier constraint failed: {:?} != {:?}",
-                            left_val, right_val
-                        );
-                        return false;
-                    }
-                    left_val.clone()
+        for &i in order {
+            let constraint = &circuit.constraints[i];
+            match evaluate_constraint(i, constraint, value_map) {
+                None => {
+                    eprintln!(
+                        "Verifier is missing a value for '{}' in constraint #{}",
+                        missing_operand(constraint, value_map),
+                        i
+                    );
+                    return false;
                 }
-            };
-
-            match &constraint.output {
-                Signal::Output(ref nm) | Signal::Witness(ref nm) => {
-                    value_map.insert(nm.clone(), result);
+                Some(Err(err)) => {
+                    eprintln!("Verifier constraint failed: {}", err);
+                    if let Some(source) = circuit.sources.get(&i) {
+                        eprintln!("  source: {}", source);
+                    }
+                    return false;
                 }
-                _ => {}
+                Some(Ok(result)) => assign(constraint, result, value_map),
             }
         }
         true
     }
-
-    fn eval_signal(
-        signal: &Signal,
-        value_map: &HashMap<String, FieldElement>,
-    ) -> Option<FieldElement> {
-        match signal {
-            Signal::Input(name) => value_map.get(name).cloned(),
-            Signal::Witness(name) => value_map.get(name).cloned(),
-            Signal::Output(name) => value_map.get(name).cloned(),
-        }
-    }
 }
@@ -4,7 +4,12 @@ use crate::field_element::FieldElement;
 
 /// Holds the prover’s secret assignments (mapping from signal names to their actual values)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Witness {
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialization::sorted")
+    )]
     pub values: HashMap<String, FieldElement>,
 }
 
@@ -13,3 +18,35 @@ impl Witness {
         Self { values }
     }
 }
+
+/// Values accepted by `witness!`: a number, or a (nested) array of numbers
+/// assigned element-wise to an array signal
+pub trait WitnessValue {
+    /// `(indices, value)` pairs, row-major
+    fn flatten(self) -> Vec<(Vec<usize>, u128)>;
+}
+
+impl WitnessValue for u128 {
+    fn flatten(self) -> Vec<(Vec<usize>, u128)> {
+        vec![(Vec::new(), self)]
+    }
+}
+
+impl<T: WitnessValue, const N: usize> WitnessValue for [T; N] {
+    fn flatten(self) -> Vec<(Vec<usize>, u128)> {
+        self.into_iter().collect::<Vec<T>>().flatten()
+    }
+}
+
+impl<T: WitnessValue> WitnessValue for Vec<T> {
+    fn flatten(self) -> Vec<(Vec<usize>, u128)> {
+        let mut values = Vec::new();
+        for (i, element) in self.into_iter().enumerate() {
+            for (mut indices, value) in element.flatten() {
+                indices.insert(0, i);
+                values.push((indices, value));
+            }
+        }
+        values
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{
+    circuit::{bounds_message, exceeded_shape, Circuit},
+    constraint::{Constraint, Operation},
+    dependency::DependencyCycle,
+    field_element::FieldElement,
+    hint::Hint,
+    signal::{Signal, SignalName},
+    trace::TraceStep,
+    witness::Witness,
+    PRIME,
+};
+
+/// Why a witness could not be generated or checked
+#[derive(Clone, Debug)]
+pub enum WitnessError {
+    /// The constraints cannot be ordered
+    Cycle(DependencyCycle),
+    /// The number of public values does not match `circuit.inputs`
+    PublicInputCount { expected: usize, got: usize },
+    /// The signal indexes a declared array out of bounds
+    OutOfBounds {
+        constraint: usize,
+        signal: String,
+        shape: Vec<usize>,
+    },
+    /// Neither the user, a hint nor another constraint provides the signal
+    MissingValue { constraint: usize, signal: String },
+    /// An equality does not hold, or an assigned value disagrees with the computed one
+    ConstraintFailed {
+        constraint: usize,
+        left: FieldElement,
+        right: FieldElement,
+    },
+    /// The condition of a `Select` is neither 0 nor 1
+    NotBoolean {
+        constraint: usize,
+        value: FieldElement,
+    },
+    /// The index of a `Mux` does not select one of its options
+    IndexOutOfRange {
+        constraint: usize,
+        index: FieldElement,
+        options: usize,
+    },
+    /// A hint returned a different number of values than it declares
+    HintOutputCount { outputs: Vec<String>, got: usize },
+    /// The values of a lookup are not a row of its table
+    NotInTable {
+        constraint: usize,
+        table: String,
+        values: Vec<FieldElement>,
+    },
+}
+
+impl WitnessError {
+    /// The index of the constraint the error is about, if any
+    pub fn constraint(&self) -> Option<usize> {
+        match self {
+            WitnessError::OutOfBounds { constraint, .. }
+            | WitnessError::MissingValue { constraint, .. }
+            | WitnessError::ConstraintFailed { constraint, .. }
+            | WitnessError::NotBoolean { constraint, .. }
+            | WitnessError::IndexOutOfRange { constraint, .. }
+            | WitnessError::NotInTable { constraint, .. } => Some(*constraint),
+            WitnessError::Cycle(_)
+            | WitnessError::PublicInputCount { .. }
+            | WitnessError::HintOutputCount { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::Cycle(cycle) => write!(f, "{}", cycle),
+            WitnessError::PublicInputCount { expected, got } => {
+                write!(f, "expected {} public inputs, got {}", expected, got)
+            }
+            WitnessError::OutOfBounds {
+                constraint,
+                signal,
+                shape,
+            } => write!(
+                f,
+                "{} (constraint #{})",
+                bounds_message(&SignalName::from(signal.as_str()), shape),
+                constraint
+            ),
+            WitnessError::MissingValue { constraint, signal } => write!(
+                f,
+                "Missing witness value for signal '{}' used by constraint #{}",
+                signal, constraint
+            ),
+            WitnessError::ConstraintFailed {
+                constraint,
+                left,
+                right,
+            } => write!(
+                f,
+                "Constraint equation failed! constraint #{}: {} != {}",
+                constraint, left.value, right.value
+            ),
+            WitnessError::NotBoolean { constraint, value } => write!(
+                f,
+                "Select condition of constraint #{} must be 0 or 1, got {}",
+                constraint, value.value
+            ),
+            WitnessError::IndexOutOfRange {
+                constraint,
+                index,
+                options,
+            } => write!(
+                f,
+                "Mux index of constraint #{} is {} but there are only {} options",
+                constraint, index.value, options
+            ),
+            WitnessError::HintOutputCount { outputs, got } => write!(
+                f,
+                "hint for {:?} returned {} values, expected {}",
+                outputs,
+                got,
+                outputs.len()
+            ),
+            WitnessError::NotInTable {
+                constraint,
+                table,
+                values,
+            } => write!(
+                f,
+                "({}) of constraint #{} is not in table `{}`",
+                values
+                    .iter()
+                    .map(|v| v.value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                constraint,
+                table
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+/// Computes a complete witness (intermediate and output values included) from the
+/// values the user knows, independently of any proving backend.
+#[derive(Clone, Debug)]
+pub struct WitnessGenerator<'a> {
+    pub(crate) circuit: &'a Circuit,
+    hints: Vec<Hint>,
+}
+
+impl<'a> WitnessGenerator<'a> {
+    pub fn new(circuit: &'a Circuit) -> Self {
+        Self {
+            circuit,
+            hints: Vec::new(),
+        }
+    }
+
+    /// Registers a hint computing `outputs` from `inputs`, on top of the hints
+    /// attached to the circuit
+    pub fn with_hint<F>(mut self, outputs: &[&str], inputs: &[&str], function: F) -> Self
+    where
+        F: Fn(&[FieldElement]) -> Vec<FieldElement> + Send + Sync + 'static,
+    {
+        self.hints.push(Hint::new(outputs, inputs, function));
+        self
+    }
+
+    /// Generates a witness from the public inputs (ordered like `circuit.inputs`)
+    /// and the private values
+    pub fn generate(
+        &self,
+        public_inputs: &[FieldElement],
+        private: &Witness,
+    ) -> Result<Witness, WitnessError> {
+        if public_inputs.len() != self.circuit.inputs.len() {
+            return Err(WitnessError::PublicInputCount {
+                expected: self.circuit.inputs.len(),
+                got: public_inputs.len(),
+            });
+        }
+
+        let mut values = private.values.clone();
+        for (sig, value) in self.circuit.inputs.iter().zip(public_inputs) {
+            if let Some(name) = sig.name() {
+                values.insert(name.to_string(), value.clone());
+            }
+        }
+        self.complete(&Witness::new(values))
+    }
+
+    /// Fills in every value the circuit computes, starting from named values
+    /// that already include the public inputs
+    pub fn complete(&self, known: &Witness) -> Result<Witness, WitnessError> {
+        let mut values = known.values.clone();
+        self.run(&mut values, None)?;
+        Ok(Witness::new(values))
+    }
+
+    /// Evaluates the constraints into `values`, recording each one in `steps`
+    /// when tracing
+    pub(crate) fn run(
+        &self,
+        values: &mut HashMap<String, FieldElement>,
+        mut steps: Option<&mut Vec<TraceStep>>,
+    ) -> Result<(), WitnessError> {
+        let order = self
+            .circuit
+            .constraint_order()
+            .map_err(WitnessError::Cycle)?;
+        self.circuit.check_bounds()?;
+
+        let mut pending: Vec<usize> = order;
+        loop {
+            self.run_hints(values)?;
+
+            let before = pending.len();
+            let mut still_pending = Vec::new();
+            for i in pending {
+                let constraint = &self.circuit.constraints[i];
+                match evaluate_constraint(i, constraint, values) {
+                    Some(result) => {
+                        let value = result?;
+                        if let Some(steps) = steps.as_deref_mut() {
+                            steps.push(TraceStep {
+                                constraint: i,
+                                value: value.clone(),
+                            });
+                        }
+                        assign(constraint, value, values);
+                    }
+                    None => still_pending.push(i),
+                }
+            }
+            pending = still_pending;
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if pending.len() == before {
+                let i = pending[0];
+                let constraint = &self.circuit.constraints[i];
+                return Err(WitnessError::MissingValue {
+                    constraint: i,
+                    signal: missing_operand(constraint, values),
+                });
+            }
+        }
+    }
+
+    /// Runs every hint whose inputs are known and whose outputs are not
+    fn run_hints(&self, values: &mut HashMap<String, FieldElement>) -> Result<(), WitnessError> {
+        loop {
+            let mut progressed = false;
+            for hint in self.circuit.hints.iter().chain(&self.hints) {
+                if hint.outputs.iter().all(|o| values.contains_key(o)) {
+                    continue;
+                }
+                let Some(args) = hint
+                    .inputs
+                    .iter()
+                    .map(|i| values.get(i).cloned())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+
+                let results = (hint.function)(&args);
+                if results.len() != hint.outputs.len() {
+                    return Err(WitnessError::HintOutputCount {
+                        outputs: hint.outputs.clone(),
+                        got: results.len(),
+                    });
+                }
+                for (name, value) in hint.outputs.iter().zip(results) {
+                    values.entry(name.clone()).or_insert(value);
+                }
+                progressed = true;
+            }
+            if !progressed {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Circuit {
+    /// Checks every indexed signal of the constraints against the shape of
+    /// the declared arrays
+    fn check_bounds(&self) -> Result<(), WitnessError> {
+        let shapes = self.shapes();
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            for sig in constraint
+                .operands()
+                .into_iter()
+                .chain([&constraint.output])
+            {
+                if let Some(shape) = exceeded_shape(&shapes, sig) {
+                    return Err(WitnessError::OutOfBounds {
+                        constraint: i,
+                        signal: sig.name().unwrap_or_default().to_string(),
+                        shape: shape.to_vec(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a complete witness satisfies every constraint, including that
+    /// computed signals hold the values the constraints compute
+    pub fn check_witness(&self, witness: &Witness) -> Result<(), WitnessError> {
+        self.check_bounds()?;
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            let result =
+                evaluate_constraint(i, constraint, &witness.values).ok_or_else(|| {
+                    WitnessError::MissingValue {
+                        constraint: i,
+                        signal: missing_operand(constraint, &witness.values),
+                    }
+                })??;
+
+            if let Signal::Output(name) | Signal::Witness(name) = &constraint.output {
+                match witness.values.get(name.as_str()) {
+                    Some(assigned) if assigned.equals(&result) => {}
+                    Some(assigned) => {
+                        return Err(WitnessError::ConstraintFailed {
+                            constraint: i,
+                            left: result,
+                            right: assigned.clone(),
+                        })
+                    }
+                    None => {
+                        return Err(WitnessError::MissingValue {
+                            constraint: i,
+                            signal: name.to_string(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value of a signal: constants evaluate to themselves, named signals are looked up
+pub(crate) fn lookup(
+    signal: &Signal,
+    values: &HashMap<String, FieldElement>,
+) -> Option<FieldElement> {
+    match signal {
+        Signal::Constant(value) => Some(FieldElement::new(*value, PRIME)),
+        Signal::Linear(lc) => lc.evaluate(values),
+        named => values.get(named.name()?).cloned(),
+    }
+}
+
+/// Name of the first operand of `constraint` without a value
+pub(crate) fn missing_operand(
+    constraint: &Constraint,
+    values: &HashMap<String, FieldElement>,
+) -> String {
+    constraint
+        .operands()
+        .into_iter()
+        .find(|s| lookup(s, values).is_none())
+        .and_then(|s| s.name())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// `None` while an operand is unknown, otherwise the value of the output.
+/// Shared by witness generation and the verifier.
+pub(crate) fn evaluate_constraint(
+    index: usize,
+    constraint: &Constraint,
+    values: &HashMap<String, FieldElement>,
+) -> Option<Result<FieldElement, WitnessError>> {
+    let left_val = lookup(&constraint.left, values)?;
+    let right_val = lookup(&constraint.right, values)?;
+
+    let result = match &constraint.operation {
+        Operation::Add => Ok(left_val.add(&right_val)),
+        Operation::Mul => Ok(left_val.mul(&right_val)),
+        Operation::Sub => Ok(left_val.sub(&right_val)),
+        Operation::Eq => {
+            if left_val.equals(&right_val) {
+                Ok(left_val)
+            } else {
+                Err(WitnessError::ConstraintFailed {
+                    constraint: index,
+                    left: left_val,
+                    right: right_val,
+                })
+            }
+        }
+        Operation::Select(cond) => {
+            let cond_val = lookup(cond, values)?;
+            match cond_val.value {
+                1 => Ok(left_val),
+                0 => Ok(right_val),
+                _ => Err(WitnessError::NotBoolean {
+                    constraint: index,
+                    value: cond_val,
+                }),
+            }
+        }
+        Operation::Mux(options) => {
+            let option_vals = options
+                .iter()
+                .map(|o| lookup(o, values))
+                .collect::<Option<Vec<_>>>()?;
+            if left_val.value < option_vals.len() as u128 {
+                Ok(option_vals[left_val.value as usize].clone())
+            } else {
+                Err(WitnessError::IndexOutOfRange {
+                    constraint: index,
+                    index: left_val,
+                    options: options.len(),
+                })
+            }
+        }
+        Operation::Custom(gate, inputs) => {
+            let input_vals = inputs
+                .iter()
+                .map(|i| lookup(i, values))
+                .collect::<Option<Vec<_>>>()?;
+            Ok(gate.evaluate(&input_vals))
+        }
+        Operation::Lookup(table, inputs) => {
+            let input_vals = inputs
+                .iter()
+                .map(|i| lookup(i, values))
+                .collect::<Option<Vec<_>>>()?;
+            let keys: Vec<u128> = input_vals.iter().map(|v| v.value).collect();
+            let found = if keys.len() == table.width() {
+                table.position(&keys).map(|_| 0)
+            } else {
+                table.lookup(&keys)
+            };
+            found
+                .map(|v| FieldElement::new(v, PRIME))
+                .ok_or(WitnessError::NotInTable {
+                    constraint: index,
+                    table: table.name.clone(),
+                    values: input_vals,
+                })
+        }
+    };
+
+    // an output without a name is not assigned but must match
+    if let Signal::Constant(_) | Signal::Linear(_) = &constraint.output {
+        let expected = lookup(&constraint.output, values)?;
+        if let Ok(value) = &result {
+            if !value.equals(&expected) {
+                return Some(Err(WitnessError::ConstraintFailed {
+                    constraint: index,
+                    left: value.clone(),
+                    right: expected,
+                }));
+            }
+        }
+    }
+    Some(result)
+}
+
+pub(crate) fn assign(
+    constraint: &Constraint,
+    result: FieldElement,
+    values: &mut HashMap<String, FieldElement>,
+) {
+    if let Signal::Output(name) | Signal::Witness(name) = &constraint.output {
+        values.insert(name.to_string(), result);
+    }
+}